@@ -0,0 +1,104 @@
+//! Temporary address override
+
+use crate::{config::Config, Address, Error, Frame, Pca9685};
+use core::ops::{Deref, DerefMut};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Driver communicating with another address until dropped
+///
+/// This gives access to all methods of the driver. When dropped, the
+/// address of the driver is restored, together with the cached mode
+/// register, prescale and output values, so that they keep describing the
+/// device at the main address.
+///
+/// Created with [`at_address()`](Pca9685::at_address).
+#[derive(Debug)]
+pub struct AtAddress<'a, I2C> {
+    pwm: &'a mut Pca9685<I2C>,
+    address: u8,
+    config: Config,
+    prescale: u8,
+    outputs: Frame,
+}
+
+impl<I2C> Deref for AtAddress<'_, I2C> {
+    type Target = Pca9685<I2C>;
+
+    fn deref(&self) -> &Self::Target {
+        self.pwm
+    }
+}
+
+impl<I2C> DerefMut for AtAddress<'_, I2C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.pwm
+    }
+}
+
+impl<I2C> Drop for AtAddress<'_, I2C> {
+    fn drop(&mut self) {
+        self.pwm.address = self.address;
+        self.pwm.config = self.config;
+        self.pwm.prescale = self.prescale;
+        self.pwm.outputs = self.outputs;
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Communicate with another address until the returned view is dropped.
+    ///
+    /// This allows a single driver to occasionally write to a second device
+    /// or to a broadcast address like the `LED All Call` address, without
+    /// switching permanently with [`set_address()`](Pca9685::set_address).
+    ///
+    /// Operations depending on the cached mode register and output values
+    /// start from those of the device at the main address. This is fine for
+    /// devices configured the same way.
+    ///
+    /// ```no_run
+    /// use linux_embedded_hal::I2cdev;
+    /// use pwm_pca9685::{Address, Channel, Pca9685};
+    ///
+    /// let dev = I2cdev::new("/dev/i2c-1").unwrap();
+    /// let mut pwm = Pca9685::new(dev, Address::default()).unwrap();
+    /// // Switch channel 0 off on all devices on the bus
+    /// pwm.at_address(0x70).unwrap().set_channel_full_off(Channel::C0).unwrap();
+    /// ```
+    ///
+    /// Returns `Error::InvalidInputData` if the address is the general call
+    /// address, a reserved address or not a 7-bit address.
+    pub fn at_address<A: Into<Address>>(
+        &mut self,
+        address: A,
+    ) -> Result<AtAddress<'_, I2C>, Error<E>> {
+        const HIGH_SPEED_MODE: u8 = 0b00_0111;
+        let a = address.into();
+        if a.0 > 0x7F || a.0 <= HIGH_SPEED_MODE {
+            return Err(Error::InvalidInputData);
+        }
+        let main_address = self.address;
+        self.address = a.0;
+        Ok(AtAddress {
+            address: main_address,
+            config: self.config,
+            prescale: self.prescale,
+            outputs: self.outputs,
+            pwm: self,
+        })
+    }
+}
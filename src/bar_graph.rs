@@ -0,0 +1,144 @@
+//! LED bar graph
+
+use crate::{channels::OutputKind, types::CHANNELS, Channel, ChannelOnOffControl, Error, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Bar graph boundary channel style
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BarGraphStyle {
+    /// Channels are either full-on or full-off (default).
+    #[default]
+    Stepped,
+    /// The channel at the boundary of the bar is dimmed proportionally to
+    /// the remaining level.
+    Fractional,
+}
+
+/// Compute the control values of a bar graph of `count` channels for a
+/// `level` in the range `[0-4095]`.
+fn bar_graph(
+    count: usize,
+    level: u16,
+    style: BarGraphStyle,
+) -> impl Iterator<Item = ChannelOnOffControl> {
+    let total = u32::from(level).saturating_mul(count as u32);
+    let lit = (total / 4095) as usize;
+    let remainder = (total % 4095) as u16;
+    (0..count).map(move |i| {
+        if i < lit {
+            ChannelOnOffControl {
+                full_on: true,
+                ..Default::default()
+            }
+        } else if i == lit && remainder != 0 && style == BarGraphStyle::Fractional {
+            ChannelOnOffControl {
+                off: remainder,
+                ..Default::default()
+            }
+        } else {
+            ChannelOnOffControl {
+                full_off: true,
+                ..Default::default()
+            }
+        }
+    })
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Display a level as a bar graph on `count` consecutive channels
+    /// starting at `start`.
+    ///
+    /// The `level` is in the range `[0-4095]`, where 4095 lights all channels.
+    /// Lit channels are set to full-on and the rest to full-off. With
+    /// `BarGraphStyle::Fractional`, the first channel which is not fully lit
+    /// is dimmed according to the remaining level.
+    ///
+    /// All channels are written in a single I2C transaction.
+    ///
+    /// Returns `Error::InvalidInputData` for `Channel::All`, if the channels do
+    /// not fit in the range `[0-15]` or if the level is greater than 4095.
+    pub async fn set_bar_graph(
+        &mut self,
+        start: Channel,
+        count: u8,
+        level: u16,
+        style: BarGraphStyle,
+    ) -> Result<(), Error<E>> {
+        let first = start.index().ok_or(Error::InvalidInputData)?;
+        let count = usize::from(count);
+        if level > 4095 || count == 0 || first.saturating_add(count) > 16 {
+            return Err(Error::InvalidInputData);
+        }
+        let mut values = [ChannelOnOffControl::default(); 16];
+        for ((slot, channel), value) in values
+            .iter_mut()
+            .zip(CHANNELS.iter().skip(first))
+            .zip(bar_graph(count, level, style))
+        {
+            self.check_channel_mode(*channel, OutputKind::of(&value))?;
+            *slot = value;
+        }
+        let values = values.get(..count).ok_or(Error::Internal)?;
+        self.write_channels(first, values).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lit(values: impl Iterator<Item = ChannelOnOffControl>) -> [u16; 4] {
+        let mut result = [0; 4];
+        for (r, v) in result.iter_mut().zip(values) {
+            *r = if v.full_on {
+                4096
+            } else if v.full_off {
+                0
+            } else {
+                v.off
+            };
+        }
+        result
+    }
+
+    #[test]
+    fn empty_and_full_bar() {
+        assert_eq!([0; 4], lit(bar_graph(4, 0, BarGraphStyle::Fractional)));
+        assert_eq!(
+            [4096; 4],
+            lit(bar_graph(4, 4095, BarGraphStyle::Fractional))
+        );
+    }
+
+    #[test]
+    fn stepped_bar_has_no_partial_channel() {
+        assert_eq!(
+            [4096, 0, 0, 0],
+            lit(bar_graph(4, 2000, BarGraphStyle::Stepped))
+        );
+    }
+
+    #[test]
+    fn fractional_bar_dims_boundary_channel() {
+        // 2000 * 4 = 8000 = 4095 + 3905
+        assert_eq!(
+            [4096, 3905, 0, 0],
+            lit(bar_graph(4, 2000, BarGraphStyle::Fractional))
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//! Numeric blink codes on a status channel
+
+use crate::{Channel, ChannelOnOffControl, Error, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Blink code timing
+///
+/// A blink code is played as `count` blinks of `on_ms` milliseconds separated
+/// by `off_ms` milliseconds, followed by a pause of `pause_ms` milliseconds.
+/// The sequence repeats until it is stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlinkCode {
+    /// Number of blinks.
+    pub count: u8,
+    /// Time the channel is on for each blink in milliseconds.
+    pub on_ms: u32,
+    /// Time the channel is off between blinks in milliseconds.
+    pub off_ms: u32,
+    /// Time the channel is off after the last blink in milliseconds.
+    pub pause_ms: u32,
+}
+
+impl BlinkCode {
+    /// Create a blink code with 200 ms blinks, 300 ms between blinks and a
+    /// 1.5 s pause.
+    pub fn new(count: u8) -> Self {
+        BlinkCode {
+            count,
+            on_ms: 200,
+            off_ms: 300,
+            pause_ms: 1500,
+        }
+    }
+
+    /// Duration of a blink and the gap following it.
+    fn blink_period_ms(&self) -> Option<u32> {
+        self.on_ms.checked_add(self.off_ms)
+    }
+
+    /// Duration of the whole sequence including the pause.
+    fn period_ms(&self) -> Option<u32> {
+        self.blink_period_ms()?
+            .checked_mul(u32::from(self.count))?
+            .checked_add(self.pause_ms)
+    }
+
+    /// Returns whether the channel is lit `elapsed_ms` milliseconds after the
+    /// start of the sequence.
+    fn is_lit_at(&self, elapsed_ms: u32) -> bool {
+        let (Some(blink_period), Some(period)) = (self.blink_period_ms(), self.period_ms()) else {
+            return false;
+        };
+        let Some(position) = elapsed_ms.checked_rem(period) else {
+            return false;
+        };
+        let blinks_ms = period.saturating_sub(self.pause_ms);
+        position < blinks_ms && position.checked_rem(blink_period).unwrap_or(0) < self.on_ms
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BlinkCodeState {
+    index: usize,
+    code: BlinkCode,
+    start_ms: u32,
+    duty: ChannelOnOffControl,
+    original: ChannelOnOffControl,
+    lit: bool,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Start playing a blink code on a status channel.
+    ///
+    /// The channel alternates between its current duty cycle and full-off on
+    /// each call to [`tick()`](Pca9685::tick). If the channel is currently
+    /// full-off, it is set to full-on while lit. The sequence starts at
+    /// `now_ms` with the first blink. Only one blink code can be played at a
+    /// time. Starting a new one stops the previous one without restoring its
+    /// channel.
+    ///
+    /// Nothing is written to the device by this method. The blink code stops
+    /// when the failsafe timeout is triggered.
+    ///
+    /// Returns `Error::InvalidInputData` if the channel is `Channel::All`, if
+    /// `count` or `on_ms` are 0 or if the sequence is longer than `u32::MAX`
+    /// milliseconds.
+    pub fn start_blink_code(
+        &mut self,
+        channel: Channel,
+        code: BlinkCode,
+        now_ms: u32,
+    ) -> Result<(), Error<E>> {
+        let index = channel.index().ok_or(Error::InvalidInputData)?;
+        if code.count == 0 || code.on_ms == 0 || code.period_ms().is_none() {
+            return Err(Error::InvalidInputData);
+        }
+        let original = self.outputs.get(channel).ok_or(Error::InvalidInputData)?;
+        let duty = if original.full_off {
+            ChannelOnOffControl::full_on()
+        } else {
+            original
+        };
+        self.blink_code = Some(BlinkCodeState {
+            index,
+            code,
+            start_ms: now_ms,
+            duty,
+            original,
+            lit: !original.full_off,
+        });
+        Ok(())
+    }
+
+    /// Stop playing the blink code and restore the control values its
+    /// channel had when it started.
+    pub async fn stop_blink_code(&mut self) -> Result<(), Error<E>> {
+        if let Some(state) = self.blink_code.take() {
+            if self.outputs.channels().get(state.index) != Some(&state.original) {
+                self.write_channels(state.index, &[state.original]).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether a blink code is being played.
+    pub fn is_blink_code_active(&self) -> bool {
+        self.blink_code.is_some()
+    }
+
+    pub(crate) async fn service_blink_code(&mut self, now_ms: u32) -> Result<(), Error<E>> {
+        let Some(state) = self.blink_code else {
+            return Ok(());
+        };
+        let lit = state.code.is_lit_at(now_ms.wrapping_sub(state.start_ms));
+        if lit != state.lit {
+            let value = if lit {
+                state.duty
+            } else {
+                ChannelOnOffControl::full_off()
+            };
+            self.write_channels(state.index, &[value]).await?;
+            if let Some(state) = self.blink_code.as_mut() {
+                state.lit = lit;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plays_blinks_then_pause() {
+        let code = BlinkCode {
+            count: 2,
+            on_ms: 100,
+            off_ms: 200,
+            pause_ms: 1000,
+        };
+        let lit: [(u32, bool); 8] = [
+            (0, true),
+            (99, true),
+            (100, false),
+            (300, true),
+            (400, false),
+            (599, false),
+            (1599, false),
+            (1600, true),
+        ];
+        for (elapsed, expected) in lit {
+            assert_eq!(expected, code.is_lit_at(elapsed), "at {elapsed} ms");
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Channel map presets for popular PCA9685 boards
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use pwm_pca9685::{boards::adafruit_motor_hat, DcMotor, MotorCommand, Pca9685};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut pwm = Pca9685::new(dev, adafruit_motor_hat::ADDRESS).unwrap();
+//! pwm.enable().unwrap();
+//!
+//! let mut motor = DcMotor::motor1_on_motor_hat(&mut pwm);
+//! motor.set_speed(2047).unwrap();
+//! motor.run(MotorCommand::Forward).unwrap();
+//! ```
+
+use crate::Channel;
+
+/// Channels connected to an H-bridge driving a DC motor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DcMotorPins {
+    /// Speed (PWM) input
+    pub pwm: Channel,
+    /// Direction input 1
+    pub in1: Channel,
+    /// Direction input 2
+    pub in2: Channel,
+}
+
+/// Channels connected to two H-bridges driving the coils of a stepper motor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StepperPins {
+    /// Coil A
+    pub coil_a: DcMotorPins,
+    /// Coil B
+    pub coil_b: DcMotorPins,
+}
+
+/// Channels connected to an RGB LED
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RgbPins {
+    /// Red LED
+    pub red: Channel,
+    /// Green LED
+    pub green: Channel,
+    /// Blue LED
+    pub blue: Channel,
+}
+
+/// Adafruit 16-Channel PWM/Servo HAT and Bonnet for Raspberry Pi
+///
+/// Servo outputs 0-15 are connected to channels `C0`-`C15`.
+pub mod adafruit_servo_hat {
+    /// Default I2C address.
+    pub const ADDRESS: u8 = 0x40;
+}
+
+/// Adafruit DC & Stepper Motor HAT for Raspberry Pi
+///
+/// The motor outputs are driven by two TB6612 H-bridges.
+pub mod adafruit_motor_hat {
+    use super::{DcMotorPins, StepperPins};
+    use crate::Channel;
+
+    /// Default I2C address.
+    pub const ADDRESS: u8 = 0x60;
+
+    /// Motor output M1.
+    pub const MOTOR1: DcMotorPins = DcMotorPins {
+        pwm: Channel::C8,
+        in1: Channel::C10,
+        in2: Channel::C9,
+    };
+
+    /// Motor output M2.
+    pub const MOTOR2: DcMotorPins = DcMotorPins {
+        pwm: Channel::C13,
+        in1: Channel::C11,
+        in2: Channel::C12,
+    };
+
+    /// Motor output M3.
+    pub const MOTOR3: DcMotorPins = DcMotorPins {
+        pwm: Channel::C2,
+        in1: Channel::C4,
+        in2: Channel::C3,
+    };
+
+    /// Motor output M4.
+    pub const MOTOR4: DcMotorPins = DcMotorPins {
+        pwm: Channel::C7,
+        in1: Channel::C5,
+        in2: Channel::C6,
+    };
+
+    /// Stepper output 1 (coils on M1 and M2).
+    pub const STEPPER1: StepperPins = StepperPins {
+        coil_a: MOTOR1,
+        coil_b: MOTOR2,
+    };
+
+    /// Stepper output 2 (coils on M3 and M4).
+    pub const STEPPER2: StepperPins = StepperPins {
+        coil_a: MOTOR3,
+        coil_b: MOTOR4,
+    };
+}
+
+/// Generic RGB LED boards with the LEDs connected to consecutive channels
+/// in red, green, blue order
+pub mod rgb_consecutive {
+    use super::RgbPins;
+    use crate::Channel;
+
+    /// RGB LEDs 0-4 on channels `C0`-`C14`. Channel `C15` is unused.
+    pub const LEDS: [RgbPins; 5] = [
+        RgbPins {
+            red: Channel::C0,
+            green: Channel::C1,
+            blue: Channel::C2,
+        },
+        RgbPins {
+            red: Channel::C3,
+            green: Channel::C4,
+            blue: Channel::C5,
+        },
+        RgbPins {
+            red: Channel::C6,
+            green: Channel::C7,
+            blue: Channel::C8,
+        },
+        RgbPins {
+            red: Channel::C9,
+            green: Channel::C10,
+            blue: Channel::C11,
+        },
+        RgbPins {
+            red: Channel::C12,
+            green: Channel::C13,
+            blue: Channel::C14,
+        },
+    ];
+}
@@ -0,0 +1,158 @@
+//! Perceptual brightness curves
+
+use crate::{Channel, ChannelOnOffControl, Duty, Error, IntoDuty, Pca9685, Rounding};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Curve mapping a brightness level to a duty cycle
+///
+/// The human eye perceives brightness nonlinearly, so equally spaced duty
+/// cycles do not look equally spaced. The perceptual curves compensate for
+/// this. They are stored as tables so no computation is done at runtime.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BrightnessCurve {
+    /// Duty cycle proportional to the level (default).
+    #[default]
+    Linear,
+    /// CIE 1931 lightness.
+    Cie1931,
+    /// sRGB transfer function.
+    Srgb,
+}
+
+impl BrightnessCurve {
+    /// Duty cycle for a brightness level in the range `[0-255]`.
+    pub fn duty(self, level: u8) -> Duty {
+        let duty = match self {
+            BrightnessCurve::Linear => level.into_duty(Rounding::Nearest),
+            BrightnessCurve::Cie1931 => Duty::new(table_value(&CIE1931, level)),
+            BrightnessCurve::Srgb => Duty::new(table_value(&SRGB, level)),
+        };
+        duty.unwrap_or_default()
+    }
+}
+
+fn table_value(table: &[u16; 256], level: u8) -> u16 {
+    table.get(usize::from(level)).copied().unwrap_or(0)
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Set the curve applied by [`set_channel_brightness()`](Pca9685::set_channel_brightness).
+    ///
+    /// This does not have any effect on the hardware.
+    pub fn set_brightness_curve(&mut self, curve: BrightnessCurve) {
+        self.brightness_curve = curve;
+    }
+
+    /// Set the brightness of a channel in the range `[0-255]`.
+    ///
+    /// The level is converted into a duty cycle with the selected brightness
+    /// curve. See: [`set_brightness_curve()`](Pca9685::set_brightness_curve).
+    /// A level of 0 sets the channel to full-off.
+    pub async fn set_channel_brightness(
+        &mut self,
+        channel: Channel,
+        level: u8,
+    ) -> Result<(), Error<E>> {
+        let duty = self.brightness_curve.duty(level);
+        self.set_channel_duty(channel, duty).await
+    }
+
+    /// Set the brightness of all channels from levels in the range `[0-255]`.
+    ///
+    /// The index of the level in the array corresponds to the channel: 0-15.
+    /// The levels are converted with the selected brightness curve, which
+    /// applies gamma correction with the perceptual curves. A level of 0 sets
+    /// the channel to full-off. All channels are written in a single
+    /// transaction like with
+    /// [`set_all_channels()`](Pca9685::set_all_channels).
+    pub async fn set_all_from_u8(&mut self, levels: &[u8; 16]) -> Result<(), Error<E>> {
+        let mut values = [ChannelOnOffControl::default(); 16];
+        for (value, level) in values.iter_mut().zip(levels) {
+            *value = ChannelOnOffControl::from(self.brightness_curve.duty(*level));
+        }
+        self.set_all_channels(&values).await
+    }
+}
+
+/// CIE 1931 lightness scaled to `[0-4095]`.
+const CIE1931: [u16; 256] = [
+    0, 2, 4, 5, 7, 9, 11, 12, 14, 16, 18, 20, 21, 23, 25, 27, 28, 30, 32, 34, 36, 37, 39, 41, 43,
+    45, 47, 49, 52, 54, 56, 59, 61, 64, 66, 69, 72, 75, 77, 80, 83, 87, 90, 93, 96, 100, 103, 107,
+    111, 115, 118, 122, 126, 131, 135, 139, 144, 148, 153, 157, 162, 167, 172, 177, 182, 187, 193,
+    198, 204, 209, 215, 221, 227, 233, 239, 246, 252, 259, 265, 272, 279, 286, 293, 300, 308, 315,
+    323, 330, 338, 346, 354, 362, 371, 379, 388, 396, 405, 414, 423, 432, 442, 451, 461, 470, 480,
+    490, 501, 511, 521, 532, 543, 553, 564, 576, 587, 598, 610, 622, 634, 646, 658, 670, 683, 695,
+    708, 721, 734, 748, 761, 775, 788, 802, 816, 831, 845, 860, 874, 889, 904, 920, 935, 951, 966,
+    982, 999, 1015, 1031, 1048, 1065, 1082, 1099, 1116, 1134, 1152, 1170, 1188, 1206, 1224, 1243,
+    1262, 1281, 1300, 1320, 1339, 1359, 1379, 1399, 1420, 1440, 1461, 1482, 1503, 1525, 1546, 1568,
+    1590, 1612, 1635, 1657, 1680, 1703, 1726, 1750, 1774, 1797, 1822, 1846, 1870, 1895, 1920, 1945,
+    1971, 1996, 2022, 2048, 2074, 2101, 2128, 2155, 2182, 2209, 2237, 2265, 2293, 2321, 2350, 2378,
+    2407, 2437, 2466, 2496, 2526, 2556, 2587, 2617, 2648, 2679, 2711, 2743, 2774, 2807, 2839, 2872,
+    2905, 2938, 2971, 3005, 3039, 3073, 3107, 3142, 3177, 3212, 3248, 3283, 3319, 3356, 3392, 3429,
+    3466, 3503, 3541, 3578, 3617, 3655, 3694, 3732, 3772, 3811, 3851, 3891, 3931, 3972, 4012, 4054,
+    4095,
+];
+
+/// sRGB transfer function scaled to `[0-4095]`.
+const SRGB: [u16; 256] = [
+    0, 1, 2, 4, 5, 6, 7, 9, 10, 11, 12, 14, 15, 16, 18, 20, 21, 23, 25, 27, 29, 31, 33, 35, 37, 40,
+    42, 45, 48, 50, 53, 56, 59, 62, 66, 69, 72, 76, 79, 83, 87, 91, 95, 99, 103, 107, 112, 116,
+    121, 126, 131, 136, 141, 146, 151, 156, 162, 168, 173, 179, 185, 191, 197, 204, 210, 216, 223,
+    230, 237, 244, 251, 258, 265, 273, 280, 288, 296, 304, 312, 320, 329, 337, 346, 354, 363, 372,
+    381, 390, 400, 409, 419, 428, 438, 448, 458, 469, 479, 490, 500, 511, 522, 533, 544, 555, 567,
+    578, 590, 602, 614, 626, 639, 651, 664, 676, 689, 702, 715, 728, 742, 755, 769, 783, 797, 811,
+    825, 840, 854, 869, 884, 899, 914, 929, 945, 960, 976, 992, 1008, 1024, 1041, 1057, 1074, 1091,
+    1108, 1125, 1142, 1159, 1177, 1195, 1213, 1231, 1249, 1267, 1286, 1304, 1323, 1342, 1361, 1381,
+    1400, 1420, 1440, 1459, 1480, 1500, 1520, 1541, 1562, 1582, 1603, 1625, 1646, 1668, 1689, 1711,
+    1733, 1755, 1778, 1800, 1823, 1846, 1869, 1892, 1916, 1939, 1963, 1987, 2011, 2035, 2059, 2084,
+    2109, 2133, 2159, 2184, 2209, 2235, 2260, 2286, 2312, 2339, 2365, 2392, 2419, 2446, 2473, 2500,
+    2527, 2555, 2583, 2611, 2639, 2668, 2696, 2725, 2754, 2783, 2812, 2841, 2871, 2901, 2931, 2961,
+    2991, 3022, 3052, 3083, 3114, 3146, 3177, 3209, 3240, 3272, 3304, 3337, 3369, 3402, 3435, 3468,
+    3501, 3535, 3568, 3602, 3636, 3670, 3705, 3739, 3774, 3809, 3844, 3879, 3915, 3950, 3986, 4022,
+    4059, 4095,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves_cover_full_range() {
+        for curve in [
+            BrightnessCurve::Linear,
+            BrightnessCurve::Cie1931,
+            BrightnessCurve::Srgb,
+        ] {
+            assert_eq!(0, curve.duty(0).count());
+            assert_eq!(4095, curve.duty(255).count());
+        }
+    }
+
+    #[test]
+    fn perceptual_curves_are_below_linear() {
+        assert_eq!(2056, BrightnessCurve::Linear.duty(128).count());
+        assert_eq!(761, BrightnessCurve::Cie1931.duty(128).count());
+        assert_eq!(884, BrightnessCurve::Srgb.duty(128).count());
+    }
+
+    #[test]
+    fn tables_are_monotonic() {
+        assert!(CIE1931.windows(2).all(|w| w[0] <= w[1]));
+        assert!(SRGB.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
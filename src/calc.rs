@@ -0,0 +1,157 @@
+//! Prescale and frequency calculations
+//!
+//! These functions work with any oscillator frequency, for example an
+//! external clock or a measured internal oscillator. All of them use the same
+//! rounding, so the achieved frequency reported for a prescale value is the
+//! one the device will really output for the given oscillator frequency.
+//!
+//! ```
+//! use pwm_pca9685::calc::prescale_for_frequency;
+//!
+//! let (prescale, achieved_mhz) = prescale_for_frequency(25_000_000, 1000).unwrap();
+//! assert_eq!(5, prescale);
+//! // 1017.253 Hz
+//! assert_eq!(1_017_253, achieved_mhz);
+//! ```
+
+/// Number of counter steps per PWM period.
+const STEPS: u32 = 4096;
+
+/// Counter steps per PWM period multiplied by microseconds per second.
+const STEP_US_DEN: u64 = STEPS as u64 * 1_000_000;
+
+/// Find the prescale value for a target PWM frequency and the frequency
+/// achieved with it in millihertz, rounded to the nearest integer.
+///
+/// The prescale value is calculated as
+/// `round(osc_hz / (4096 * target_hz)) - 1`. The achieved frequency shows
+/// the quantization error, for example 50 Hz results in 50.029 Hz.
+///
+/// Returns `None` if the target frequency is 0 or if the resulting prescale
+/// value is out of the range `[3-255]`.
+pub const fn prescale_for_frequency(osc_hz: u32, target_hz: u32) -> Option<(u8, u32)> {
+    let Some(rounded) = div_nearest(osc_hz, target_hz.saturating_mul(STEPS)) else {
+        return None;
+    };
+    let prescale = rounded.saturating_sub(1);
+    if prescale < 3 || prescale > 255 {
+        return None;
+    }
+    match frequency_mhz_for_prescale(osc_hz, prescale as u8) {
+        Some(achieved_mhz) => Some((prescale as u8, achieved_mhz)),
+        None => None,
+    }
+}
+
+/// Find the prescale value for a target PWM period in microseconds.
+///
+/// The prescale value is calculated as
+/// `round(period_us * osc_hz / (4096 * 1 s)) - 1`.
+///
+/// Returns `None` if the resulting prescale value is out of the range
+/// `[3-255]`.
+pub const fn prescale_for_period_us(osc_hz: u32, period_us: u32) -> Option<u8> {
+    let ticks = (period_us as u64).saturating_mul(osc_hz as u64);
+    let Some(rounded) = ticks
+        .saturating_add(STEP_US_DEN / 2)
+        .checked_div(STEP_US_DEN)
+    else {
+        return None;
+    };
+    let prescale = rounded.saturating_sub(1);
+    if prescale < 3 || prescale > 255 {
+        return None;
+    }
+    Some(prescale as u8)
+}
+
+/// PWM frequency in Hz obtained with a prescale value, rounded to the
+/// nearest integer.
+///
+/// The frequency is calculated as `osc_hz / (4096 * (prescale + 1))`.
+///
+/// Returns `None` if the prescale value is lower than 3.
+pub const fn frequency_for_prescale(osc_hz: u32, prescale: u8) -> Option<u32> {
+    if prescale < 3 {
+        return None;
+    }
+    div_nearest(
+        osc_hz,
+        (prescale as u32).saturating_add(1).saturating_mul(STEPS),
+    )
+}
+
+/// PWM frequency in millihertz obtained with a prescale value, rounded to
+/// the nearest integer.
+///
+/// The frequency is calculated as `1000 * osc_hz / (4096 * (prescale + 1))`.
+///
+/// Returns `None` if the prescale value is lower than 3.
+pub const fn frequency_mhz_for_prescale(osc_hz: u32, prescale: u8) -> Option<u32> {
+    if prescale < 3 {
+        return None;
+    }
+    let den = (prescale as u64)
+        .saturating_add(1)
+        .saturating_mul(STEPS as u64);
+    match (osc_hz as u64)
+        .saturating_mul(1000)
+        .saturating_add(den / 2)
+        .checked_div(den)
+    {
+        Some(mhz) if mhz <= u32::MAX as u64 => Some(mhz as u32),
+        _ => None,
+    }
+}
+
+/// Divide rounding to the nearest integer. Returns `None` if `den` is 0.
+const fn div_nearest(num: u32, den: u32) -> Option<u32> {
+    match (num as u64)
+        .saturating_add(den as u64 / 2)
+        .checked_div(den as u64)
+    {
+        Some(quotient) => Some(quotient as u32),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_find_prescale() {
+        assert_eq!(Some((121, 50_029)), prescale_for_frequency(25_000_000, 50));
+        assert_eq!(
+            Some((3, 1_525_879)),
+            prescale_for_frequency(25_000_000, 1526)
+        );
+        assert_eq!(Some((253, 24_030)), prescale_for_frequency(25_000_000, 24));
+        assert_eq!(Some((97, 49_825)), prescale_for_frequency(20_000_000, 50));
+    }
+
+    #[test]
+    fn reports_quantization_error() {
+        // 200 Hz cannot be reached exactly: prescale 30 gives 196.888 Hz.
+        assert_eq!(Some((30, 196_888)), prescale_for_frequency(25_000_000, 200));
+        assert_eq!(Some(196_888), frequency_mhz_for_prescale(25_000_000, 30));
+        assert_eq!(Some(197), frequency_for_prescale(25_000_000, 30));
+        assert_eq!(None, frequency_mhz_for_prescale(25_000_000, 2));
+    }
+
+    #[test]
+    fn can_find_prescale_for_period() {
+        assert_eq!(Some(121), prescale_for_period_us(25_000_000, 20_000));
+        assert_eq!(Some(97), prescale_for_period_us(20_000_000, 20_000));
+        assert_eq!(None, prescale_for_period_us(25_000_000, 500));
+        assert_eq!(None, prescale_for_period_us(25_000_000, 50_000));
+    }
+
+    #[test]
+    fn rejects_out_of_range_frequencies() {
+        assert_eq!(None, prescale_for_frequency(25_000_000, 0));
+        assert_eq!(None, prescale_for_frequency(25_000_000, 23));
+        assert_eq!(None, prescale_for_frequency(25_000_000, 2000));
+        assert_eq!(None, frequency_for_prescale(25_000_000, 2));
+    }
+}
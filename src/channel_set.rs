@@ -0,0 +1,226 @@
+//! Operations on subsets of channels
+
+use crate::{
+    channels::OutputKind, limits::Limited, Channel, ChannelOnOffControl, Duty, Error, IntoDuty,
+    Pca9685,
+};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Set of channels stored as a bitmask
+///
+/// Bit `n` selects channel `n`.
+///
+/// ```
+/// use pwm_pca9685::{Channel, ChannelSet};
+///
+/// let set = ChannelSet::EMPTY.with(Channel::C1).with(Channel::C4);
+/// assert_eq!(0b1_0010, set.bits());
+/// assert!(set.contains(Channel::C4));
+/// assert_eq!(2, set.len());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChannelSet(u16);
+
+impl ChannelSet {
+    /// Set without any channel.
+    pub const EMPTY: ChannelSet = ChannelSet(0);
+    /// Set with all 16 channels.
+    pub const ALL: ChannelSet = ChannelSet(0xFFFF);
+
+    /// Create a set from a bitmask where bit `n` selects channel `n`.
+    pub const fn from_bits(bits: u16) -> Self {
+        ChannelSet(bits)
+    }
+
+    /// Get the bitmask where bit `n` selects channel `n`.
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Get the set with a channel added.
+    ///
+    /// `Channel::All` adds every channel.
+    pub fn with(mut self, channel: Channel) -> Self {
+        self.insert(channel);
+        self
+    }
+
+    /// Add a channel to the set.
+    ///
+    /// `Channel::All` adds every channel.
+    pub fn insert(&mut self, channel: Channel) {
+        self.0 |= mask(channel);
+    }
+
+    /// Remove a channel from the set.
+    ///
+    /// `Channel::All` removes every channel.
+    pub fn remove(&mut self, channel: Channel) {
+        self.0 &= !mask(channel);
+    }
+
+    /// Returns whether the set contains a channel.
+    ///
+    /// For `Channel::All`, returns whether the set contains every channel.
+    pub fn contains(self, channel: Channel) -> bool {
+        self.0 & mask(channel) == mask(channel)
+    }
+
+    /// Returns whether the set does not contain any channel.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Number of channels in the set.
+    pub const fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Iterate over the channels in the set in ascending order.
+    pub fn iter(self) -> impl Iterator<Item = Channel> {
+        (0..16_u16)
+            .filter(move |i| self.0 & (1 << i) != 0)
+            .filter_map(|i| Channel::try_from(i).ok())
+    }
+
+    /// Index of the lowest and highest channel in the set.
+    fn span(self) -> Option<(usize, usize)> {
+        if self.is_empty() {
+            return None;
+        }
+        let first = self.0.trailing_zeros() as usize;
+        let last = 15_usize.saturating_sub(self.0.leading_zeros() as usize);
+        Some((first, last))
+    }
+}
+
+fn mask(channel: Channel) -> u16 {
+    match channel.index() {
+        Some(i) => 1 << i,
+        None => 0xFFFF,
+    }
+}
+
+impl From<Channel> for ChannelSet {
+    fn from(channel: Channel) -> Self {
+        ChannelSet::EMPTY.with(channel)
+    }
+}
+
+impl FromIterator<Channel> for ChannelSet {
+    fn from_iter<T: IntoIterator<Item = Channel>>(iter: T) -> Self {
+        iter.into_iter().fold(ChannelSet::EMPTY, ChannelSet::with)
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Set all channels in a set to full-off.
+    ///
+    /// The channels from the lowest to the highest one in the set are
+    /// written in a single transaction. Channels in between which are not in
+    /// the set are written with the values last written by this driver.
+    /// Nothing is written for an empty set.
+    pub async fn set_channels_full_off(&mut self, set: ChannelSet) -> Result<(), Error<E>> {
+        let mut values = *self.outputs.channels();
+        for channel in set.iter() {
+            let slot = channel
+                .index()
+                .and_then(|i| values.get_mut(i))
+                .ok_or(Error::InvalidInputData)?;
+            *slot = ChannelOnOffControl::full_off();
+        }
+        self.write_channel_set(set, &values).await
+    }
+
+    /// Set the duty cycle of all channels in a set.
+    ///
+    /// The duty cycle is converted and limited as with
+    /// [`set_channel_duty()`](Pca9685::set_channel_duty). The channels from
+    /// the lowest to the highest one in the set are written in a single
+    /// transaction. Channels in between which are not in the set are written
+    /// with the values last written by this driver. Nothing is written for an
+    /// empty set.
+    ///
+    /// Returns `Error::InvalidInputData` if the value cannot be converted.
+    pub async fn set_channels_duty(
+        &mut self,
+        set: ChannelSet,
+        duty: impl IntoDuty,
+    ) -> Result<(), Error<E>> {
+        let duty = self.convert_duty(duty)?;
+        let counts = match self.apply_limits(Channel::All, duty.count()) {
+            Limited::Skip => [None; 16],
+            Limited::Uniform(count) => [Some(count); 16],
+            Limited::PerChannel(counts) => counts,
+        };
+        let mut values = *self.outputs.channels();
+        for channel in set.iter() {
+            let index = channel.index().ok_or(Error::InvalidInputData)?;
+            let slot = values.get_mut(index).ok_or(Error::InvalidInputData)?;
+            if let Some(count) = counts.get(index).copied().flatten() {
+                *slot = match count {
+                    4095 if self.promote_full_on_off => ChannelOnOffControl::full_on(),
+                    _ => ChannelOnOffControl::from(Duty::new(count).unwrap_or_default()),
+                };
+            }
+        }
+        self.write_channel_set(set, &values).await
+    }
+
+    /// Write the span of channels covered by a set.
+    async fn write_channel_set(
+        &mut self,
+        set: ChannelSet,
+        values: &[ChannelOnOffControl; 16],
+    ) -> Result<(), Error<E>> {
+        let Some((first, last)) = set.span() else {
+            return Ok(());
+        };
+        for channel in set.iter() {
+            let value = channel
+                .index()
+                .and_then(|i| values.get(i))
+                .ok_or(Error::InvalidInputData)?;
+            self.check_channel_mode(channel, OutputKind::of(value))?;
+        }
+        let values = values.get(first..=last).ok_or(Error::InvalidInputData)?;
+        self.write_channels(first, values).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_build_sets() {
+        let set: ChannelSet = [Channel::C0, Channel::C15].into_iter().collect();
+        assert_eq!(0x8001, set.bits());
+        assert_eq!(Some((0, 15)), set.span());
+        assert!(!set.contains(Channel::All));
+        assert!(ChannelSet::from(Channel::All).contains(Channel::All));
+        let mut set = ChannelSet::ALL;
+        set.remove(Channel::C3);
+        assert_eq!(15, set.len());
+        assert_eq!(None, ChannelSet::EMPTY.span());
+        assert_eq!(
+            Some(Channel::C4),
+            ChannelSet::from_bits(0b11_0000).iter().next()
+        );
+    }
+}
@@ -1,4 +1,10 @@
-use crate::{types::ChannelOnOffControl, Channel, Error, Pca9685, Register};
+use crate::{
+    config::BitFlagMode2,
+    consts::{FULL_OFF_BIT, FULL_ON_BIT},
+    regs,
+    types::{ChannelOnOffControl, CHANNELS},
+    Channel, ChannelMode, Error, Frame, Pca9685, Priority, UpdateOrdering,
+};
 
 #[cfg(not(feature = "async"))]
 use embedded_hal::i2c::I2c;
@@ -6,7 +12,7 @@ use embedded_hal::i2c::I2c;
 use embedded_hal_async::i2c::I2c as AsyncI2c;
 
 macro_rules! imp_set_channels {
-    ($([$method_name:ident, $array_size:expr, $buffer_size:expr]),+) => {
+    ($([$method_name:ident, $array_size:expr]),+) => {
     #[maybe_async_cfg::maybe(
         sync(
             cfg(not(feature = "async")),
@@ -34,7 +40,7 @@ macro_rules! imp_set_channels {
                 on: &[u16; $array_size],
                 off: &[u16; $array_size],
             ) -> Result<(), Error<E>> {
-                self.set_channels_on_off::<$array_size, $buffer_size>(on, off).await
+                self.set_channels_on_off(on, off).await
             }
         )+
     }
@@ -42,10 +48,10 @@ macro_rules! imp_set_channels {
 }
 
 imp_set_channels!(
-    [set_all_on_off, 16, 65],
-    [set_first_4_channels_on_off, 4, 17],
-    [set_first_8_channels_on_off, 8, 33],
-    [set_first_12_channels_on_off, 12, 49]
+    [set_all_on_off, 16],
+    [set_first_4_channels_on_off, 4],
+    [set_first_8_channels_on_off, 8],
+    [set_first_12_channels_on_off, 12]
 );
 
 #[maybe_async_cfg::maybe(
@@ -66,20 +72,16 @@ where
     /// See section 7.3.3 "LED output and PWM control" of the datasheet for
     /// further details.
     pub async fn set_channel_on(&mut self, channel: Channel, value: u16) -> Result<(), Error<E>> {
-        if value > 4095 {
-            return Err(Error::InvalidInputData);
-        }
-        let reg = get_register_on(channel);
-        self.write_double_register(reg, value).await
+        let value = self.check_count(value)?;
+        self.check_channel_mode(channel, OutputKind::Counters)?;
+        self.write_channel(channel, ChannelWrite::On(value)).await
     }
 
     /// Set the `OFF` counter for the selected channel.
     pub async fn set_channel_off(&mut self, channel: Channel, value: u16) -> Result<(), Error<E>> {
-        if value > 4095 {
-            return Err(Error::InvalidInputData);
-        }
-        let reg = get_register_off(channel);
-        self.write_double_register(reg, value).await
+        let value = self.check_count(value)?;
+        self.check_channel_mode(channel, OutputKind::Counters)?;
+        self.write_channel(channel, ChannelWrite::Off(value)).await
     }
 
     /// Set the `ON` and `OFF` counters for the selected channel.
@@ -93,11 +95,41 @@ where
         on: u16,
         off: u16,
     ) -> Result<(), Error<E>> {
-        if on > 4095 || off > 4095 {
-            return Err(Error::InvalidInputData);
-        }
-        let reg = get_register_on(channel);
-        self.write_two_double_registers(reg, on, off).await
+        let on = self.check_count(on)?;
+        let off = self.check_count(off)?;
+        self.check_channel_mode(channel, OutputKind::Counters)?;
+        self.write_channel(channel, ChannelWrite::OnOff(on, off))
+            .await
+    }
+
+    /// Set the duty cycle of the selected channel through its `OFF` counter
+    /// keeping the `ON` counter at 0.
+    ///
+    /// If the driver last wrote an `ON` counter of 0 without the full-on bit
+    /// for this channel, only the `OFF` registers are written, halving the size
+    /// of the transaction. Otherwise, the `ON` counter is set to 0 as well.
+    ///
+    /// Note that the driver cannot know about changes to the `ON` registers
+    /// done outside of this driver.
+    pub async fn set_channel_duty_off_only(
+        &mut self,
+        channel: Channel,
+        off: u16,
+    ) -> Result<(), Error<E>> {
+        let off = self.check_count(off)?;
+        self.check_channel_mode(channel, OutputKind::Counters)?;
+        let on_is_zero = |c: &ChannelOnOffControl| c.on == 0 && !c.full_on;
+        let cached = self.outputs.channels();
+        let on_is_zero = match channel.index() {
+            Some(i) => cached.get(i).is_some_and(on_is_zero),
+            None => cached.iter().all(on_is_zero),
+        };
+        let write = if on_is_zero {
+            ChannelWrite::Off(off)
+        } else {
+            ChannelWrite::OnOff(0, off)
+        };
+        self.write_channel(channel, write).await
     }
 
     /// Set the channel always on.
@@ -112,25 +144,129 @@ where
         channel: Channel,
         value: u16,
     ) -> Result<(), Error<E>> {
-        if value > 4095 {
-            return Err(Error::InvalidInputData);
-        }
-        let reg = get_register_on(channel);
-        let value = value | 0b0001_0000_0000_0000;
-        self.write_double_register(reg, value).await
+        let value = self.check_count(value)?;
+        self.check_channel_mode(channel, OutputKind::FullOn)?;
+        let value = value | FULL_ON_BIT;
+        self.write_channel(channel, ChannelWrite::On(value)).await
     }
 
     /// Set the channel always off.
     ///
     /// This takes precedence over the `on` settings and can be cleared by setting
     /// the `off` counter with [`set_channel_off`](struct.Pca9685.html#method.set_channel_off).
+    /// The `off` counter last written by this driver is kept, so that
+    /// [`toggle_channel()`](Pca9685::toggle_channel) can restore it.
     ///
     /// See section 7.3.3 "LED output and PWM control" of the datasheet for
     /// further details.
     pub async fn set_channel_full_off(&mut self, channel: Channel) -> Result<(), Error<E>> {
-        let reg = get_register_off(channel);
-        let value = 0b0001_0000_0000_0000;
-        self.write_double_register(reg, value).await
+        self.check_channel_mode(channel, OutputKind::FullOff)?;
+        let off = self.outputs.get(channel).map_or(0, |value| value.off);
+        self.write_channel(channel, ChannelWrite::Off(off | FULL_OFF_BIT))
+            .await
+    }
+
+    /// Set all channels always off in a single transaction.
+    ///
+    /// Only the four `ALL_LED` registers are written, which also clears the
+    /// `on` and `off` counters and the full-on bit of every channel. This is
+    /// useful to blank all outputs, for example between animation frames or
+    /// on a safety shutdown.
+    pub async fn set_all_full_off(&mut self) -> Result<(), Error<E>> {
+        self.check_channel_mode(Channel::All, OutputKind::FullOff)?;
+        self.write_all_channels(&[ChannelOnOffControl::full_off(); 16])
+            .await
+    }
+
+    /// Toggle a channel between full-off and its last configuration.
+    ///
+    /// Only the full-off bit in the `OFF_H` register is changed, so that the
+    /// `on` and `off` counters and the full-on bit last written by this
+    /// driver are restored when the channel is toggled on again, also after
+    /// [`set_channel_full_off()`](Pca9685::set_channel_full_off).
+    ///
+    /// Returns whether the channel is now off.
+    ///
+    /// Returns `Error::InvalidInputData` for `Channel::All`.
+    pub async fn toggle_channel(&mut self, channel: Channel) -> Result<bool, Error<E>> {
+        let mut value = self.outputs.get(channel).ok_or(Error::InvalidInputData)?;
+        value.full_off = !value.full_off;
+        self.check_channel_mode(channel, OutputKind::of(&value))?;
+        let off = if value.full_off {
+            value.off | FULL_OFF_BIT
+        } else {
+            value.off
+        };
+        self.write_channel(channel, ChannelWrite::Off(off)).await?;
+        Ok(value.full_off)
+    }
+
+    /// Write all PWM control registers of a channel.
+    pub(crate) async fn set_channel_value(
+        &mut self,
+        channel: Channel,
+        value: ChannelOnOffControl,
+    ) -> Result<(), Error<E>> {
+        if value.on > 4095 || value.off > 4095 {
+            return Err(Error::InvalidInputData);
+        }
+        self.check_channel_mode(channel, OutputKind::of(&value))?;
+        let (on, off) = register_values(&value);
+        self.write_channel(channel, ChannelWrite::OnOff(on, off))
+            .await
+    }
+
+    /// Modify the PWM control values of a channel with a closure.
+    ///
+    /// The closure receives the values last written to the channel by this
+    /// driver. The registers are only written if the closure changed them.
+    ///
+    /// Returns `Error::InvalidInputData` for `Channel::All` or if the
+    /// modified values are out of range.
+    ///
+    /// ```no_run
+    /// use linux_embedded_hal::I2cdev;
+    /// use pwm_pca9685::{Address, Channel, Pca9685};
+    ///
+    /// let dev = I2cdev::new("/dev/i2c-1").unwrap();
+    /// let mut pwm = Pca9685::new(dev, Address::default()).unwrap();
+    /// // Increase the duty cycle by 5%
+    /// pwm.update_channel(Channel::C0, |value| {
+    ///     value.off = value.off.saturating_add(205).min(4095);
+    ///     value.full_off = false;
+    /// })
+    /// .unwrap();
+    /// ```
+    pub async fn update_channel(
+        &mut self,
+        channel: Channel,
+        f: impl FnOnce(&mut ChannelOnOffControl),
+    ) -> Result<(), Error<E>> {
+        let current = self.outputs.get(channel).ok_or(Error::InvalidInputData)?;
+        let mut value = current;
+        f(&mut value);
+        if value == current {
+            return Ok(());
+        }
+        self.set_channel_value(channel, value).await
+    }
+
+    /// Write all PWM control values of a channel and get the values
+    /// previously written to it by this driver.
+    ///
+    /// This is useful for toggles, undo or change detection without keeping
+    /// a separate copy of the state.
+    ///
+    /// Returns `Error::InvalidInputData` for `Channel::All` or if the values
+    /// are out of range.
+    pub async fn replace_channel(
+        &mut self,
+        channel: Channel,
+        value: ChannelOnOffControl,
+    ) -> Result<ChannelOnOffControl, Error<E>> {
+        let previous = self.outputs.get(channel).ok_or(Error::InvalidInputData)?;
+        self.set_channel_value(channel, value).await?;
+        Ok(previous)
     }
 
     /// Set the PWM control registers for each channel at once.
@@ -139,54 +275,612 @@ where
     /// full-on and full-off bit in a single I2C transaction.
     /// The index of the value in the array corresponds to the channel: 0-15.
     ///
+    /// If all channels get the same values, only the `ALL_LED` registers are
+    /// written, which reduces the size of the transaction from 65 to 5 bytes.
+    ///
     /// See section 7.3.3 "LED output and PWM control" of the datasheet for
     /// further details.
     pub async fn set_all_channels(
         &mut self,
         values: &[ChannelOnOffControl; 16],
     ) -> Result<(), Error<E>> {
-        const FULL_ON_OFF: u8 = 0b0001_0000;
-        let mut data = [0; 65];
-        data[0] = Register::C0_ON_L;
-        for (i, channel_value) in values.iter().enumerate() {
-            if channel_value.on > 4095 || channel_value.off > 4095 {
-                return Err(Error::InvalidInputData);
+        for (channel, value) in CHANNELS.iter().zip(values) {
+            self.check_channel_mode(*channel, OutputKind::of(value))?;
+        }
+        self.write_all_channels(values).await
+    }
+
+    /// Read the PWM control registers of all channels.
+    ///
+    /// All 64 registers are read in a single transaction. This can be used
+    /// for diagnostics or to find out the state of the outputs after the
+    /// device was configured by other software.
+    ///
+    /// This does not update the values cached by this driver. See
+    /// [`sync_outputs_from_device()`](Pca9685::sync_outputs_from_device).
+    pub async fn read_all_channels(&mut self) -> Result<[ChannelOnOffControl; 16], Error<E>> {
+        let mut data = [0; 64];
+        self.read_register_block(regs::C0_ON_L, &mut data).await?;
+        let mut values = [ChannelOnOffControl::default(); 16];
+        for (value, chunk) in values.iter_mut().zip(data.chunks_exact(4)) {
+            if let [on_l, on_h, off_l, off_h] = *chunk {
+                *value = ChannelOnOffControl::decode([on_l, on_h, off_l, off_h]);
             }
-            data[i * 4 + 1] = channel_value.on as u8;
-            data[i * 4 + 2] =
-                (channel_value.on >> 8) as u8 | (FULL_ON_OFF * channel_value.full_on as u8);
-            data[i * 4 + 3] = channel_value.off as u8;
-            data[i * 4 + 4] =
-                (channel_value.off >> 8) as u8 | (FULL_ON_OFF * channel_value.full_off as u8);
-        }
-        self.enable_auto_increment().await?;
-        self.i2c
-            .write(self.address, &data)
-            .await
-            .map_err(Error::I2C)
+        }
+        Ok(values)
+    }
+
+    /// Read the PWM control registers of all channels and replace the values
+    /// cached by this driver with their contents.
+    ///
+    /// This seeds the cache used by methods like
+    /// [`toggle_channel()`](Pca9685::toggle_channel) and
+    /// [`snapshot()`](Pca9685::snapshot) after power-up or after the channels
+    /// were configured by other software, without writing to the channels.
+    /// See [`read_all_channels()`](Pca9685::read_all_channels).
+    pub async fn sync_outputs_from_device(&mut self) -> Result<(), Error<E>> {
+        let values = self.read_all_channels().await?;
+        self.outputs = Frame::new(values);
+        Ok(())
+    }
+
+    /// Write a frame to all channels.
+    ///
+    /// All channels are written in a single 65-byte transaction, or a 5-byte
+    /// transaction to the `ALL_LED` registers if all channels get the same
+    /// values. This is equivalent to
+    /// [`set_all_channels()`](Pca9685::set_all_channels).
+    pub async fn write_frame(&mut self, frame: &Frame) -> Result<(), Error<E>> {
+        self.set_all_channels(frame.channels()).await
+    }
+
+    /// Write only the channels which differ between two frames.
+    ///
+    /// Each run of consecutive changed channels is written in its own
+    /// transaction, so that unchanged channels are never transferred. `old`
+    /// should be the frame last written to the device, for example the
+    /// previous frame of an animation. Nothing is written if the frames are
+    /// equal.
+    pub async fn write_frame_diff(&mut self, old: &Frame, new: &Frame) -> Result<(), Error<E>> {
+        let mut run: Option<usize> = None;
+        for ((channel, old_value), new_value) in
+            CHANNELS.iter().zip(old.channels()).zip(new.channels())
+        {
+            if old_value != new_value {
+                self.check_channel_mode(*channel, OutputKind::of(new_value))?;
+            }
+        }
+        // Past the last channel both are `None`, which ends the last run.
+        for i in 0..=16 {
+            let changed = old.channels().get(i) != new.channels().get(i);
+            match (run, changed) {
+                (None, true) => run = Some(i),
+                (Some(first), false) => {
+                    let values = new.channels().get(first..i).ok_or(Error::Internal)?;
+                    self.write_channels(first, values).await?;
+                    run = None;
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a frame to all channels latching each channel as soon as its four
+    /// PWM control registers have been written.
+    ///
+    /// With the default output change behavior, all outputs change at the
+    /// I2C STOP condition at the end of the transaction. This method instead
+    /// selects the outputs to change on ACK for the duration of the write.
+    /// The registers are written in auto-increment order, so that each channel
+    /// is updated atomically after its `OFF_H` register is acknowledged, while
+    /// the following channels are still being transferred.
+    ///
+    /// If the outputs were configured to change on STOP, this configuration is
+    /// restored afterwards, also if writing the frame fails. This takes up to
+    /// two additional transactions.
+    pub async fn commit_on_ack(&mut self, frame: &Frame) -> Result<(), Error<E>> {
+        let previous = self.config;
+        if previous.is_low(BitFlagMode2::Och) {
+            self.write_mode2(previous.with_high(BitFlagMode2::Och))
+                .await?;
+        }
+        let result = self.set_all_channels(frame.channels()).await;
+        if previous.is_low(BitFlagMode2::Och) {
+            // Report the frame write error rather than the restore error.
+            let restored = self.write_mode2(previous).await;
+            return result.and(restored);
+        }
+        result
+    }
+
+    /// Write the PWM control registers for each channel without checking
+    /// the channel modes.
+    pub(crate) async fn write_all_channels(
+        &mut self,
+        values: &[ChannelOnOffControl; 16],
+    ) -> Result<(), Error<E>> {
+        self.write_channels(0, values).await
+    }
+
+    /// Write the PWM control registers of consecutive channels starting at
+    /// the channel with index `first` without checking the channel modes.
+    ///
+    /// If all 16 channels get the same values, only the `ALL_LED` registers
+    /// are written.
+    ///
+    /// The values are written in a single transaction unless the update
+    /// ordering requires routing some channels through full-off first.
+    pub(crate) async fn write_channels(
+        &mut self,
+        first: usize,
+        values: &[ChannelOnOffControl],
+    ) -> Result<(), Error<E>> {
+        if first.saturating_add(values.len()) > CHANNELS.len()
+            || values.iter().any(|v| v.on > 4095 || v.off > 4095)
+        {
+            return Err(Error::InvalidInputData);
+        }
+        let cached = self.outputs.channels().iter().skip(first);
+        if self.update_ordering == UpdateOrdering::GlitchFree
+            && cached
+                .clone()
+                .zip(values)
+                .any(|(old, new)| glitch_risk(old, new))
+        {
+            let mut intermediate = [ChannelOnOffControl::default(); 16];
+            for ((slot, old), new) in intermediate.iter_mut().zip(cached).zip(values) {
+                *slot = *new;
+                slot.full_off |= glitch_risk(old, new);
+            }
+            let intermediate = intermediate.get(..values.len()).ok_or(Error::Internal)?;
+            self.write_channels_raw(first, intermediate).await?;
+        }
+        self.write_channels_raw(first, values).await?;
+        let mut outputs = self.outputs;
+        for (channel, value) in CHANNELS.iter().skip(first).zip(values) {
+            outputs.set(*channel, *value);
+        }
+        self.commit_outputs(outputs);
+        self.mark_updated(first, values.len());
+        Ok(())
+    }
+
+    async fn write_channels_raw(
+        &mut self,
+        first: usize,
+        values: &[ChannelOnOffControl],
+    ) -> Result<(), Error<E>> {
+        // Nothing can be written past the last channel.
+        let Some(channel) = CHANNELS.get(first) else {
+            return Ok(());
+        };
+        let register = get_register_on(*channel);
+        if let [value, rest @ ..] = values {
+            if first == 0 && rest.len() == 15 && rest.iter().all(|v| v == value) {
+                let [on_l, on_h, off_l, off_h] = value.encode();
+                return self
+                    .write_register_block(&[regs::ALL_C_ON_L, on_l, on_h, off_l, off_h])
+                    .await;
+            }
+        }
+        let mut register = register;
+        for values in values.chunks(self.max_write_channels.max(1)) {
+            let mut data = [0; 65];
+            let [reg, payload @ ..] = &mut data;
+            *reg = register;
+            for (chunk, channel_value) in payload.chunks_exact_mut(4).zip(values) {
+                chunk.copy_from_slice(&channel_value.encode());
+            }
+            let len = values.len().saturating_mul(4).saturating_add(1);
+            let data = data.get(..len).ok_or(Error::Internal)?;
+            self.write_register_block(data).await?;
+            register = register.wrapping_add(len.saturating_sub(1) as u8);
+        }
+        Ok(())
+    }
+
+    /// Write some PWM control registers of a channel and update the cached
+    /// output state.
+    async fn write_channel(
+        &mut self,
+        channel: Channel,
+        write: ChannelWrite,
+    ) -> Result<(), Error<E>> {
+        let mut outputs = self.outputs;
+        outputs.update(channel, |c| write.apply(c));
+        let risk = self
+            .outputs
+            .channels()
+            .iter()
+            .zip(outputs.channels())
+            .any(|(old, new)| glitch_risk(old, new));
+        if self.update_ordering == UpdateOrdering::GlitchFree && risk {
+            let reg = get_register_off(channel);
+            self.write_double_register(reg, FULL_OFF_BIT).await?;
+            match channel.index() {
+                Some(i) => {
+                    let value = outputs.channels().get(i).copied().unwrap_or_default();
+                    let (on, off) = register_values(&value);
+                    let reg = get_register_on(channel);
+                    self.write_two_double_registers(reg, on, off).await?;
+                }
+                None => self.write_channels_raw(0, outputs.channels()).await?,
+            }
+        } else {
+            match write {
+                ChannelWrite::On(value) => {
+                    self.write_double_register(get_register_on(channel), value)
+                        .await?
+                }
+                ChannelWrite::Off(value) => {
+                    self.write_double_register(get_register_off(channel), value)
+                        .await?
+                }
+                ChannelWrite::OnOff(on, off) => {
+                    self.write_two_double_registers(get_register_on(channel), on, off)
+                        .await?
+                }
+            }
+        }
+        self.commit_outputs(outputs);
+        match channel.index() {
+            Some(i) => self.mark_updated(i, 1),
+            None => self.mark_updated(0, 16),
+        }
+        Ok(())
     }
 
-    async fn set_channels_on_off<const N: usize, const M: usize>(
+    async fn set_channels_on_off<const N: usize>(
         &mut self,
         on: &[u16; N],
         off: &[u16; N],
     ) -> Result<(), Error<E>> {
-        let mut data = [0; M];
-        data[0] = Register::C0_ON_L;
-        for (i, (on, off)) in on.iter().zip(off).enumerate() {
-            if *on > 4095 || *off > 4095 {
+        self.set_channels_on_off_from_iter(Channel::C0, on.iter().copied().zip(off.iter().copied()))
+            .await
+    }
+
+    /// Set the `ON` and `OFF` counters of consecutive channels starting at
+    /// `first` from slices.
+    ///
+    /// All channels are written in a single I2C transaction.
+    ///
+    /// Returns `Error::InvalidInputData` for `Channel::All`, if the slices
+    /// have different lengths or are empty, if the channels do not fit in the
+    /// range `[0-15]` or if any value is greater than 4095.
+    pub async fn set_channels_on_off_from_slices(
+        &mut self,
+        first: Channel,
+        on: &[u16],
+        off: &[u16],
+    ) -> Result<(), Error<E>> {
+        if on.len() != off.len() {
+            return Err(Error::InvalidInputData);
+        }
+        self.set_channels_on_off_from_iter(first, on.iter().copied().zip(off.iter().copied()))
+            .await
+    }
+
+    /// Set the `ON` and `OFF` counters of consecutive channels starting at
+    /// `first` from an iterator of `(on, off)` pairs.
+    ///
+    /// All channels are written in a single I2C transaction.
+    ///
+    /// Returns `Error::InvalidInputData` for `Channel::All`, if the iterator
+    /// is empty, if the channels do not fit in the range `[0-15]` or if any
+    /// value is greater than 4095.
+    pub async fn set_channels_on_off_from_iter(
+        &mut self,
+        first: Channel,
+        values: impl IntoIterator<Item = (u16, u16)>,
+    ) -> Result<(), Error<E>> {
+        self.set_channels_from_iter(
+            first,
+            values.into_iter().map(|(on, off)| ChannelOnOffControl {
+                on,
+                off,
+                ..Default::default()
+            }),
+        )
+        .await
+    }
+
+    /// Set the `ON` and `OFF` counters of consecutive channels starting at
+    /// `first` from `(on, off)` pairs.
+    ///
+    /// All channels are written in a single I2C transaction, so that only
+    /// the changed range of channels needs to be transferred.
+    ///
+    /// Returns `Error::InvalidInputData` for `Channel::All`, if `values` is
+    /// empty, if the channels do not fit in the range `[0-15]` or if any
+    /// value is greater than 4095.
+    pub async fn set_channel_range_on_off(
+        &mut self,
+        first: Channel,
+        values: &[(u16, u16)],
+    ) -> Result<(), Error<E>> {
+        self.set_channels_on_off_from_iter(first, values.iter().copied())
+            .await
+    }
+
+    /// Set the PWM control values of consecutive channels starting at
+    /// `first` from an iterator.
+    ///
+    /// All channels are written in a single I2C transaction.
+    ///
+    /// Returns `Error::InvalidInputData` for `Channel::All`, if the iterator
+    /// is empty, if the channels do not fit in the range `[0-15]` or if any
+    /// value is greater than 4095.
+    pub async fn set_channels_from_iter(
+        &mut self,
+        first: Channel,
+        values: impl IntoIterator<Item = ChannelOnOffControl>,
+    ) -> Result<(), Error<E>> {
+        let start = first.index().ok_or(Error::InvalidInputData)?;
+        let mut values = values.into_iter();
+        let mut buffer = [ChannelOnOffControl::default(); 16];
+        let mut count = 0_usize;
+        // `values` is polled last so that an element which does not fit is
+        // left in it.
+        for ((slot, channel), value) in buffer
+            .iter_mut()
+            .zip(CHANNELS.iter().skip(start))
+            .zip(&mut values)
+        {
+            if value.on > 4095 || value.off > 4095 {
                 return Err(Error::InvalidInputData);
             }
-            data[i * 4 + 1] = *on as u8;
-            data[i * 4 + 2] = (*on >> 8) as u8;
-            data[i * 4 + 3] = *off as u8;
-            data[i * 4 + 4] = (*off >> 8) as u8;
-        }
-        self.enable_auto_increment().await?;
-        self.i2c
-            .write(self.address, &data)
-            .await
-            .map_err(Error::I2C)
+            self.check_channel_mode(*channel, OutputKind::of(&value))?;
+            *slot = value;
+            count = count.saturating_add(1);
+        }
+        if count == 0 || values.next().is_some() {
+            return Err(Error::InvalidInputData);
+        }
+        let values = buffer.get(..count).ok_or(Error::Internal)?;
+        self.write_channels(start, values).await
+    }
+
+    /// Set the order in which the PWM control registers are updated.
+    ///
+    /// This does not have any effect on the hardware.
+    pub fn set_update_ordering(&mut self, ordering: UpdateOrdering) {
+        self.update_ordering = ordering;
+    }
+
+    /// Select whether out-of-range counter values are clamped instead of
+    /// rejected.
+    ///
+    /// By default, counter values greater than 4095 passed to
+    /// [`set_channel_on()`](Pca9685::set_channel_on),
+    /// [`set_channel_off()`](Pca9685::set_channel_off),
+    /// [`set_channel_on_off()`](Pca9685::set_channel_on_off),
+    /// [`set_channel_duty_off_only()`](Pca9685::set_channel_duty_off_only),
+    /// [`set_channel_full_on()`](Pca9685::set_channel_full_on) and
+    /// [`set_channel_duty()`](Pca9685::set_channel_duty) are rejected with
+    /// `Error::InvalidInputData`. When enabled, they are lowered to 4095
+    /// instead, which is recorded in a flag that can be checked with
+    /// [`take_clamped()`](Pca9685::take_clamped).
+    ///
+    /// This does not have any effect on the hardware.
+    pub fn set_clamp_out_of_range(&mut self, enabled: bool) {
+        self.clamp_out_of_range = enabled;
+    }
+
+    /// Returns whether any value has been clamped since the last call and
+    /// clears the flag.
+    ///
+    /// See: [`set_clamp_out_of_range()`](Pca9685::set_clamp_out_of_range).
+    pub fn take_clamped(&mut self) -> bool {
+        core::mem::take(&mut self.clamped)
+    }
+
+    /// Check a counter value, clamping it if out-of-range values are clamped.
+    pub(crate) fn check_count(&mut self, value: u16) -> Result<u16, Error<E>> {
+        if value <= 4095 {
+            Ok(value)
+        } else if self.clamp_out_of_range {
+            self.clamped = true;
+            Ok(4095)
+        } else {
+            Err(Error::InvalidInputData)
+        }
+    }
+
+    /// Set the maximum length in bytes of a write transaction.
+    ///
+    /// Some I2C implementations cannot write the 65 bytes needed to update
+    /// all channels at once. Writes to several channels are then split at
+    /// channel boundaries into as many auto-increment transactions as
+    /// necessary. Each transaction consists of the register address and
+    /// 4 bytes per channel. The default is 65 bytes.
+    ///
+    /// Returns `Error::InvalidInputData` if `len` is lower than 5, which is
+    /// needed to write a single channel.
+    /// This does not have any effect on the hardware.
+    pub fn set_max_write_len(&mut self, len: usize) -> Result<(), Error<E>> {
+        if len < 5 {
+            return Err(Error::InvalidInputData);
+        }
+        self.max_write_channels = (len.saturating_sub(1) / 4).min(16);
+        Ok(())
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Set the intended use of a channel.
+    ///
+    /// Subsequent operations on the channel which do not match this mode are
+    /// rejected with `Error::ChannelModeMismatch`. This helps catching wiring
+    /// and configuration mix-ups. All channels are in `ChannelMode::Any` by
+    /// default, which allows any operation.
+    ///
+    /// `Channel::All` sets the mode of every channel.
+    pub fn set_channel_mode(&mut self, channel: Channel, mode: ChannelMode) {
+        match channel.index() {
+            Some(i) => {
+                if let Some(m) = self.channel_modes.get_mut(i) {
+                    *m = mode;
+                }
+            }
+            None => self.channel_modes = [mode; 16],
+        }
+    }
+
+    /// Set the priority class of a channel.
+    ///
+    /// When a commit is split across several transactions, like with
+    /// [`start_chunked_commit()`](Pca9685::start_chunked_commit), channels
+    /// with a higher priority are written first. This makes the order
+    /// deterministic if the bus fails in the middle of a frame.
+    ///
+    /// `Channel::All` sets the priority of every channel.
+    ///
+    /// This does not have any effect on the hardware.
+    pub fn set_channel_priority(&mut self, channel: Channel, priority: Priority) {
+        match channel.index() {
+            Some(i) => {
+                if let Some(p) = self.priorities.get_mut(i) {
+                    *p = priority;
+                }
+            }
+            None => self.priorities = [priority; 16],
+        }
+    }
+
+    /// Get the priority class of a channel.
+    ///
+    /// Returns `None` for `Channel::All`.
+    pub fn channel_priority(&self, channel: Channel) -> Option<Priority> {
+        channel
+            .index()
+            .and_then(|i| self.priorities.get(i))
+            .copied()
+    }
+
+    /// Get the intended use of a channel.
+    ///
+    /// Returns `None` for `Channel::All`.
+    pub fn channel_mode(&self, channel: Channel) -> Option<ChannelMode> {
+        channel
+            .index()
+            .and_then(|i| self.channel_modes.get(i))
+            .copied()
+    }
+
+    pub(crate) fn check_channel_mode(
+        &self,
+        channel: Channel,
+        kind: OutputKind,
+    ) -> Result<(), Error<E>> {
+        let mismatch = match channel.index() {
+            Some(i) => self
+                .channel_modes
+                .get(i)
+                .filter(|m| !m.allows(kind))
+                .map(|_| channel),
+            None => self
+                .channel_modes
+                .iter()
+                .position(|m| !m.allows(kind))
+                .and_then(|i| Channel::try_from(i).ok()),
+        };
+        match mismatch {
+            Some(c) => Err(Error::ChannelModeMismatch(c)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Contents of the `ON` and `OFF` registers of a channel.
+fn register_values(value: &ChannelOnOffControl) -> (u16, u16) {
+    let bit = |set: bool, mask: u16| if set { mask } else { 0 };
+    (
+        value.on | bit(value.full_on, FULL_ON_BIT),
+        value.off | bit(value.full_off, FULL_OFF_BIT),
+    )
+}
+
+/// Partial write to the PWM control registers of a channel.
+///
+/// The values include the full-on and full-off bits.
+#[derive(Debug, Clone, Copy)]
+enum ChannelWrite {
+    On(u16),
+    Off(u16),
+    OnOff(u16, u16),
+}
+
+impl ChannelWrite {
+    fn apply(self, value: &mut ChannelOnOffControl) {
+        let (on, off) = match self {
+            ChannelWrite::On(on) => (Some(on), None),
+            ChannelWrite::Off(off) => (None, Some(off)),
+            ChannelWrite::OnOff(on, off) => (Some(on), Some(off)),
+        };
+        if let Some(on) = on {
+            value.on = on & !FULL_ON_BIT;
+            value.full_on = on & FULL_ON_BIT != 0;
+        }
+        if let Some(off) = off {
+            value.off = off & !FULL_OFF_BIT;
+            value.full_off = off & FULL_OFF_BIT != 0;
+        }
+    }
+}
+
+/// Whether changing the PWM control values of a channel from `old` to `new`
+/// can produce a glitch in the current PWM cycle.
+///
+/// This is the case when a channel keeps generating pulses but the `ON`
+/// counter changes or the `OFF` counter moves earlier. If the counter has
+/// already passed the new switching point, the output is held high for up
+/// to a full cycle.
+fn glitch_risk(old: &ChannelOnOffControl, new: &ChannelOnOffControl) -> bool {
+    let is_pwm = |c: &ChannelOnOffControl| !c.full_on && !c.full_off;
+    is_pwm(old) && is_pwm(new) && (new.on != old.on || new.off < old.off)
+}
+
+/// Kind of output written to a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputKind {
+    Counters,
+    FullOn,
+    FullOff,
+    Servo,
+}
+
+impl OutputKind {
+    pub(crate) fn of(value: &ChannelOnOffControl) -> Self {
+        if value.full_off {
+            OutputKind::FullOff
+        } else if value.full_on {
+            OutputKind::FullOn
+        } else {
+            OutputKind::Counters
+        }
+    }
+}
+
+impl ChannelMode {
+    pub(crate) fn allows(self, kind: OutputKind) -> bool {
+        match self {
+            ChannelMode::Any => true,
+            ChannelMode::Pwm => kind != OutputKind::Servo,
+            ChannelMode::Digital => matches!(kind, OutputKind::FullOn | OutputKind::FullOff),
+            ChannelMode::Servo => matches!(kind, OutputKind::Servo | OutputKind::FullOff),
+        }
     }
 }
 
@@ -194,13 +888,13 @@ macro_rules! get_register {
     ($channel:expr, $($C:ident, $reg:ident),*) => {
         match $channel {
             $(
-                Channel::$C  => Register::$reg,
+                Channel::$C  => regs::$reg,
             )*
         }
     };
 }
 
-fn get_register_on(channel: Channel) -> u8 {
+pub(crate) fn get_register_on(channel: Channel) -> u8 {
     get_register!(
         channel, C0, C0_ON_L, C1, C1_ON_L, C2, C2_ON_L, C3, C3_ON_L, C4, C4_ON_L, C5, C5_ON_L, C6,
         C6_ON_L, C7, C7_ON_L, C8, C8_ON_L, C9, C9_ON_L, C10, C10_ON_L, C11, C11_ON_L, C12,
@@ -208,7 +902,7 @@ fn get_register_on(channel: Channel) -> u8 {
     )
 }
 
-fn get_register_off(channel: Channel) -> u8 {
+pub(crate) fn get_register_off(channel: Channel) -> u8 {
     get_register!(
         channel,
         C0,
@@ -0,0 +1,129 @@
+//! Frame commits split into chunks
+
+use crate::{channels::OutputKind, types::CHANNELS, Error, Frame, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ChunkedCommit {
+    frame: Frame,
+    /// Channel indices in the order they are written.
+    order: [u8; 16],
+    next: usize,
+    chunk_size: usize,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Start writing a frame to all channels in chunks of `channels_per_chunk`
+    /// channels.
+    ///
+    /// Nothing is written to the device by this method. Each call to
+    /// [`commit_next_chunk()`](Pca9685::commit_next_chunk) or to
+    /// [`tick()`](Pca9685::tick) writes the next chunk. This bounds the time
+    /// the bus is held per call, at the expense of the channels being updated
+    /// at different times.
+    ///
+    /// The channels are written in order of their priority class. See:
+    /// [`set_channel_priority()`](Pca9685::set_channel_priority). Each run
+    /// of consecutive channels in a chunk is written in a single I2C
+    /// transaction, so with the default priorities each chunk is written in
+    /// a single transaction.
+    ///
+    /// A pending chunked commit is replaced. If the failsafe timeout is
+    /// triggered, the pending chunked commit is cancelled.
+    ///
+    /// Returns `Error::InvalidInputData` if `channels_per_chunk` is not in
+    /// the range `[1-16]` or if any value in the frame is greater than 4095.
+    pub fn start_chunked_commit(
+        &mut self,
+        frame: Frame,
+        channels_per_chunk: u8,
+    ) -> Result<(), Error<E>> {
+        let chunk_size = usize::from(channels_per_chunk);
+        if chunk_size == 0 || chunk_size > 16 {
+            return Err(Error::InvalidInputData);
+        }
+        for (channel, value) in CHANNELS.iter().zip(frame.channels()) {
+            if value.on > 4095 || value.off > 4095 {
+                return Err(Error::InvalidInputData);
+            }
+            self.check_channel_mode(*channel, OutputKind::of(value))?;
+        }
+        let mut order = [0; 16];
+        for (i, slot) in order.iter_mut().enumerate() {
+            *slot = i as u8;
+        }
+        let priorities = self.priorities;
+        order.sort_unstable_by_key(|&i| (priorities.get(usize::from(i)).copied(), i));
+        self.chunked_commit = Some(ChunkedCommit {
+            frame,
+            order,
+            next: 0,
+            chunk_size,
+        });
+        Ok(())
+    }
+
+    /// Write the next chunk of the pending chunked commit.
+    ///
+    /// Returns `true` once all channels have been written or if there is no
+    /// pending chunked commit.
+    pub async fn commit_next_chunk(&mut self) -> Result<bool, Error<E>> {
+        let Some(commit) = self.chunked_commit else {
+            return Ok(true);
+        };
+        let end = commit.next.saturating_add(commit.chunk_size).min(16);
+        let mut chunk = commit
+            .order
+            .get(commit.next..end)
+            .ok_or(Error::InvalidInputData)?;
+        while let [first, ..] = chunk {
+            let run = chunk
+                .iter()
+                .zip(*first..)
+                .take_while(|(index, expected)| **index == *expected)
+                .count();
+            let first = usize::from(*first);
+            let values = commit
+                .frame
+                .channels()
+                .get(first..)
+                .and_then(|values| values.get(..run))
+                .ok_or(Error::InvalidInputData)?;
+            self.write_channels(first, values).await?;
+            chunk = chunk.get(run..).unwrap_or(&[]);
+        }
+        if end == 16 {
+            self.chunked_commit = None;
+        } else if let Some(c) = &mut self.chunked_commit {
+            c.next = end;
+        }
+        Ok(end == 16)
+    }
+
+    /// Returns whether a chunked commit has not been completely written yet.
+    pub fn is_chunked_commit_pending(&self) -> bool {
+        self.chunked_commit.is_some()
+    }
+
+    /// Cancel the pending chunked commit.
+    ///
+    /// Chunks already written are not reverted.
+    pub fn cancel_chunked_commit(&mut self) {
+        self.chunked_commit = None;
+    }
+}
@@ -0,0 +1,119 @@
+//! Interoperability with the color types of the `rgb` crate
+
+use crate::{Duty, Frame, IntoDuty, Rounding, Segment};
+use rgb::{RGB16, RGB8, RGBA8};
+
+/// Color which can be rendered onto a pixel of `N` channels
+///
+/// This is implemented for the `rgb` crate types `RGB8` and `RGB16` for
+/// RGB pixels and `RGBA8` for RGBW pixels, where the alpha component drives
+/// the white channel. 8-bit components are scaled to the full duty cycle
+/// range rounding to the nearest value and 16-bit components are truncated
+/// to 12 bits.
+pub trait IntoPixel<const N: usize> {
+    /// Convert into the duty cycles of the channels of a pixel.
+    fn into_pixel(self) -> [Duty; N];
+}
+
+impl<const N: usize> IntoPixel<N> for [Duty; N] {
+    fn into_pixel(self) -> [Duty; N] {
+        self
+    }
+}
+
+impl IntoPixel<3> for RGB8 {
+    fn into_pixel(self) -> [Duty; 3] {
+        [from_u8(self.r), from_u8(self.g), from_u8(self.b)]
+    }
+}
+
+impl IntoPixel<3> for RGB16 {
+    fn into_pixel(self) -> [Duty; 3] {
+        [from_u16(self.r), from_u16(self.g), from_u16(self.b)]
+    }
+}
+
+impl IntoPixel<4> for RGBA8 {
+    fn into_pixel(self) -> [Duty; 4] {
+        [
+            from_u8(self.r),
+            from_u8(self.g),
+            from_u8(self.b),
+            from_u8(self.a),
+        ]
+    }
+}
+
+fn from_u8(value: u8) -> Duty {
+    value.into_duty(Rounding::Nearest).unwrap_or_default()
+}
+
+fn from_u16(value: u16) -> Duty {
+    Duty::new(value >> 4).unwrap_or_default()
+}
+
+impl<const N: usize> Segment<N> {
+    /// Set the channels of a pixel to a color.
+    ///
+    /// Returns `false` if `index` is out of range.
+    pub fn set_pixel_color(
+        &self,
+        frame: &mut Frame,
+        index: usize,
+        color: impl IntoPixel<N>,
+    ) -> bool {
+        self.set_pixel(frame, index, color.into_pixel())
+    }
+
+    /// Set all pixels to the same color.
+    pub fn fill_color(&self, frame: &mut Frame, color: impl IntoPixel<N>) {
+        self.fill(frame, color.into_pixel());
+    }
+
+    /// Set the pixels to a row of colors, for example a line of an image.
+    ///
+    /// The first color is set on the first pixel. Surplus colors are ignored
+    /// and pixels without a color are left unchanged.
+    pub fn set_pixel_colors<C: IntoPixel<N> + Copy>(&self, frame: &mut Frame, colors: &[C]) {
+        for (index, color) in colors.iter().enumerate().take(self.len()) {
+            self.set_pixel_color(frame, index, *color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{boards::rgb_consecutive, Channel};
+
+    fn off(frame: &Frame, channel: Channel) -> Option<u16> {
+        frame.get(channel).map(|c| c.off)
+    }
+
+    #[test]
+    fn can_scale_components() {
+        let duty = |c| Duty::new(c).unwrap();
+        assert_eq!(
+            [duty(4095), duty(2056), duty(0)],
+            RGB8::new(255, 128, 0).into_pixel()
+        );
+        assert_eq!(
+            [duty(4095), duty(2048), duty(0)],
+            RGB16::new(0xFFFF, 0x8000, 0x000F).into_pixel()
+        );
+        assert_eq!(duty(4095), RGBA8::new(0, 0, 0, 255).into_pixel()[3]);
+    }
+
+    #[test]
+    fn can_set_pixel_colors() {
+        let segment = Segment::from_rgb_pins(&rgb_consecutive::LEDS).unwrap();
+        let mut frame = Frame::all_off();
+        segment.set_pixel_colors(&mut frame, &[RGB8::new(255, 0, 0), RGB8::new(0, 0, 255)]);
+        assert_eq!(Some(4095), off(&frame, Channel::C0));
+        assert_eq!(
+            frame.get(Channel::C1),
+            Some(crate::ChannelOnOffControl::full_off())
+        );
+        assert_eq!(Some(4095), off(&frame, Channel::C5));
+    }
+}
@@ -1,27 +1,31 @@
 //! Device configuration
 
+use crate::regs::{mode1, mode2};
+
 pub enum BitFlag {
     Mode1(BitFlagMode1),
     Mode2(BitFlagMode2),
 }
 
+#[repr(u8)]
 pub enum BitFlagMode1 {
-    Restart = 0b1000_0000,
-    ExtClk = 0b0100_0000,
-    AutoInc = 0b0010_0000,
-    Sleep = 0b0001_0000,
-    Subaddr1 = 0b0000_1000,
-    Subaddr2 = 0b0000_0100,
-    Subaddr3 = 0b0000_0010,
-    AllCall = 0b0000_0001,
+    Restart = mode1::RESTART,
+    ExtClk = mode1::EXTCLK,
+    AutoInc = mode1::AI,
+    Sleep = mode1::SLEEP,
+    Subaddr1 = mode1::SUB1,
+    Subaddr2 = mode1::SUB2,
+    Subaddr3 = mode1::SUB3,
+    AllCall = mode1::ALLCALL,
 }
 
+#[repr(u8)]
 pub enum BitFlagMode2 {
-    Invrt = 0b0001_0000,
-    Och = 0b0000_1000,
-    OutDrv = 0b0000_0100,
-    OutNe1 = 0b0000_0010,
-    OutNe0 = 0b0000_0001,
+    Invrt = mode2::INVRT,
+    Och = mode2::OCH,
+    OutDrv = mode2::OUTDRV,
+    OutNe1 = mode2::OUTNE1,
+    OutNe0 = mode2::OUTNE0,
 }
 
 impl From<BitFlagMode1> for BitFlag {
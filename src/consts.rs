@@ -0,0 +1,42 @@
+//! Device limits and common values
+//!
+//! ```
+//! use pwm_pca9685::consts::{FULL_OFF_BIT, MAX_COUNT, PRESCALE_SERVO_50HZ};
+//!
+//! assert_eq!(4095, MAX_COUNT);
+//! assert_eq!(0x1000, FULL_OFF_BIT);
+//! assert_eq!(121, PRESCALE_SERVO_50HZ);
+//! ```
+
+use crate::{regs, Prescale};
+
+/// Maximum value of the `on` and `off` counters.
+pub const MAX_COUNT: u16 = 4095;
+
+/// Full-on bit in the 16-bit value of the `ON` counter registers.
+///
+/// This is the bit [`regs::FULL_ON_OFF`] in the `ON_H` register.
+pub const FULL_ON_BIT: u16 = (regs::FULL_ON_OFF as u16) << 8;
+
+/// Full-off bit in the 16-bit value of the `OFF` counter registers.
+///
+/// This is the bit [`regs::FULL_ON_OFF`] in the `OFF_H` register.
+pub const FULL_OFF_BIT: u16 = (regs::FULL_ON_OFF as u16) << 8;
+
+/// Minimum prescale value accepted by the device. See [`Prescale::MIN`].
+pub const PRESCALE_MIN: u8 = Prescale::MIN.value();
+
+/// Maximum prescale value. See [`Prescale::MAX`].
+pub const PRESCALE_MAX: u8 = Prescale::MAX.value();
+
+/// Power-up prescale value. See [`Prescale::DEFAULT`].
+pub const PRESCALE_DEFAULT: u8 = Prescale::DEFAULT.value();
+
+/// Prescale value for 50 Hz servos. See [`Prescale::SERVO_50HZ`].
+pub const PRESCALE_SERVO_50HZ: u8 = Prescale::SERVO_50HZ.value();
+
+/// Prescale value for 60 Hz servos. See [`Prescale::SERVO_60HZ`].
+pub const PRESCALE_SERVO_60HZ: u8 = Prescale::SERVO_60HZ.value();
+
+/// Prescale value for about 1 kHz LED dimming. See [`Prescale::LED_1KHZ`].
+pub const PRESCALE_LED_1KHZ: u8 = Prescale::LED_1KHZ.value();
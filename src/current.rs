@@ -0,0 +1,49 @@
+//! Current consumption estimation
+
+use crate::{config::BitFlagMode2, Channel, Pca9685};
+
+impl<I2C> Pca9685<I2C> {
+    /// Set the current drawn by the load of a channel while its output is
+    /// active, in milliamperes.
+    ///
+    /// `Channel::All` sets the current of every channel.
+    ///
+    /// This does not have any effect on the hardware.
+    pub fn set_channel_current_ma(&mut self, channel: Channel, current_ma: u16) {
+        for (i, current) in self.channel_current_ma.iter_mut().enumerate() {
+            if channel.index().is_some_and(|index| index != i) {
+                continue;
+            }
+            *current = current_ma;
+        }
+    }
+
+    /// Estimate the average current drawn by the loads of all channels in
+    /// milliamperes.
+    ///
+    /// The current of each channel set with
+    /// [`set_channel_current_ma()`](Pca9685::set_channel_current_ma) is
+    /// weighted with the fraction of the PWM cycle its output is active,
+    /// taking full-on/full-off states and inverted output logic into account.
+    /// The estimate is based on the control values last written by this
+    /// driver and is rounded to the nearest milliampere.
+    pub fn estimated_current_ma(&self) -> u32 {
+        let inverted = self.config.is_high(BitFlagMode2::Invrt);
+        let total: u64 = self
+            .outputs
+            .channels()
+            .iter()
+            .zip(self.channel_current_ma)
+            .map(|(value, current_ma)| {
+                let counts = value.active_counts();
+                let counts = if inverted {
+                    4096_u16.saturating_sub(counts)
+                } else {
+                    counts
+                };
+                u64::from(current_ma).saturating_mul(u64::from(counts))
+            })
+            .fold(0, u64::saturating_add);
+        (total.saturating_add(2048) / 4096) as u32
+    }
+}
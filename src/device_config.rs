@@ -0,0 +1,197 @@
+//! Full device configuration
+
+use crate::{
+    config::Config, device_impl::PRESCALE_DEFAULT, regs, Error, Mode1Flags, Mode2Flags, Pca9685,
+};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Contents of the mode and prescale registers
+///
+/// See: [`apply_config()`](Pca9685::apply_config).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeviceConfig {
+    /// `MODE1` register contents.
+    pub mode1: Mode1Flags,
+    /// `MODE2` register contents.
+    pub mode2: Mode2Flags,
+    /// Prescale value.
+    pub prescale: u8,
+}
+
+/// Power-up state.
+impl Default for DeviceConfig {
+    fn default() -> Self {
+        DeviceConfig {
+            mode1: Mode1Flags::default(),
+            mode2: Mode2Flags::default(),
+            prescale: PRESCALE_DEFAULT,
+        }
+    }
+}
+
+/// Differences between the mode registers of the device and the
+/// configuration cached by the driver
+///
+/// The `RESTART` bit is not compared. See:
+/// [`verify_config()`](Pca9685::verify_config).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConfigDiff {
+    /// `MODE1` bits which differ.
+    pub mode1: Mode1Flags,
+    /// `MODE2` bits which differ.
+    pub mode2: Mode2Flags,
+    /// `MODE1` register contents read from the device.
+    pub device_mode1: Mode1Flags,
+    /// `MODE2` register contents read from the device.
+    pub device_mode2: Mode2Flags,
+}
+
+impl ConfigDiff {
+    /// Returns whether the device and the cached configuration agree.
+    pub fn is_empty(&self) -> bool {
+        self.mode1.bits() == 0 && self.mode2.bits() == 0
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Get the configuration last written by this driver.
+    ///
+    /// This does not read the device.
+    pub fn device_config(&self) -> DeviceConfig {
+        DeviceConfig {
+            mode1: self.mode1_flags(),
+            mode2: self.mode2_flags(),
+            prescale: self.prescale,
+        }
+    }
+
+    /// Read the `MODE1`, `MODE2` and `PRE_SCALE` registers and replace the
+    /// configuration and prescale value cached by this driver with their
+    /// contents.
+    ///
+    /// This is necessary if the device was configured by other software, for
+    /// example a bootloader or another process, or if it was reset without
+    /// this driver noticing. Otherwise, the next mode register write would
+    /// overwrite the settings on the device with the stale cached ones and
+    /// the frequency and pulse width conversions would use a stale prescale
+    /// value. The `RESTART` bit is not cached.
+    ///
+    /// The cached channel values are not updated. See
+    /// [`sync_outputs_from_device()`](Pca9685::sync_outputs_from_device).
+    pub async fn sync_from_device(&mut self) -> Result<(), Error<E>> {
+        let mode1 = self.read_register(regs::MODE1).await?;
+        let mode2 = self.read_register(regs::MODE2).await?;
+        let prescale = self.read_register(regs::PRE_SCALE).await?;
+        self.config = Config {
+            mode1: mode1 & !regs::mode1::RESTART,
+            mode2,
+        };
+        self.prescale = prescale;
+        Ok(())
+    }
+
+    /// Read the `MODE1` and `MODE2` registers and compare them with the
+    /// configuration cached by this driver.
+    ///
+    /// A difference means that the device was reset, for example after a
+    /// brown-out, or that another I2C master changed its configuration. The
+    /// cached configuration is not changed. See
+    /// [`sync_from_device()`](Pca9685::sync_from_device).
+    ///
+    /// Returns `Error::ConfigMismatch` with the differing bits if the
+    /// registers do not match.
+    pub async fn verify_config(&mut self) -> Result<(), Error<E>> {
+        let mode1 = self.read_register(regs::MODE1).await? & !regs::mode1::RESTART;
+        let mode2 = self.read_register(regs::MODE2).await?;
+        let cached_mode1 = self.config.mode1 & !regs::mode1::RESTART;
+        let diff = ConfigDiff {
+            mode1: Mode1Flags::from_bits_truncate(mode1 ^ cached_mode1),
+            mode2: Mode2Flags::from_bits_truncate(mode2 ^ self.config.mode2),
+            device_mode1: Mode1Flags::from_bits_truncate(mode1),
+            device_mode2: Mode2Flags::from_bits_truncate(mode2),
+        };
+        if diff.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::ConfigMismatch(diff))
+        }
+    }
+
+    /// Apply a full configuration writing only the registers which differ
+    /// from the configuration last written by this driver.
+    ///
+    /// If the prescale value or the clock source change, the oscillator is
+    /// stopped first as required by the device. The `MODE1` register is
+    /// written last, so the oscillator is started again if `target` is not
+    /// asleep. Allow 500 µs for the oscillator to stabilize before relying on
+    /// the outputs in that case. The `RESTART` bit of `target` is ignored.
+    ///
+    /// Returns `Error::InvalidInputData` if the prescale value is lower than
+    /// 3 or if `target` selects the internal oscillator while the external
+    /// clock is in use, since only a reset can switch back to it.
+    pub async fn apply_config(&mut self, target: DeviceConfig) -> Result<(), Error<E>> {
+        let mut mode1 = target.mode1;
+        mode1.remove(Mode1Flags::RESTART);
+        let current = self.mode1_flags();
+        let set_extclk =
+            mode1.contains(Mode1Flags::EXTCLK) && !current.contains(Mode1Flags::EXTCLK);
+        if target.prescale < 3
+            || (current.contains(Mode1Flags::EXTCLK) && !mode1.contains(Mode1Flags::EXTCLK))
+        {
+            return Err(Error::InvalidInputData);
+        }
+        if target.mode2 != self.mode2_flags() {
+            self.write_mode2(Config {
+                mode2: target.mode2.bits(),
+                ..self.config
+            })
+            .await?;
+        }
+        if target.prescale != self.prescale || set_extclk {
+            if !current.contains(Mode1Flags::SLEEP) {
+                self.write_mode1(Config {
+                    mode1: (current | Mode1Flags::SLEEP).bits(),
+                    ..self.config
+                })
+                .await?;
+            }
+            if target.prescale != self.prescale {
+                self.i2c
+                    .write(self.address, &[regs::PRE_SCALE, target.prescale])
+                    .await
+                    .map_err(Error::I2C)?;
+                self.prescale = target.prescale;
+            }
+            if set_extclk {
+                self.write_mode1(Config {
+                    mode1: (current | Mode1Flags::SLEEP | Mode1Flags::EXTCLK).bits(),
+                    ..self.config
+                })
+                .await?;
+            }
+        }
+        if mode1 != self.mode1_flags() {
+            self.write_mode1(Config {
+                mode1: mode1.bits(),
+                ..self.config
+            })
+            .await?;
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,14 @@
 use crate::{
+    calc,
     config::{BitFlagMode1, BitFlagMode2, Config},
-    Address, DisabledOutputValue, Error, OutputDriver, OutputLogicState, OutputStateChange,
-    Pca9685, ProgrammableAddress, Register,
+    profile::Profiles,
+    quirks::QuirkState,
+    regs,
+    servo::ServoIdle,
+    staleness::Staleness,
+    Address, BrightnessCurve, ChannelLimits, ChannelMode, DisabledOutputValue, Error, Frame,
+    Mode1Flags, Mode2Flags, OutputDriver, OutputLogicState, OutputStateChange, Pca9685, Prescale,
+    Priority, ProgrammableAddress, Rounding, Topology, UpdateOrdering,
 };
 
 #[cfg(not(feature = "async"))]
@@ -9,6 +16,9 @@ use embedded_hal::{delay::DelayNs, i2c::I2c};
 #[cfg(feature = "async")]
 use embedded_hal_async::{delay::DelayNs as AsyncDelayNs, i2c::I2c as AsyncI2c};
 
+/// Prescale register value after power-up (200 Hz).
+pub(crate) const PRESCALE_DEFAULT: u8 = Prescale::DEFAULT.value();
+
 #[maybe_async_cfg::maybe(
     sync(
         cfg(not(feature = "async")),
@@ -32,9 +42,65 @@ where
             i2c,
             address: a.0,
             config: Config::default(),
+            prescale: PRESCALE_DEFAULT,
+            oscillator_correction_ppm: 0,
+            rounding: Rounding::default(),
+            brightness_curve: BrightnessCurve::default(),
+            channel_modes: [ChannelMode::Any; 16],
+            priorities: [Priority::Normal; 16],
+            outputs: Frame::all_off(),
+            update_ordering: UpdateOrdering::default(),
+            max_write_channels: 16,
+            multi_master: false,
+            channel_current_ma: [0; 16],
+            limits: [ChannelLimits::default(); 16],
+            promote_full_on_off: false,
+            single_register_writes: false,
+            quirks: QuirkState::default(),
+            clamp_out_of_range: false,
+            clamped: false,
+            labels: [None; 16],
+            failsafe: None,
+            chunked_commit: None,
+            restart_started_us: None,
+            suspended: None,
+            slow_blink: [None; 16],
+            blink_code: None,
+            observer: None,
+            servo_idle: [None; 16],
+            waveforms: [None; 16],
+            profiles: Profiles::default(),
+            staleness: Staleness::default(),
         })
     }
 
+    /// Create a new instance of the device and configure it for a PWM
+    /// frequency in Hz.
+    ///
+    /// This performs the whole initialization sequence: the controller is
+    /// put to sleep with register auto-increment enabled, the prescale
+    /// value is written as with
+    /// [`set_frequency_hz()`](Pca9685::set_frequency_hz) and the controller
+    /// is enabled again. Allow 500 µs for the oscillator to stabilize before
+    /// relying on the outputs.
+    ///
+    /// Returns `Error::InvalidInputData` if the address or the frequency is
+    /// out of range.
+    pub async fn new_with_frequency<A: Into<Address>>(
+        i2c: I2C,
+        address: A,
+        frequency_hz: u16,
+    ) -> Result<Self, Error<E>> {
+        let mut pwm = Self::new(i2c, address)?;
+        let prescale = pwm.prescale_for_frequency_hz(frequency_hz)?;
+        let config = pwm.config.with_high(BitFlagMode1::Sleep);
+        pwm.write_mode1(config.with_high(BitFlagMode1::AutoInc))
+            .await?;
+        pwm.set_prescale(prescale).await?;
+        pwm.enable().await?;
+        Ok(pwm)
+    }
+
     /// Destroy driver instance, return I²C bus instance.
     pub fn destroy(self) -> I2C {
         self.i2c
@@ -53,6 +119,41 @@ where
             .await
     }
 
+    /// Returns whether the controller is enabled according to the `MODE1`
+    /// register last written by this driver.
+    ///
+    /// This does not read the device. See
+    /// [`is_enabled_read()`](Pca9685::is_enabled_read).
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_low(BitFlagMode1::Sleep)
+    }
+
+    /// Returns whether the controller is asleep according to the `MODE1`
+    /// register last written by this driver.
+    ///
+    /// This does not read the device. See
+    /// [`is_sleeping_read()`](Pca9685::is_sleeping_read).
+    pub fn is_sleeping(&self) -> bool {
+        self.config.is_high(BitFlagMode1::Sleep)
+    }
+
+    /// Read the `MODE1` register and return whether the controller is
+    /// enabled.
+    ///
+    /// This does not update the configuration cached by this driver.
+    pub async fn is_enabled_read(&mut self) -> Result<bool, Error<E>> {
+        Ok(!self.is_sleeping_read().await?)
+    }
+
+    /// Read the `MODE1` register and return whether the controller is
+    /// asleep.
+    ///
+    /// This does not update the configuration cached by this driver.
+    pub async fn is_sleeping_read(&mut self) -> Result<bool, Error<E>> {
+        let mode1 = self.read_register(regs::MODE1).await?;
+        Ok((mode1 & BitFlagMode1::Sleep as u8) != 0)
+    }
+
     /// Put the controller to sleep while keeping the PWM register
     /// contents in preparation for a future restart.
     pub async fn enable_restart_and_disable(&mut self) -> Result<(), Error<E>> {
@@ -61,24 +162,65 @@ where
             .await?;
         // Do not store restart bit high as writing this bit high again
         // would internally clear it to 0. Writing 0 has no effect.
-        self.config = config;
+        self.config.mode1 &= !(BitFlagMode1::Restart as u8);
         Ok(())
     }
 
+    /// Read the `MODE1` register and return whether the `RESTART` bit is set.
+    ///
+    /// This is the case after the controller was put to sleep while PWM
+    /// channels were active. The channels can then be restarted with
+    /// [`restart()`](Pca9685::restart). Otherwise, the device needs to be
+    /// configured again.
+    pub async fn is_restart_pending(&mut self) -> Result<bool, Error<E>> {
+        let mode1 = self.read_register(regs::MODE1).await?;
+        Ok((mode1 & BitFlagMode1::Restart as u8) != 0)
+    }
+
     /// Re-enable the controller after a sleep with restart enabled so that
     /// previously active PWM channels are restarted.
     ///
     /// This includes a delay of 500us in order for the oscillator to stabilize.
     /// If you cannot afford a 500us delay you can use `restart_nonblocking()`.
     pub async fn restart(&mut self, delay: &mut impl AsyncDelayNs) -> Result<(), Error<E>> {
-        let mode1 = self.read_register(Register::MODE1).await?;
+        let mode1 = self.read_register(regs::MODE1).await?;
         if (mode1 & BitFlagMode1::Restart as u8) != 0 {
             self.enable().await?;
             delay.delay_us(500).await;
-            let previous = self.config;
-            let config = previous.with_high(BitFlagMode1::Restart);
+            let config = self.config.with_high(BitFlagMode1::Restart);
             self.write_mode1(config).await?;
-            self.config = previous;
+            self.config.mode1 &= !(BitFlagMode1::Restart as u8);
+        }
+        Ok(())
+    }
+
+    /// Put the controller to sleep recording the control values of all
+    /// channels so that they can be restored with
+    /// [`resume()`](Pca9685::resume).
+    ///
+    /// Unlike [`enable_restart_and_disable()`](Pca9685::enable_restart_and_disable),
+    /// this does not depend on the hardware restart flow, which does not
+    /// restore channels set to full-on or full-off. The recorded values are
+    /// the ones last written by this driver.
+    pub async fn suspend(&mut self) -> Result<(), Error<E>> {
+        self.disable().await?;
+        self.suspended = Some(self.outputs);
+        Ok(())
+    }
+
+    /// Re-enable the controller after [`suspend()`](Pca9685::suspend) and
+    /// restore the control values of all channels recorded at that time.
+    ///
+    /// This includes a delay of 500us in order for the oscillator to stabilize
+    /// before all channels are written in a single transaction. Changes made
+    /// to the channels while suspended are overwritten.
+    /// If the controller was not suspended, this only enables it.
+    pub async fn resume(&mut self, delay: &mut impl AsyncDelayNs) -> Result<(), Error<E>> {
+        self.enable().await?;
+        if let Some(frame) = self.suspended {
+            delay.delay_us(500).await;
+            self.write_all_channels(frame.channels()).await?;
+            self.suspended = None;
         }
         Ok(())
     }
@@ -91,7 +233,48 @@ where
     /// calling again to continue.
     pub async fn restart_nonblocking(&mut self) -> nb::Result<(), Error<E>> {
         let mode1 = self
-            .read_register(Register::MODE1)
+            .read_register(regs::MODE1)
+            .await
+            .map_err(nb::Error::Other)?;
+        let restart_high = (mode1 & BitFlagMode1::Restart as u8) != 0;
+        let sleep_high = (mode1 & BitFlagMode1::Sleep as u8) != 0;
+        if restart_high {
+            if sleep_high {
+                self.enable().await.map_err(nb::Error::Other)?;
+                return Err(nb::Error::WouldBlock);
+            } else {
+                let config = self.config.with_high(BitFlagMode1::Restart);
+                self.write_mode1(config).await.map_err(nb::Error::Other)?;
+                self.config.mode1 &= !(BitFlagMode1::Restart as u8);
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-enable the controller after a sleep with restart enabled so that
+    /// previously active PWM channels are restarted (polling version).
+    ///
+    /// This is a nonblocking version for systems with a timestamp source but
+    /// no delay implementation. `now_us` is the current time in microseconds
+    /// from a monotonic clock, which is allowed to wrap around.
+    ///
+    /// Call this repeatedly until it does not return `WouldBlock` anymore.
+    /// The driver keeps track of the time the oscillator was started and
+    /// completes the restart once 500us have elapsed. Calls in between do not
+    /// communicate with the device.
+    pub async fn restart_poll(&mut self, now_us: u32) -> nb::Result<(), Error<E>> {
+        if let Some(started_us) = self.restart_started_us {
+            if now_us.wrapping_sub(started_us) < 500 {
+                return Err(nb::Error::WouldBlock);
+            }
+            self.restart_started_us = None;
+            let config = self.config.with_high(BitFlagMode1::Restart);
+            self.write_mode1(config).await.map_err(nb::Error::Other)?;
+            self.config.mode1 &= !(BitFlagMode1::Restart as u8);
+            return Ok(());
+        }
+        let mode1 = self
+            .read_register(regs::MODE1)
             .await
             .map_err(nb::Error::Other)?;
         let restart_high = (mode1 & BitFlagMode1::Restart as u8) != 0;
@@ -99,12 +282,12 @@ where
         if restart_high {
             if sleep_high {
                 self.enable().await.map_err(nb::Error::Other)?;
+                self.restart_started_us = Some(now_us);
                 return Err(nb::Error::WouldBlock);
             } else {
-                let previous = self.config;
-                let config = previous.with_high(BitFlagMode1::Restart);
+                let config = self.config.with_high(BitFlagMode1::Restart);
                 self.write_mode1(config).await.map_err(nb::Error::Other)?;
-                self.config = previous;
+                self.config.mode1 &= !(BitFlagMode1::Restart as u8);
             }
         }
         Ok(())
@@ -124,10 +307,10 @@ where
 
         Self::check_address(a.0)?;
         let reg = match address_type {
-            ProgrammableAddress::Subaddress1 => Register::SUBADDR1,
-            ProgrammableAddress::Subaddress2 => Register::SUBADDR2,
-            ProgrammableAddress::Subaddress3 => Register::SUBADDR3,
-            ProgrammableAddress::AllCall => Register::ALL_CALL_ADDR,
+            ProgrammableAddress::Subaddress1 => regs::SUBADDR1,
+            ProgrammableAddress::Subaddress2 => regs::SUBADDR2,
+            ProgrammableAddress::Subaddress3 => regs::SUBADDR3,
+            ProgrammableAddress::AllCall => regs::ALL_CALL_ADDR,
         };
         self.i2c
             .write(self.address, &[reg, a.0])
@@ -254,6 +437,26 @@ where
         }
     }
 
+    /// Configure the outputs for the circuit connected to them.
+    ///
+    /// This sets the output logic state, the output driver and the disabled
+    /// output value in a single `MODE2` write, keeping the output change
+    /// behavior. See [`Topology`] for the resulting settings.
+    pub async fn set_output_topology(&mut self, topology: Topology) -> Result<(), Error<E>> {
+        let (state, driver, disabled) = topology.settings();
+        let mode2 = Mode2Flags::from_config(
+            state,
+            self.mode2_flags().output_change_behavior(),
+            driver,
+            disabled,
+        );
+        self.write_mode2(Config {
+            mode2: mode2.bits(),
+            ..self.config
+        })
+        .await
+    }
+
     /// Enable using the EXTCLK pin as clock source input.
     ///
     /// This setting is _sticky_. It can only be cleared by a power cycle or
@@ -267,6 +470,59 @@ where
             .await
     }
 
+    /// Set the prescale value for a PWM period in microseconds.
+    ///
+    /// For example, a period of 20 ms corresponds to the 50 Hz frame rate of
+    /// servos. The prescale value is calculated with
+    /// [`calc::prescale_for_period_us()`], assuming the internal oscillator
+    /// with the correction set with
+    /// [`set_oscillator_correction_ppm()`](Pca9685::set_oscillator_correction_ppm).
+    ///
+    /// Returns `Error::InvalidInputData` if the period is out of the range
+    /// `[574-42024]` µs without correction.
+    pub async fn set_period_us(&mut self, period_us: u32) -> Result<(), Error<E>> {
+        let prescale = calc::prescale_for_period_us(self.oscillator_hz(), period_us)
+            .ok_or(Error::InvalidInputData)?;
+        self.set_prescale(prescale).await
+    }
+
+    /// Set the prescale value for a PWM period in milliseconds.
+    ///
+    /// See: [`set_period_us()`](Pca9685::set_period_us).
+    pub async fn set_period_ms(&mut self, period_ms: u32) -> Result<(), Error<E>> {
+        let period_us = period_ms.checked_mul(1000).ok_or(Error::InvalidInputData)?;
+        self.set_period_us(period_us).await
+    }
+
+    /// Set the prescale value for a PWM frequency in Hz.
+    ///
+    /// The prescale value is calculated with
+    /// [`calc::prescale_for_frequency()`], assuming the internal oscillator
+    /// with the correction set with
+    /// [`set_oscillator_correction_ppm()`](Pca9685::set_oscillator_correction_ppm).
+    /// The resulting frequency may differ slightly from the requested one
+    /// due to rounding.
+    ///
+    /// Returns `Error::InvalidInputData` if the frequency is out of the range
+    /// `[24-1526]` Hz without correction.
+    pub async fn set_frequency_hz(&mut self, frequency_hz: u16) -> Result<(), Error<E>> {
+        let prescale = self.prescale_for_frequency_hz(frequency_hz)?;
+        self.set_prescale(prescale).await
+    }
+
+    fn prescale_for_frequency_hz(&self, frequency_hz: u16) -> Result<u8, Error<E>> {
+        let osc_hz = self.oscillator_hz();
+        let frequency_hz = u32::from(frequency_hz);
+        let min = calc::frequency_for_prescale(osc_hz, Prescale::MAX.value());
+        let max = calc::frequency_for_prescale(osc_hz, Prescale::MIN.value());
+        if min.is_some_and(|min| frequency_hz < min) || max.is_some_and(|max| frequency_hz > max) {
+            return Err(Error::InvalidInputData);
+        }
+        calc::prescale_for_frequency(osc_hz, frequency_hz)
+            .map(|(prescale, _)| prescale)
+            .ok_or(Error::InvalidInputData)
+    }
+
     /// Set the prescale value.
     ///
     /// The prescale value can be calculated for an update rate with the formula:
@@ -280,6 +536,9 @@ where
     /// correspond to a frequency of about 60 Hz, which is the frequency at
     /// which servos work.
     ///
+    /// Presets for common frequencies are available in [`Prescale`], for
+    /// example `set_prescale(Prescale::SERVO_50HZ.value())`.
+    ///
     /// Internally this function stops the oscillator and restarts it after
     /// setting the prescale value if it was running.
     pub async fn set_prescale(&mut self, prescale: u8) -> Result<(), Error<E>> {
@@ -295,9 +554,10 @@ where
         }
 
         self.i2c
-            .write(self.address, &[Register::PRE_SCALE, prescale])
+            .write(self.address, &[regs::PRE_SCALE, prescale])
             .await
             .map_err(Error::I2C)?;
+        self.prescale = prescale;
 
         if was_oscillator_running {
             // restart the oscillator
@@ -306,12 +566,86 @@ where
         Ok(())
     }
 
+    /// Set the prescale value like [`set_prescale()`](Pca9685::set_prescale)
+    /// and wait for the oscillator to stabilize.
+    ///
+    /// If the oscillator was running, it is restarted after writing the
+    /// prescale value and this includes a delay of 500us, as required by the
+    /// device before the outputs are reliable.
+    pub async fn set_prescale_with_delay(
+        &mut self,
+        prescale: u8,
+        delay: &mut impl AsyncDelayNs,
+    ) -> Result<(), Error<E>> {
+        let was_oscillator_running = self.config.is_low(BitFlagMode1::Sleep);
+        self.set_prescale(prescale).await?;
+        if was_oscillator_running {
+            delay.delay_us(500).await;
+        }
+        Ok(())
+    }
+
+    /// Read the prescale value from the `PRE_SCALE` register.
+    ///
+    /// This does not update the value cached by this driver. See
+    /// [`get_prescale()`](Pca9685::get_prescale).
+    pub async fn read_prescale(&mut self) -> Result<u8, Error<E>> {
+        self.read_register(regs::PRE_SCALE).await
+    }
+
+    /// Set the deviation of the internal oscillator from its nominal 25 MHz
+    /// in parts per million, as measured for example with an oscilloscope.
+    ///
+    /// The internal oscillator can be several percent off. The correction is
+    /// applied by all frequency, period and pulse width conversions of this
+    /// driver. A positive value means that the oscillator runs faster than
+    /// nominal. The default is 0.
+    ///
+    /// This does not have any effect on the hardware.
+    ///
+    /// Returns `Error::InvalidInputData` if the correction is out of the
+    /// range `[-200000-200000]` ppm.
+    pub fn set_oscillator_correction_ppm(&mut self, ppm: i32) -> Result<(), Error<E>> {
+        if !(-200_000..=200_000).contains(&ppm) {
+            return Err(Error::InvalidInputData);
+        }
+        self.oscillator_correction_ppm = ppm;
+        Ok(())
+    }
+
+    /// Set the rounding applied when converting values like pulse widths
+    /// into register values.
+    ///
+    /// This does not have any effect on the hardware.
+    pub fn set_rounding(&mut self, rounding: Rounding) {
+        self.rounding = rounding;
+    }
+
+    /// Get the `MODE1` register contents last written by this driver.
+    ///
+    /// This does not read the device.
+    pub fn mode1_flags(&self) -> Mode1Flags {
+        Mode1Flags::from(self.config.mode1)
+    }
+
+    /// Get the `MODE2` register contents last written by this driver.
+    ///
+    /// This does not read the device.
+    pub fn mode2_flags(&self) -> Mode2Flags {
+        Mode2Flags::from_bits_truncate(self.config.mode2)
+    }
+
     /// Reset the internal state of this driver to the default values.
     ///
     /// *Note:* This does not alter the state or configuration of the device.
     ///
-    /// This resets the cached configuration register value in this driver to
-    /// the power-up (reset) configuration of the device.
+    /// This resets the cached configuration register and prescale values in
+    /// this driver to the power-up (reset) configuration of the device.
+    /// The cached channel control values are reset to full-off. Slow blinks,
+    /// blink codes, waveforms, profile crossfades and pending chunked commits
+    /// are stopped without writing to the device, the servo idle timeouts
+    /// restart as if newly set and a recorded suspend state is discarded,
+    /// since they refer to the values before the reset.
     ///
     /// This needs to be called after performing a reset on the device, for
     /// example through an I2C general-call Reset command, which was not done
@@ -319,5 +653,33 @@ where
     /// and in the driver match.
     pub fn reset_internal_driver_state(&mut self) {
         self.config = Config::default();
+        self.prescale = PRESCALE_DEFAULT;
+        self.restart_started_us = None;
+        self.outputs = Frame::all_off();
+        self.suspended = None;
+        self.chunked_commit = None;
+        self.slow_blink = [None; 16];
+        self.blink_code = None;
+        for state in self.servo_idle.iter_mut().flatten() {
+            *state = ServoIdle::new(state.timeout_ms());
+        }
+        self.waveforms = [None; 16];
+        self.stop_profile_fade();
+    }
+
+    /// Enable or disable the multi-master mode.
+    ///
+    /// When another controller or an operating system driver also accesses the
+    /// device, the cached contents of the mode registers may be outdated.
+    /// In multi-master mode, the `MODE1` and `MODE2` registers are read before
+    /// each modification and only the bits changed by this driver are
+    /// modified, keeping the changes made by others. The `RESTART` bit read
+    /// from the device is never written back. The `MODE1` register is also
+    /// read before writing to several channels, to ensure auto-increment is
+    /// enabled. This takes one additional transaction in each case.
+    ///
+    /// This does not have any effect on the hardware.
+    pub fn set_multi_master(&mut self, enabled: bool) {
+        self.multi_master = enabled;
     }
 }
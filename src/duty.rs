@@ -0,0 +1,562 @@
+//! Duty cycle representations
+
+use crate::{
+    consts::MAX_COUNT, limits::Limited, math::div_rounded, types::CHANNELS, Channel,
+    ChannelOnOffControl, Error, Pca9685, Rounding,
+};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Duty cycle as a 12-bit counter value in the range `[0-4095]`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duty(u16);
+
+impl Duty {
+    /// Duty cycle of 0 counter steps.
+    pub const MIN: Duty = Duty(0);
+    /// Duty cycle of 4095 counter steps.
+    pub const MAX: Duty = Duty(MAX_COUNT);
+
+    /// Create a duty cycle from a counter value.
+    ///
+    /// Returns `None` if `count` is greater than 4095.
+    pub const fn new(count: u16) -> Option<Self> {
+        if count > MAX_COUNT {
+            None
+        } else {
+            Some(Duty(count))
+        }
+    }
+
+    /// Create a duty cycle from a counter value lowering values greater than
+    /// 4095 to 4095.
+    pub const fn saturating(count: u16) -> Self {
+        if count > MAX_COUNT {
+            Duty(MAX_COUNT)
+        } else {
+            Duty(count)
+        }
+    }
+
+    /// Get the counter value.
+    pub const fn count(self) -> u16 {
+        self.0
+    }
+
+    #[doc(hidden)]
+    pub const fn __from_percent(percent: u32) -> Option<Self> {
+        duty_nearest((percent as u64).saturating_mul(MAX_COUNT as u64), 100)
+    }
+
+    #[doc(hidden)]
+    pub const fn __from_pulse(pulse_us: u32, frequency_hz: u32) -> Option<Self> {
+        let pulse = (pulse_us as u64).saturating_mul(frequency_hz as u64);
+        duty_nearest(pulse.saturating_mul(4096), 1_000_000)
+    }
+}
+
+/// Duty cycle of `num / den` counts rounded to the nearest value.
+///
+/// Returns `None` if `den` is zero or the result is out of range.
+const fn duty_nearest(num: u64, den: u64) -> Option<Duty> {
+    let count = match num.checked_add(den / 2) {
+        Some(num) => num.checked_div(den),
+        None => None,
+    };
+    match count {
+        Some(count) if count <= MAX_COUNT as u64 => Some(Duty(count as u16)),
+        _ => None,
+    }
+}
+
+/// Create a [`Duty`] constant from a literal, failing the build if it is out
+/// of range.
+///
+/// The following forms are supported:
+/// - `duty!(75%)`: percentage in the range `[0-100]`.
+/// - `duty!(1500 us @ 50 Hz)`: pulse width in microseconds at a PWM
+///   frequency. The nominal frequency is used, so the result does not account
+///   for the rounding of the prescale value.
+/// - `duty!(0x3FF)`: counter value in the range `[0-4095]`.
+///
+/// Values are rounded to the nearest counter value.
+///
+/// ```
+/// use pwm_pca9685::{duty, Duty};
+///
+/// const HALF: Duty = duty!(50%);
+/// assert_eq!(2048, HALF.count());
+/// assert_eq!(307, duty!(1500 us @ 50 Hz).count());
+/// assert_eq!(0x3FF, duty!(0x3FF).count());
+/// ```
+///
+/// Out-of-range values do not compile:
+///
+/// ```compile_fail
+/// use pwm_pca9685::duty;
+///
+/// let duty = duty!(101%);
+/// ```
+#[macro_export]
+macro_rules! duty {
+    ($percent:literal %) => {
+        $crate::duty!(@checked $crate::Duty::__from_percent($percent), "percentage out of range")
+    };
+    ($pulse:literal us @ $frequency:literal Hz) => {
+        $crate::duty!(@checked $crate::Duty::__from_pulse($pulse, $frequency), "pulse width out of range")
+    };
+    ($count:literal) => {
+        $crate::duty!(@checked $crate::Duty::new($count), "counter value out of range")
+    };
+    (@checked $duty:expr, $message:literal) => {{
+        const DUTY: $crate::Duty = match $duty {
+            Some(duty) => duty,
+            None => panic!($message),
+        };
+        DUTY
+    }};
+}
+
+impl TryFrom<u16> for Duty {
+    type Error = ();
+
+    /// Create a duty cycle from a counter value.
+    ///
+    /// Will return an empty error if the value is greater than 4095.
+    fn try_from(count: u16) -> Result<Self, Self::Error> {
+        Duty::new(count).ok_or(())
+    }
+}
+
+impl From<Duty> for u16 {
+    fn from(duty: Duty) -> Self {
+        duty.0
+    }
+}
+
+impl From<Duty> for ChannelOnOffControl {
+    /// Control values switching the channel on at the start of each PWM cycle.
+    ///
+    /// A duty cycle of 0 sets the channel to full-off.
+    fn from(duty: Duty) -> Self {
+        match duty.count() {
+            0 => ChannelOnOffControl::full_off(),
+            count => ChannelOnOffControl {
+                off: count,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Duty cycle as a percentage in the range `[0-100]`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Percent(u8);
+
+impl Percent {
+    /// Create a percentage.
+    ///
+    /// Returns `None` if `percent` is greater than 100.
+    pub const fn new(percent: u8) -> Option<Self> {
+        if percent > 100 {
+            None
+        } else {
+            Some(Percent(percent))
+        }
+    }
+
+    /// Get the percentage.
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+}
+
+/// Conversion into a duty cycle
+///
+/// This is implemented for:
+/// - `u16`: counter value in the range `[0-4095]`.
+/// - `u8`: value in the range `[0-255]` scaled to `[0-4095]`.
+/// - [`Percent`]: percentage scaled to `[0-4095]`.
+/// - [`Duty`].
+pub trait IntoDuty {
+    /// Convert into a duty cycle applying `rounding` if the value needs to be
+    /// scaled.
+    ///
+    /// Returns `None` if the value is out of range or cannot be converted
+    /// with the selected rounding.
+    fn into_duty(self, rounding: Rounding) -> Option<Duty>;
+
+    /// Convert into a duty cycle lowering values above the range to the
+    /// maximum duty cycle.
+    ///
+    /// Returns the duty cycle and whether the value has been clamped, or
+    /// `None` if the value cannot be converted.
+    fn into_duty_clamped(self, rounding: Rounding) -> Option<(Duty, bool)>
+    where
+        Self: Sized,
+    {
+        self.into_duty(rounding).map(|duty| (duty, false))
+    }
+}
+
+impl IntoDuty for Duty {
+    fn into_duty(self, _rounding: Rounding) -> Option<Duty> {
+        Some(self)
+    }
+}
+
+impl IntoDuty for u16 {
+    fn into_duty(self, _rounding: Rounding) -> Option<Duty> {
+        Duty::new(self)
+    }
+
+    fn into_duty_clamped(self, _rounding: Rounding) -> Option<(Duty, bool)> {
+        Some((Duty(self.min(MAX_COUNT)), self > MAX_COUNT))
+    }
+}
+
+impl IntoDuty for u8 {
+    fn into_duty(self, rounding: Rounding) -> Option<Duty> {
+        scale(u64::from(self), u64::from(u8::MAX), rounding)
+    }
+}
+
+impl IntoDuty for Percent {
+    fn into_duty(self, rounding: Rounding) -> Option<Duty> {
+        scale(u64::from(self.0), 100, rounding)
+    }
+}
+
+/// Scale `value` in the range `[0-max]` to the range `[0-4095]`.
+fn scale(value: u64, max: u64, rounding: Rounding) -> Option<Duty> {
+    let count = div_rounded(value.checked_mul(u64::from(MAX_COUNT))?, max, rounding)?;
+    Duty::new(u16::try_from(count).ok()?)
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Set the duty cycle of a channel.
+    ///
+    /// The duty cycle can be given in any representation implementing
+    /// [`IntoDuty`]. Scaled values are rounded as configured with
+    /// [`set_rounding()`](Pca9685::set_rounding).
+    ///
+    /// The channel is switched on at the start of each PWM cycle. See
+    /// [`set_channel_duty_off_only()`](Pca9685::set_channel_duty_off_only).
+    ///
+    /// The limits configured with
+    /// [`set_channel_limits()`](Pca9685::set_channel_limits) are applied to
+    /// the resulting counter value.
+    ///
+    /// Returns `Error::InvalidInputData` if the value cannot be converted.
+    /// A [`Duty`] is validated when it is created, so its conversion never fails.
+    /// Out-of-range values can be clamped instead. See
+    /// [`set_clamp_out_of_range()`](Pca9685::set_clamp_out_of_range).
+    pub async fn set_channel_duty(
+        &mut self,
+        channel: Channel,
+        duty: impl IntoDuty,
+    ) -> Result<(), Error<E>> {
+        let duty = self.convert_duty(duty)?;
+        match self.apply_limits(channel, duty.count()) {
+            Limited::Skip => Ok(()),
+            Limited::Uniform(count) => self.write_duty_count(channel, count).await,
+            Limited::PerChannel(counts) => {
+                for (channel, count) in CHANNELS.iter().zip(counts) {
+                    if let Some(count) = count {
+                        self.write_duty_count(*channel, count).await?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Set the duty cycle of a channel as a percentage in the range
+    /// `[0-100]`.
+    ///
+    /// This is equivalent to calling
+    /// [`set_channel_duty()`](Pca9685::set_channel_duty) with a [`Percent`].
+    ///
+    /// Returns `Error::InvalidInputData` if the percentage is greater than
+    /// 100, unless out-of-range values are clamped.
+    pub async fn set_channel_duty_percent(
+        &mut self,
+        channel: Channel,
+        percent: u8,
+    ) -> Result<(), Error<E>> {
+        self.set_channel_duty_fraction(channel, u64::from(percent), 100)
+            .await
+    }
+
+    /// Set the duty cycle of a channel as a fixed-point percentage in
+    /// hundredths of a percent in the range `[0-10000]`.
+    ///
+    /// For example, 1250 corresponds to 12.5%. Otherwise this behaves like
+    /// [`set_channel_duty_percent()`](Pca9685::set_channel_duty_percent).
+    pub async fn set_channel_duty_percent_hundredths(
+        &mut self,
+        channel: Channel,
+        hundredths: u16,
+    ) -> Result<(), Error<E>> {
+        self.set_channel_duty_fraction(channel, u64::from(hundredths), 10_000)
+            .await
+    }
+
+    /// Set the duty cycle of a channel from a value in the full `u16` range
+    /// `[0-65535]`.
+    ///
+    /// The value is scaled to `[0-4095]` and rounded as configured with
+    /// [`set_rounding()`](Pca9685::set_rounding), so that code written for
+    /// 16-bit PWM peripherals can be used unchanged. Otherwise this behaves
+    /// like [`set_channel_duty()`](Pca9685::set_channel_duty).
+    pub async fn set_channel_duty_u16(
+        &mut self,
+        channel: Channel,
+        duty: u16,
+    ) -> Result<(), Error<E>> {
+        self.set_channel_duty_fraction(channel, u64::from(duty), u64::from(u16::MAX))
+            .await
+    }
+
+    /// Set the duty cycle of a channel to `value / max` applying the
+    /// rounding and out-of-range policy.
+    async fn set_channel_duty_fraction(
+        &mut self,
+        channel: Channel,
+        value: u64,
+        max: u64,
+    ) -> Result<(), Error<E>> {
+        let value = if self.clamp_out_of_range && value > max {
+            self.clamped = true;
+            max
+        } else {
+            value
+        };
+        if value > max {
+            return Err(Error::InvalidInputData);
+        }
+        let duty = scale(value, max, self.rounding).ok_or(Error::InvalidInputData)?;
+        self.set_channel_duty(channel, duty).await
+    }
+
+    /// Set the duty cycle of a channel like
+    /// [`set_channel_duty()`](Pca9685::set_channel_duty) and get the number
+    /// of counts the output was previously active per PWM cycle.
+    ///
+    /// The previous value is in the range `[0-4096]`, where 4096 corresponds
+    /// to full-on. See [`ChannelOnOffControl::active_counts()`].
+    ///
+    /// Returns `Error::InvalidInputData` for `Channel::All` or if the value
+    /// cannot be converted.
+    pub async fn replace_channel_duty(
+        &mut self,
+        channel: Channel,
+        duty: impl IntoDuty,
+    ) -> Result<u16, Error<E>> {
+        let previous = self.outputs.get(channel).ok_or(Error::InvalidInputData)?;
+        self.set_channel_duty(channel, duty).await?;
+        Ok(previous.active_counts())
+    }
+
+    /// Set the duty cycle of a channel switching it on at the counter value
+    /// `phase` in the range `[0-4095]`.
+    ///
+    /// The `off` counter is computed as `(phase + duty) % 4096`, so pulses
+    /// extending past the end of the PWM cycle wrap around. A duty cycle of 0
+    /// sets the channel to full-off. See
+    /// [`ChannelOnOffControl::duty_at_phase()`].
+    ///
+    /// Returns `Error::InvalidInputData` if the duty cycle cannot be
+    /// converted or if `phase` is out of range.
+    pub async fn set_channel_duty_at_phase(
+        &mut self,
+        channel: Channel,
+        duty: impl IntoDuty,
+        phase: u16,
+    ) -> Result<(), Error<E>> {
+        let duty = self.convert_duty(duty)?;
+        let phase = self.check_count(phase)?;
+        let value = ChannelOnOffControl::duty_at_phase(duty.count(), phase)
+            .ok_or(Error::InvalidInputData)?;
+        self.set_channel_value(channel, value).await
+    }
+
+    /// Set a channel to output a pulse of `width_ticks` counter steps
+    /// starting at the counter value `phase_ticks` in the range `[0-4095]`.
+    ///
+    /// The `on` and `off` counters are computed from the phase and width, so
+    /// that pulses extending past the end of the PWM cycle wrap around. This
+    /// allows spreading the switching instants of several channels over the
+    /// PWM cycle to reduce current peaks. A width of 0 sets the channel to
+    /// full-off and a width of 4096 sets it to full-on.
+    ///
+    /// Returns `Error::InvalidInputData` if `phase_ticks` is greater than
+    /// 4095 or `width_ticks` is greater than 4096.
+    pub async fn set_channel_phase_width(
+        &mut self,
+        channel: Channel,
+        phase_ticks: u16,
+        width_ticks: u16,
+    ) -> Result<(), Error<E>> {
+        let value = if width_ticks == 4096 && phase_ticks <= 4095 {
+            ChannelOnOffControl::full_on()
+        } else {
+            ChannelOnOffControl::duty_at_phase(width_ticks, phase_ticks)
+                .ok_or(Error::InvalidInputData)?
+        };
+        self.set_channel_value(channel, value).await
+    }
+
+    /// Set the duty cycle of a channel used as a simple digital-to-analog
+    /// converter so that the filtered output reaches `millivolts`.
+    ///
+    /// This assumes the output switches between 0 V and `vref_mv`, for
+    /// example the supply voltage of the device with totem-pole outputs or
+    /// the pull-up voltage with open-drain outputs, and that it is smoothed
+    /// by an RC low-pass filter with a corner frequency well below the PWM
+    /// frequency feeding a high-impedance load. The average voltage is then
+    /// proportional to the duty cycle. Inverted output logic is not taken
+    /// into account.
+    ///
+    /// The duty cycle is rounded as configured with
+    /// [`set_rounding()`](Pca9685::set_rounding) and written like with
+    /// [`set_channel_duty()`](Pca9685::set_channel_duty).
+    ///
+    /// Returns `Error::InvalidInputData` if `vref_mv` is 0 or if
+    /// `millivolts` is greater than `vref_mv`, unless out-of-range values
+    /// are clamped.
+    pub async fn set_analog_output(
+        &mut self,
+        channel: Channel,
+        millivolts: u16,
+        vref_mv: u16,
+    ) -> Result<(), Error<E>> {
+        self.set_channel_duty_fraction(channel, u64::from(millivolts), u64::from(vref_mv))
+            .await
+    }
+
+    /// Convert a duty cycle applying the rounding and out-of-range policy.
+    pub(crate) fn convert_duty(&mut self, duty: impl IntoDuty) -> Result<Duty, Error<E>> {
+        if self.clamp_out_of_range {
+            let (duty, clamped) = duty
+                .into_duty_clamped(self.rounding)
+                .ok_or(Error::InvalidInputData)?;
+            self.clamped |= clamped;
+            Ok(duty)
+        } else {
+            duty.into_duty(self.rounding).ok_or(Error::InvalidInputData)
+        }
+    }
+
+    /// Select whether the duty cycle setters use the full-off and full-on
+    /// flags for the extreme values.
+    ///
+    /// When enabled, [`set_channel_duty()`](Pca9685::set_channel_duty) sets
+    /// the channel to full-off for a counter value of 0 and to full-on for a
+    /// counter value of 4095. This guarantees truly static output levels,
+    /// since with counters the output is still active for one step per cycle
+    /// at 0 and inactive for one step at 4095. Disabled by default.
+    ///
+    /// This does not have any effect on the hardware.
+    pub fn set_full_on_off_promotion(&mut self, enabled: bool) {
+        self.promote_full_on_off = enabled;
+    }
+
+    async fn write_duty_count(&mut self, channel: Channel, count: u16) -> Result<(), Error<E>> {
+        match count {
+            0 if self.promote_full_on_off => self.set_channel_full_off(channel).await,
+            MAX_COUNT if self.promote_full_on_off => {
+                self.set_channel_value(channel, ChannelOnOffControl::full_on())
+                    .await
+            }
+            _ => self.set_channel_duty_off_only(channel, count).await,
+        }
+    }
+
+    /// Change the duty cycle of a channel by `delta` counter steps.
+    ///
+    /// The current duty cycle is taken from the control values last written
+    /// by this driver. The result saturates at 0 and 4095 and is written as
+    /// with [`set_channel_duty()`](Pca9685::set_channel_duty), so a duty
+    /// cycle of 0 is only set to full-off if enabled with
+    /// [`set_full_on_off_promotion()`](Pca9685::set_full_on_off_promotion).
+    /// The channel limits are not applied. Nothing is written if the duty
+    /// cycle does not change.
+    ///
+    /// Returns `Error::InvalidInputData` for `Channel::All`.
+    pub async fn adjust_duty(&mut self, channel: Channel, delta: i16) -> Result<(), Error<E>> {
+        let current = self
+            .outputs
+            .get(channel)
+            .ok_or(Error::InvalidInputData)?
+            .active_counts();
+        let count = current
+            .min(MAX_COUNT)
+            .saturating_add_signed(delta)
+            .min(MAX_COUNT);
+        if count == current {
+            return Ok(());
+        }
+        self.write_duty_count(channel, count).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_ranges() {
+        assert_eq!(None, Duty::new(4096));
+        assert_eq!(Some(4095), Duty::new(4095).map(Duty::count));
+        assert_eq!(None, Percent::new(101));
+        assert_eq!(None, 4096_u16.into_duty(Rounding::Nearest));
+        assert_eq!(Err(()), Duty::try_from(4096));
+        assert_eq!(Ok(Duty::MAX), Duty::try_from(4095));
+        assert_eq!(Duty::MAX, Duty::saturating(u16::MAX));
+        assert_eq!(17, u16::from(Duty::saturating(17)));
+    }
+
+    #[test]
+    fn converts_duty_literals() {
+        assert_eq!(Duty(3071), duty!(75%));
+        assert_eq!(Duty(4095), duty!(100%));
+        assert_eq!(Duty(205), duty!(1000 us @ 50 Hz));
+        assert_eq!(Duty(0x3FF), duty!(0x3FF));
+        assert_eq!(None, Duty::__from_pulse(20_000, 50));
+    }
+
+    #[test]
+    fn scales_u8() {
+        assert_eq!(Duty::new(0), 0_u8.into_duty(Rounding::Nearest));
+        assert_eq!(Duty::new(4095), 255_u8.into_duty(Rounding::Nearest));
+        // 128 * 4095 / 255 = 2055.53
+        assert_eq!(Duty::new(2056), 128_u8.into_duty(Rounding::Nearest));
+        assert_eq!(Duty::new(2055), 128_u8.into_duty(Rounding::Down));
+        assert_eq!(None, 128_u8.into_duty(Rounding::Exact));
+    }
+
+    #[test]
+    fn scales_percent() {
+        let percent = |p| Percent::new(p).unwrap();
+        assert_eq!(Duty::new(4095), percent(100).into_duty(Rounding::Nearest));
+        // 50 * 4095 / 100 = 2047.5
+        assert_eq!(Duty::new(2048), percent(50).into_duty(Rounding::Nearest));
+        assert_eq!(Duty::new(2047), percent(50).into_duty(Rounding::Down));
+        assert_eq!(Duty::new(1638), percent(40).into_duty(Rounding::Exact));
+    }
+}
@@ -0,0 +1,161 @@
+//! Easing functions
+
+/// Fixed-point scale of the progress: 1.0 = 65536.
+pub(crate) const PROGRESS_SCALE: u32 = 1 << 16;
+
+const ONE: u64 = PROGRESS_SCALE as u64;
+
+/// Easing function shaping the progress of a transition
+///
+/// All functions start at 0 and end at the full value. The computations use
+/// integer arithmetic only.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Easing {
+    /// Constant speed (default).
+    #[default]
+    Linear,
+    /// Quadratic, accelerating from zero speed.
+    QuadIn,
+    /// Quadratic, decelerating to zero speed.
+    QuadOut,
+    /// Quadratic, accelerating until halfway, then decelerating.
+    QuadInOut,
+    /// Cubic, accelerating from zero speed.
+    CubicIn,
+    /// Cubic, decelerating to zero speed.
+    CubicOut,
+    /// Cubic, accelerating until halfway, then decelerating.
+    CubicInOut,
+    /// Sinusoidal, accelerating from zero speed.
+    SineIn,
+    /// Sinusoidal, decelerating to zero speed.
+    SineOut,
+    /// Sinusoidal, accelerating until halfway, then decelerating.
+    SineInOut,
+}
+
+impl Easing {
+    /// Get the eased elapsed time after `elapsed` out of a total `duration`
+    /// in any unit of time.
+    ///
+    /// The elapsed time is clamped to the duration.
+    pub fn apply(self, elapsed: u32, duration: u32) -> u32 {
+        u64::from(duration)
+            .checked_mul(self.progress(elapsed, duration))
+            .map(|d| d / ONE)
+            .and_then(|d| u32::try_from(d).ok())
+            .unwrap_or(duration)
+    }
+
+    /// Eased progress after `elapsed` out of a total `duration` scaled to
+    /// [`PROGRESS_SCALE`].
+    pub(crate) fn progress(self, elapsed: u32, duration: u32) -> u64 {
+        if elapsed >= duration {
+            return ONE;
+        }
+        let progress = u64::from(elapsed)
+            .checked_mul(ONE)
+            .and_then(|p| p.checked_div(u64::from(duration)))
+            .unwrap_or(ONE);
+        self.ease(progress).unwrap_or(progress).min(ONE)
+    }
+
+    /// Eased progress for a progress in the range `[0, ONE]`.
+    fn ease(self, p: u64) -> Option<u64> {
+        let q = ONE.checked_sub(p)?;
+        let half = ONE / 2;
+        match self {
+            Easing::Linear => Some(p),
+            Easing::QuadIn => Some(p.checked_mul(p)? / ONE),
+            Easing::QuadOut => ONE.checked_sub(q.checked_mul(q)? / ONE),
+            Easing::QuadInOut if p < half => Some(p.checked_mul(p)?.checked_mul(2)? / ONE),
+            Easing::QuadInOut => ONE.checked_sub(q.checked_mul(q)?.checked_mul(2)? / ONE),
+            Easing::CubicIn => Some(p.checked_pow(3)? / (ONE * ONE)),
+            Easing::CubicOut => ONE.checked_sub(q.checked_pow(3)? / (ONE * ONE)),
+            Easing::CubicInOut if p < half => Some(p.checked_pow(3)?.checked_mul(4)? / (ONE * ONE)),
+            Easing::CubicInOut => ONE.checked_sub(q.checked_pow(3)?.checked_mul(4)? / (ONE * ONE)),
+            Easing::SineIn => ONE.checked_sub(quarter_sine(q)?),
+            Easing::SineOut => quarter_sine(p),
+            Easing::SineInOut if p < half => {
+                let cos = quarter_sine(ONE.checked_sub(p.checked_mul(2)?)?)?;
+                Some(ONE.checked_sub(cos)? / 2)
+            }
+            Easing::SineInOut => {
+                let cos = quarter_sine(p.checked_mul(2)?.checked_sub(ONE)?)?;
+                Some(ONE.checked_add(cos)? / 2)
+            }
+        }
+    }
+}
+
+/// `sin(x * pi / 2)` for `x` in the range `[0, ONE]`, interpolated
+/// linearly from a table.
+fn quarter_sine(x: u64) -> Option<u64> {
+    let step = ONE / 64;
+    let index = usize::try_from(x / step).ok()?;
+    let rem = x % step;
+    let a = u64::from(*SINE.get(index)?);
+    let Some(b) = SINE.get(index.checked_add(1)?) else {
+        return Some(a);
+    };
+    let delta = u64::from(*b).checked_sub(a)?.checked_mul(rem)? / step;
+    a.checked_add(delta)
+}
+
+/// `sin(i * pi / 128)` for `i` in the range `[0, 64]` scaled to `ONE`.
+const SINE: [u32; 65] = [
+    0, 1608, 3216, 4821, 6424, 8022, 9616, 11204, 12785, 14359, 15924, 17479, 19024, 20557, 22078,
+    23586, 25080, 26558, 28020, 29466, 30893, 32303, 33692, 35062, 36410, 37736, 39040, 40320,
+    41576, 42806, 44011, 45190, 46341, 47464, 48559, 49624, 50660, 51665, 52639, 53581, 54491,
+    55368, 56212, 57022, 57798, 58538, 59244, 59914, 60547, 61145, 61705, 62228, 62714, 63162,
+    63572, 63944, 64277, 64571, 64827, 65043, 65220, 65358, 65457, 65516, 65536,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Easing; 10] = [
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::SineIn,
+        Easing::SineOut,
+        Easing::SineInOut,
+    ];
+
+    #[test]
+    fn starts_and_ends_at_endpoints() {
+        for easing in ALL {
+            assert_eq!(0, easing.apply(0, 1000), "{easing:?}");
+            assert_eq!(1000, easing.apply(1000, 1000), "{easing:?}");
+            assert_eq!(1000, easing.apply(2000, 1000), "{easing:?}");
+        }
+    }
+
+    #[test]
+    fn is_monotonic() {
+        for easing in ALL {
+            let mut last = 0;
+            for elapsed in 0..=100 {
+                let value = easing.apply(elapsed, 100);
+                assert!(value >= last, "{easing:?} at {elapsed}");
+                last = value;
+            }
+        }
+    }
+
+    #[test]
+    fn can_ease() {
+        assert_eq!(500, Easing::Linear.apply(500, 1000));
+        assert_eq!(250, Easing::QuadIn.apply(500, 1000));
+        assert_eq!(750, Easing::QuadOut.apply(500, 1000));
+        assert_eq!(125, Easing::CubicIn.apply(500, 1000));
+        assert_eq!(500, Easing::SineInOut.apply(500, 1000));
+        assert_eq!(707, Easing::SineOut.apply(500, 1000));
+    }
+}
@@ -0,0 +1,90 @@
+//! Failsafe timeout
+
+use crate::{Error, Frame, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FailsafeState {
+    frame: Frame,
+    timeout_ms: u32,
+    last_feed_ms: u32,
+    tripped: bool,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Enable the failsafe timeout.
+    ///
+    /// If [`feed_failsafe()`](Pca9685::feed_failsafe) is not called for longer
+    /// than `timeout_ms` milliseconds, the next call to [`tick()`](Pca9685::tick)
+    /// writes `safe_frame` to all channels. This can be used to turn all outputs
+    /// off or to send servos to a neutral position if the application stops
+    /// sending updates, for example because a remote-control link dropped.
+    /// Any pending chunked commit, slow blinking, blink code, waveform
+    /// playback and profile crossfade are stopped when the timeout expires.
+    ///
+    /// `now_ms` is the current time in milliseconds from a monotonic clock.
+    /// The clock is allowed to wrap around.
+    pub fn enable_failsafe(&mut self, safe_frame: Frame, timeout_ms: u32, now_ms: u32) {
+        self.failsafe = Some(FailsafeState {
+            frame: safe_frame,
+            timeout_ms,
+            last_feed_ms: now_ms,
+            tripped: false,
+        });
+    }
+
+    /// Disable the failsafe timeout.
+    pub fn disable_failsafe(&mut self) {
+        self.failsafe = None;
+    }
+
+    /// Signal that the application is still alive and restart the failsafe timeout.
+    ///
+    /// If the failsafe had been triggered, it is rearmed. The channels keep
+    /// the safe frame values until they are written again.
+    pub fn feed_failsafe(&mut self, now_ms: u32) {
+        if let Some(failsafe) = &mut self.failsafe {
+            failsafe.last_feed_ms = now_ms;
+            failsafe.tripped = false;
+        }
+    }
+
+    /// Returns whether the safe frame has been written because the failsafe
+    /// timeout expired.
+    pub fn is_failsafe_triggered(&self) -> bool {
+        self.failsafe.is_some_and(|f| f.tripped)
+    }
+
+    pub(crate) async fn service_failsafe(&mut self, now_ms: u32) -> Result<(), Error<E>> {
+        if let Some(failsafe) = self.failsafe {
+            let elapsed = now_ms.wrapping_sub(failsafe.last_feed_ms);
+            if !failsafe.tripped && elapsed >= failsafe.timeout_ms {
+                self.write_all_channels(failsafe.frame.channels()).await?;
+                if let Some(f) = &mut self.failsafe {
+                    f.tripped = true;
+                }
+                self.chunked_commit = None;
+                self.slow_blink = [None; 16];
+                self.blink_code = None;
+                self.waveforms = [None; 16];
+                self.stop_profile_fade();
+            }
+        }
+        Ok(())
+    }
+}
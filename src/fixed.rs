@@ -0,0 +1,137 @@
+//! Fixed-point numbers
+//!
+//! All conversions in this driver use integer arithmetic only, so that no
+//! soft-float code is pulled in on targets without an FPU. [`Q16`] allows
+//! passing and getting fractional values without floating point.
+
+use crate::{math::div_rounded, Duty, IntoDuty, Pca9685, Rounding};
+
+/// Number of fractional bits.
+const FRAC_BITS: u32 = 16;
+
+/// Unsigned fixed-point number with 16 integer and 16 fractional bits
+/// (Q16.16)
+///
+/// As a duty cycle, it is interpreted as a fraction of the full PWM cycle in
+/// the range `[0-1]`.
+///
+/// ```
+/// use pwm_pca9685::Q16;
+///
+/// let three_quarters = Q16::from_ratio(3, 4).unwrap();
+/// assert_eq!(0xC000, three_quarters.to_bits());
+/// assert_eq!(Some(750), three_quarters.mul_int(1000));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Q16(u32);
+
+impl Q16 {
+    /// Zero.
+    pub const ZERO: Q16 = Q16(0);
+    /// One.
+    pub const ONE: Q16 = Q16(1 << FRAC_BITS);
+
+    /// Create a number from its raw bits.
+    pub const fn from_bits(bits: u32) -> Self {
+        Q16(bits)
+    }
+
+    /// Get the raw bits.
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Create a number from an integer.
+    pub const fn from_int(value: u16) -> Self {
+        Q16((value as u32) << FRAC_BITS)
+    }
+
+    /// Create a number from the ratio `num / den` rounded to the nearest
+    /// representable value.
+    ///
+    /// Returns `None` if `den` is zero or the result is out of range.
+    pub const fn from_ratio(num: u32, den: u32) -> Option<Self> {
+        let scaled = (num as u64) << FRAC_BITS;
+        let Some(bits) = scaled
+            .saturating_add(den as u64 / 2)
+            .checked_div(den as u64)
+        else {
+            return None;
+        };
+        if bits > u32::MAX as u64 {
+            None
+        } else {
+            Some(Q16(bits as u32))
+        }
+    }
+
+    /// Get the integer part.
+    pub const fn int_part(self) -> u16 {
+        (self.0 >> FRAC_BITS) as u16
+    }
+
+    /// Multiply by an integer rounding the result to the nearest integer.
+    ///
+    /// Returns `None` if the result is out of range.
+    pub fn mul_int(self, value: u32) -> Option<u32> {
+        let product = u64::from(self.0).checked_mul(u64::from(value))?;
+        let result = div_rounded(product, 1 << FRAC_BITS, Rounding::Nearest)?;
+        u32::try_from(result).ok()
+    }
+}
+
+impl IntoDuty for Q16 {
+    /// Fraction of the PWM cycle scaled to `[0-4095]`.
+    fn into_duty(self, rounding: Rounding) -> Option<Duty> {
+        if self > Q16::ONE {
+            return None;
+        }
+        let count = div_rounded(
+            u64::from(self.0).checked_mul(4095)?,
+            1 << FRAC_BITS,
+            rounding,
+        )?;
+        Duty::new(u16::try_from(count).ok()?)
+    }
+}
+
+impl<I2C> Pca9685<I2C> {
+    /// Get the PWM frequency in Hz resulting from the prescale value last
+    /// written by this driver with 16 fractional bits.
+    ///
+    /// Returns `None` if the external clock is in use, since its frequency
+    /// is unknown. See [`frequency()`](Pca9685::frequency).
+    ///
+    /// This does not read the device.
+    pub fn frequency_q16(&self) -> Option<Q16> {
+        self.frequency()?;
+        let den = u32::from(self.prescale).checked_add(1)?.checked_mul(4096)?;
+        Q16::from_ratio(self.oscillator_hz(), den)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_create_from_ratio() {
+        assert_eq!(Some(Q16::ONE), Q16::from_ratio(7, 7));
+        assert_eq!(Some(Q16::from_bits(0x5555)), Q16::from_ratio(1, 3));
+        assert_eq!(Some(Q16::from_bits(0xAAAB)), Q16::from_ratio(2, 3));
+        assert_eq!(None, Q16::from_ratio(1, 0));
+        assert_eq!(None, Q16::from_ratio(u32::MAX, 1));
+        assert_eq!(3, Q16::from_ratio(7, 2).unwrap().int_part());
+    }
+
+    #[test]
+    fn can_convert_to_duty() {
+        let duty = |q: Q16| q.into_duty(Rounding::Nearest).map(Duty::count);
+        assert_eq!(Some(4095), duty(Q16::ONE));
+        assert_eq!(Some(0), duty(Q16::ZERO));
+        // 0.5 * 4095 = 2047.5
+        assert_eq!(Some(2048), duty(Q16::from_bits(0x8000)));
+        assert_eq!(None, duty(Q16::from_bits(0x1_0001)));
+        assert_eq!(Some(4094), Q16::from_int(2).mul_int(2047));
+    }
+}
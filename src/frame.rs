@@ -0,0 +1,245 @@
+//! Output state of all channels
+
+use crate::{regs, Channel, ChannelOnOffControl, Duty};
+
+/// PWM control values for all 16 channels.
+///
+/// The index of each value corresponds to the channel: 0-15.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Frame {
+    channels: [ChannelOnOffControl; 16],
+}
+
+impl Frame {
+    /// Create a frame from the control values of each channel.
+    pub const fn new(channels: [ChannelOnOffControl; 16]) -> Self {
+        Frame { channels }
+    }
+
+    /// Create a frame where all channels are full-off.
+    ///
+    /// This corresponds to the power-up state of the device.
+    pub const fn all_off() -> Self {
+        Frame {
+            channels: [ChannelOnOffControl::full_off(); 16],
+        }
+    }
+
+    /// Get the control values of all channels.
+    pub fn channels(&self) -> &[ChannelOnOffControl; 16] {
+        &self.channels
+    }
+
+    /// Get the control values of a channel.
+    ///
+    /// Returns `None` for `Channel::All`.
+    pub fn get(&self, channel: Channel) -> Option<ChannelOnOffControl> {
+        channel.index().and_then(|i| self.channels.get(i)).copied()
+    }
+
+    /// Set the control values of a channel.
+    ///
+    /// `Channel::All` sets the values of every channel.
+    pub fn set(&mut self, channel: Channel, value: ChannelOnOffControl) {
+        match channel.index() {
+            Some(i) => {
+                if let Some(c) = self.channels.get_mut(i) {
+                    *c = value;
+                }
+            }
+            None => self.channels = [value; 16],
+        }
+    }
+
+    /// Get the frame with the control values of a channel changed.
+    ///
+    /// `Channel::All` sets the values of every channel.
+    pub fn with(mut self, channel: Channel, value: ChannelOnOffControl) -> Self {
+        self.set(channel, value);
+        self
+    }
+
+    /// Set the duty cycle of a channel switching it on at the start of each
+    /// PWM cycle.
+    ///
+    /// `Channel::All` sets the duty cycle of every channel. A duty cycle of 0
+    /// sets the channel to full-off.
+    pub fn set_duty(&mut self, channel: Channel, duty: Duty) {
+        self.set(channel, ChannelOnOffControl::from(duty));
+    }
+
+    /// Set the `on` and `off` counters of a channel clearing the full-on and
+    /// full-off flags.
+    ///
+    /// `Channel::All` sets the counters of every channel.
+    ///
+    /// Returns `false` without changing the frame if a value is greater than
+    /// 4095.
+    pub fn set_on_off(&mut self, channel: Channel, on: u16, off: u16) -> bool {
+        match ChannelOnOffControl::try_from((on, off)) {
+            Ok(value) => {
+                self.set(channel, value);
+                true
+            }
+            Err(()) => false,
+        }
+    }
+
+    /// Set a channel to full-on.
+    ///
+    /// `Channel::All` sets every channel to full-on.
+    pub fn set_full_on(&mut self, channel: Channel) {
+        self.set(channel, ChannelOnOffControl::full_on());
+    }
+
+    /// Set a channel to full-off.
+    ///
+    /// `Channel::All` sets every channel to full-off.
+    pub fn set_full_off(&mut self, channel: Channel) {
+        self.set(channel, ChannelOnOffControl::full_off());
+    }
+
+    /// Encode the contents of the PWM control registers of all channels in
+    /// register order, starting with `LED0_ON_L`.
+    pub fn encode_into(&self, buffer: &mut [u8; 64]) {
+        for (chunk, value) in buffer.chunks_exact_mut(4).zip(self.channels) {
+            chunk.copy_from_slice(&value.encode());
+        }
+    }
+
+    /// Modify the control values of a channel or of every channel for
+    /// `Channel::All`.
+    pub(crate) fn update(&mut self, channel: Channel, f: impl Fn(&mut ChannelOnOffControl)) {
+        match channel.index() {
+            Some(i) => self.channels.get_mut(i).into_iter().for_each(f),
+            None => self.channels.iter_mut().for_each(f),
+        }
+    }
+}
+
+/// Frame encoded as a complete I2C write to the PWM control registers
+///
+/// Encoding frames in advance reduces the work needed to write them. See:
+/// [`FramePlayer`](crate::FramePlayer).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EncodedFrame {
+    data: [u8; 65],
+}
+
+impl EncodedFrame {
+    /// Encode a frame.
+    pub fn new(frame: &Frame) -> Self {
+        let mut payload = [0; 64];
+        frame.encode_into(&mut payload);
+        let mut data = [regs::C0_ON_L; 65];
+        if let Some((_, rest)) = data.split_first_mut() {
+            rest.copy_from_slice(&payload);
+        }
+        EncodedFrame { data }
+    }
+
+    /// Get the bytes of the I2C write, starting with the register address.
+    pub fn as_bytes(&self) -> &[u8; 65] {
+        &self.data
+    }
+
+    /// Decode the frame.
+    pub fn frame(&self) -> Frame {
+        let mut frame = Frame::default();
+        let payload = self.data.get(1..).unwrap_or_default();
+        for (value, chunk) in frame.channels.iter_mut().zip(payload.chunks_exact(4)) {
+            let mut registers = [0; 4];
+            registers.copy_from_slice(chunk);
+            *value = ChannelOnOffControl::decode(registers);
+        }
+        frame
+    }
+}
+
+impl From<&Frame> for EncodedFrame {
+    fn from(frame: &Frame) -> Self {
+        EncodedFrame::new(frame)
+    }
+}
+
+impl From<[ChannelOnOffControl; 16]> for Frame {
+    fn from(channels: [ChannelOnOffControl; 16]) -> Self {
+        Frame::new(channels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_off_sets_full_off() {
+        let frame = Frame::all_off();
+        assert!(frame.channels().iter().all(|c| c.full_off && !c.full_on));
+    }
+
+    #[test]
+    fn can_set_and_get_channel() {
+        let mut frame = Frame::default();
+        let value = ChannelOnOffControl {
+            on: 1,
+            off: 2,
+            ..Default::default()
+        };
+        frame.set(Channel::C3, value);
+        assert_eq!(Some(value), frame.get(Channel::C3));
+        assert_eq!(Some(ChannelOnOffControl::default()), frame.get(Channel::C2));
+        assert_eq!(None, frame.get(Channel::All));
+    }
+
+    #[test]
+    fn setting_all_sets_every_channel() {
+        let mut frame = Frame::default();
+        let value = ChannelOnOffControl {
+            full_on: true,
+            ..Default::default()
+        };
+        frame.set(Channel::All, value);
+        assert_eq!(&[value; 16], frame.channels());
+    }
+
+    #[test]
+    fn can_use_setters() {
+        let mut frame = Frame::default().with(Channel::C0, ChannelOnOffControl::full_on());
+        frame.set_duty(Channel::C1, Duty::MAX);
+        assert!(frame.set_on_off(Channel::C2, 10, 20));
+        assert!(!frame.set_on_off(Channel::C2, 10, 4096));
+        frame.set_full_off(Channel::C3);
+        assert_eq!(Some(ChannelOnOffControl::full_on()), frame.get(Channel::C0));
+        assert_eq!(Some(4095), frame.get(Channel::C1).map(|c| c.off));
+        assert_eq!(
+            Ok(frame.channels()[2]),
+            ChannelOnOffControl::try_from((10, 20))
+        );
+        assert_eq!(
+            Some(ChannelOnOffControl::full_off()),
+            frame.get(Channel::C3)
+        );
+        frame.set_full_on(Channel::All);
+        assert_eq!(&[ChannelOnOffControl::full_on(); 16], frame.channels());
+    }
+
+    #[test]
+    fn can_encode_and_decode() {
+        let mut frame = Frame::all_off();
+        let value = ChannelOnOffControl {
+            on: 0x123,
+            off: 0x456,
+            full_on: true,
+            full_off: false,
+        };
+        frame.set(Channel::C15, value);
+        let encoded = EncodedFrame::new(&frame);
+        assert_eq!(regs::C0_ON_L, encoded.as_bytes()[0]);
+        assert_eq!([0x23, 0x11, 0x56, 0x04], encoded.as_bytes()[61..]);
+        assert_eq!(frame, encoded.frame());
+        let mut buffer = [0; 64];
+        frame.encode_into(&mut buffer);
+        assert_eq!(encoded.as_bytes()[1..], buffer);
+    }
+}
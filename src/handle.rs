@@ -0,0 +1,153 @@
+//! Handles for single channels
+
+use crate::{Channel, ChannelOnOffControl, Error, IntoDuty, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Number of counter steps in a PWM cycle.
+const CYCLE: u16 = 4096;
+
+/// Handle to a single channel of the device
+///
+/// The handle borrows the driver, so that other drivers for LEDs or motors
+/// can consume a channel generically. Without the `async` feature it
+/// implements [`embedded_hal::pwm::SetDutyCycle`]. With the `async` feature
+/// it implements `AsyncPwmChannel` instead.
+///
+/// In both cases the maximum duty cycle is 4096, which sets the channel to
+/// full-on, and a duty cycle of 0 sets it to full-off.
+///
+/// Created with [`channel()`](Pca9685::channel).
+#[derive(Debug)]
+pub struct ChannelHandle<'a, I2C> {
+    pwm: &'a mut Pca9685<I2C>,
+    channel: Channel,
+}
+
+/// Asynchronous PWM channel
+///
+/// This is the asynchronous equivalent of
+/// [`embedded_hal::pwm::SetDutyCycle`].
+#[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
+pub trait AsyncPwmChannel {
+    /// Error type.
+    type Error;
+
+    /// Get the maximum duty cycle value, which corresponds to 100%.
+    fn max_duty_cycle(&self) -> u16;
+
+    /// Set the duty cycle in the range `[0, max_duty_cycle()]`.
+    async fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error>;
+}
+
+impl<I2C> Pca9685<I2C> {
+    /// Get a handle to a single channel.
+    ///
+    /// `Channel::All` controls all channels at once.
+    pub fn channel(&mut self, channel: Channel) -> ChannelHandle<'_, I2C> {
+        ChannelHandle { pwm: self, channel }
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "ChannelHandle",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<'a, I2C, E> ChannelHandle<'a, I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Get the channel controlled by this handle.
+    pub fn channel_id(&self) -> Channel {
+        self.channel
+    }
+
+    /// Set the duty cycle. See: [`set_channel_duty()`](Pca9685::set_channel_duty).
+    pub async fn set_duty(&mut self, duty: impl IntoDuty) -> Result<(), Error<E>> {
+        self.pwm.set_channel_duty(self.channel, duty).await
+    }
+
+    /// Set the _on_ and _off_ counters. See: [`set_channel_on_off()`](Pca9685::set_channel_on_off).
+    pub async fn set_on_off(&mut self, on: u16, off: u16) -> Result<(), Error<E>> {
+        self.pwm.set_channel_on_off(self.channel, on, off).await
+    }
+
+    /// Set the channel to full-on.
+    ///
+    /// All four PWM control registers are written, so that a full-off bit set
+    /// before, which would take precedence, is cleared.
+    pub async fn set_full_on(&mut self) -> Result<(), Error<E>> {
+        self.pwm
+            .set_channel_value(self.channel, ChannelOnOffControl::full_on())
+            .await
+    }
+
+    /// Set the channel to full-off.
+    pub async fn set_full_off(&mut self) -> Result<(), Error<E>> {
+        self.pwm.set_channel_full_off(self.channel).await
+    }
+
+    async fn write_duty_cycle(&mut self, duty: u16) -> Result<(), Error<E>> {
+        match duty {
+            0 => self.set_full_off().await,
+            CYCLE => self.set_full_on().await,
+            duty if duty < CYCLE => self.set_duty(duty).await,
+            _ => Err(Error::InvalidInputData),
+        }
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<E: core::fmt::Debug> embedded_hal::pwm::Error for Error<E> {
+    fn kind(&self) -> embedded_hal::pwm::ErrorKind {
+        embedded_hal::pwm::ErrorKind::Other
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<I2C, E> embedded_hal::pwm::ErrorType for ChannelHandle<'_, I2C>
+where
+    I2C: I2c<Error = E>,
+    E: core::fmt::Debug,
+{
+    type Error = Error<E>;
+}
+
+#[cfg(not(feature = "async"))]
+impl<I2C, E> embedded_hal::pwm::SetDutyCycle for ChannelHandle<'_, I2C>
+where
+    I2C: I2c<Error = E>,
+    E: core::fmt::Debug,
+{
+    fn max_duty_cycle(&self) -> u16 {
+        CYCLE
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.write_duty_cycle(duty)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C, E> AsyncPwmChannel for ChannelHandle<'_, I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    type Error = Error<E>;
+
+    fn max_duty_cycle(&self) -> u16 {
+        CYCLE
+    }
+
+    async fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.write_duty_cycle(duty).await
+    }
+}
@@ -0,0 +1,100 @@
+//! Per-channel debug labels
+
+use crate::{Channel, Error, Pca9685};
+use core::fmt::{Display, Formatter};
+
+/// Channel displayed together with its label, if any
+///
+/// For example: `channel 3 (left aileron)`.
+/// See: [`channel_name()`](Pca9685::channel_name).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChannelName {
+    channel: Channel,
+    label: Option<&'static str>,
+}
+
+impl Display for ChannelName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.label {
+            Some(label) => write!(f, "{} ({})", self.channel, label),
+            None => write!(f, "{}", self.channel),
+        }
+    }
+}
+
+/// Error displayed with the labels of the channels it refers to
+///
+/// See: [`describe_error()`](Pca9685::describe_error).
+#[derive(Debug, Clone, Copy)]
+pub struct LabeledError<'a, E> {
+    error: &'a Error<E>,
+    labels: &'a [Option<&'static str>; 16],
+}
+
+impl<E: Display> Display for LabeledError<'_, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.error {
+            Error::ChannelModeMismatch(channel) => {
+                let name = ChannelName {
+                    channel: *channel,
+                    label: label_of(self.labels, *channel),
+                };
+                write!(f, "Operation does not match the mode of {}", name)
+            }
+            error => write!(f, "{}", error),
+        }
+    }
+}
+
+fn label_of(labels: &[Option<&'static str>; 16], channel: Channel) -> Option<&'static str> {
+    channel
+        .index()
+        .and_then(|i| labels.get(i))
+        .copied()
+        .flatten()
+}
+
+impl<I2C> Pca9685<I2C> {
+    /// Attach a label like `"status LED"` to a channel for diagnostics or
+    /// remove it with `None`.
+    ///
+    /// Labels are included when displaying channels with
+    /// [`channel_name()`](Pca9685::channel_name) and errors with
+    /// [`describe_error()`](Pca9685::describe_error).
+    ///
+    /// `Channel::All` sets the label of every channel.
+    ///
+    /// This does not have any effect on the hardware.
+    pub fn set_channel_label(&mut self, channel: Channel, label: Option<&'static str>) {
+        for (i, slot) in self.labels.iter_mut().enumerate() {
+            if channel.index().is_some_and(|index| index != i) {
+                continue;
+            }
+            *slot = label;
+        }
+    }
+
+    /// Get the label of a channel.
+    ///
+    /// Returns `None` for `Channel::All` or if the channel has no label.
+    pub fn channel_label(&self, channel: Channel) -> Option<&'static str> {
+        label_of(&self.labels, channel)
+    }
+
+    /// Get a displayable name of a channel including its label.
+    pub fn channel_name(&self, channel: Channel) -> ChannelName {
+        ChannelName {
+            channel,
+            label: self.channel_label(channel),
+        }
+    }
+
+    /// Get a displayable version of an error including the labels of the
+    /// channels it refers to.
+    pub fn describe_error<'a, E>(&'a self, error: &'a Error<E>) -> LabeledError<'a, E> {
+        LabeledError {
+            error,
+            labels: &self.labels,
+        }
+    }
+}
@@ -5,22 +5,116 @@
 //!
 //! This driver allows you to:
 //! - Enable/disable the device. See: [`enable()`](Pca9685::enable).
+//! - Enable the device bringing channels up in groups to limit inrush current. See: [`enable_sequenced()`](Pca9685::enable_sequenced).
 //! - Set the _on_ and _off_ counter for a channel or all of them. See: [`set_channel_on()`](Pca9685::set_channel_on).
 //! - Set the _on_ and _off_ counters for a channel or all of them at once. See: [`set_channel_on_off()`](Pca9685::set_channel_on_off).
+//! - Set the duty cycle of a channel writing only the _off_ counter. See: [`set_channel_duty_off_only()`](Pca9685::set_channel_duty_off_only).
+//! - Set the duty cycle of a channel as a counter value, scaled `u8` or percentage. See: [`set_channel_duty()`](Pca9685::set_channel_duty).
+//! - Set the duty cycle of a channel as a percentage, also in hundredths of a percent. See: [`set_channel_duty_percent()`](Pca9685::set_channel_duty_percent).
+//! - Set the duty cycle of a channel from a 16-bit value. See: [`set_channel_duty_u16()`](Pca9685::set_channel_duty_u16).
+//! - Create duty cycle constants checked at compile time. See: [`duty!`].
+//! - Set the duty cycle of a channel starting at a phase offset. See: [`set_channel_duty_at_phase()`](Pca9685::set_channel_duty_at_phase).
+//! - Set a channel from a phase offset and pulse width in counter steps. See: [`set_channel_phase_width()`](Pca9685::set_channel_phase_width).
+//! - Use channels as simple DACs with an RC filter. See: [`set_analog_output()`](Pca9685::set_analog_output).
+//! - Use the full-on and full-off flags for the extreme duty cycles. See: [`set_full_on_off_promotion()`](Pca9685::set_full_on_off_promotion).
+//! - Increase or decrease the duty cycle of a channel. See: [`adjust_duty()`](Pca9685::adjust_duty).
+//! - Ignore small changes and clamp the values of a channel. See: [`set_channel_limits()`](Pca9685::set_channel_limits).
+//! - Clamp out-of-range counter values instead of rejecting them. See: [`set_clamp_out_of_range()`](Pca9685::set_clamp_out_of_range).
+//! - Set the brightness of a channel with a perceptual curve like CIE 1931 or sRGB. See: [`set_channel_brightness()`](Pca9685::set_channel_brightness).
+//! - Set the brightness of all channels at once from 8-bit levels. See: [`set_all_from_u8()`](Pca9685::set_all_from_u8).
+//! - Pass single channels to other drivers through `SetDutyCycle` or `AsyncPwmChannel` handles. See: [`channel()`](Pca9685::channel).
+//! - Share the device between threads (`std` only). See: `SharedPca9685`.
+//! - Run fades and timeouts on a background thread (`std` only). See: `Pca9685Service`.
+//! - Modify the values of a channel with a closure. See: [`update_channel()`](Pca9685::update_channel).
+//! - Set the values of a channel getting the previous ones back. See: [`replace_channel()`](Pca9685::replace_channel).
 //! - Set a channel to be always on or off. See: [`set_channel_full_on()`](Pca9685::set_channel_full_on).
 //! - Set the _on_ and _off_ counters for each channel at once. See: [`set_all_on_off()`](Pca9685::set_all_on_off).
 //! - Set the _on_ and _off_ counters for a subset of channels at once. See for example: [`set_first_4_channels_on_off()`](Pca9685::set_first_4_channels_on_off).
+//! - Set the _on_ and _off_ counters of consecutive channels from slices or iterators. See: [`set_channels_on_off_from_slices()`](Pca9685::set_channels_on_off_from_slices).
 //! - Set the _on_ and _off_ counters **and** the always-on/always-off flags for each channel at once. See: [`set_all_channels()`](Pca9685::set_all_channels).
+//! - Write a [`Frame`] with the state of all channels in one transaction. See: [`write_frame()`](Pca9685::write_frame).
+//! - Write only the channels which differ from the previous frame. See: [`write_frame_diff()`](Pca9685::write_frame_diff).
+//! - Interpolate between two frames without floating point. See: [`Frame::lerp()`].
+//! - Set the duty cycle or full-off state of an arbitrary set of channels. See: [`set_channels_duty()`](Pca9685::set_channels_duty).
+//! - Set the _on_ and _off_ counters of a range of consecutive channels in one transaction. See: [`set_channel_range_on_off()`](Pca9685::set_channel_range_on_off).
+//! - Set all channels always off in a single transaction. See: [`set_all_full_off()`](Pca9685::set_all_full_off).
+//! - Toggle a channel between full-off and its last configuration. See: [`toggle_channel()`](Pca9685::toggle_channel).
+//! - Read the PWM control registers of all channels. See: [`read_all_channels()`](Pca9685::read_all_channels).
+//! - Load the channel values read from the device into the driver cache. See: [`sync_outputs_from_device()`](Pca9685::sync_outputs_from_device).
+//! - Refresh the cached mode registers and prescale value after another software configured the device. See: [`sync_from_device()`](Pca9685::sync_from_device).
+//! - Detect resets or other I2C masters by comparing the mode registers with the cached configuration. See: [`verify_config()`](Pca9685::verify_config).
+//! - Read and write any register directly. See: [`write_register_raw()`](Pca9685::write_register_raw).
+//! - Check whether the controller is asleep. See: [`is_sleeping()`](Pca9685::is_sleeping).
+//! - Write a frame updating each channel atomically as soon as its registers arrive. See: [`commit_on_ack()`](Pca9685::commit_on_ack).
 //! - Set the prescale value. See: [`set_prescale()`](Pca9685::set_prescale).
+//! - Set the PWM period instead of the prescale value. See: [`set_period_us()`](Pca9685::set_period_us).
+//! - Set the PWM frequency instead of the prescale value. See: [`set_frequency_hz()`](Pca9685::set_frequency_hz).
+//! - Create an instance and configure the PWM frequency in one go. See: [`new_with_frequency()`](Pca9685::new_with_frequency).
+//! - Set the prescale value waiting for the oscillator to stabilize. See: [`set_prescale_with_delay()`](Pca9685::set_prescale_with_delay).
+//! - Get the cached prescale value or read it from the device. See: [`get_prescale()`](Pca9685::get_prescale) and [`read_prescale()`](Pca9685::read_prescale).
+//! - Correct the deviation of the internal oscillator. See: [`set_oscillator_correction_ppm()`](Pca9685::set_oscillator_correction_ppm).
+//! - Get the effective PWM frequency. See: [`frequency()`](Pca9685::frequency).
+//! - Convert between counter steps and microseconds. See: [`us_to_ticks()`](Pca9685::us_to_ticks) and [`ticks_to_us()`](Pca9685::ticks_to_us).
+//! - Pass `fugit` rates and durations to timing setters (`fugit` only). See: `set_frequency()`, `set_period()` and `set_servo_pulse()`.
+//! - Calculate prescale values and the frequencies achieved with them. See: [`calc`].
+//! - Use named constants for the counter limits, full-on/off bits and common prescale values. See: [`consts`].
+//! - Pass duty cycles and get the frequency as fixed-point numbers without floating point. See: [`Q16`].
+//! - Use presets for common frequencies. See: [`Prescale`].
+//! - Sweep the PWM frequency through a range of prescale values. See: [`sweep_frequency()`](Pca9685::sweep_frequency).
 //! - Select the output logic state direct or inverted. See: [`set_output_logic_state()`](Pca9685::set_output_logic_state).
 //! - Set when the outputs change. See: [`set_output_change_behavior()`](Pca9685::set_output_change_behavior).
 //! - Set the output driver configuration. See: [`set_output_driver()`](Pca9685::set_output_driver).
 //! - Set the output value when outputs are disabled. See: [`set_disabled_output_value()`](Pca9685::set_disabled_output_value)
+//! - Configure the outputs for the connected circuit in one call. See: [`set_output_topology()`](Pca9685::set_output_topology).
+//! - Dim all channels with a PWM signal on the `OE` pin. See: [`enable_master_dimming()`](Pca9685::enable_master_dimming).
+//! - Apply a full configuration writing only the registers which changed. See: [`apply_config()`](Pca9685::apply_config).
+//! - Store output profiles and switch between them, optionally with a crossfade. See: [`switch_profile()`](Pca9685::switch_profile).
+//! - Store and restore the driver state as bytes. See: [`DeviceSnapshot`].
 //! - Select the EXTCLK pin as clock source. See: [`use_external_clock()`](Pca9685::use_external_clock).
 //! - Enable/disable a programmable address. See: [`enable_programmable_address()`](Pca9685::enable_programmable_address).
 //! - Set a programmable address. See: [`set_programmable_address()`](Pca9685::set_programmable_address).
+//! - Keep the changes made to the mode registers by other controllers. See: [`set_multi_master()`](Pca9685::set_multi_master).
+//! - Write to another address temporarily. See: [`at_address()`](Pca9685::at_address).
 //! - Change the address used by the driver. See: [`set_address()`](Pca9685::set_address).
 //! - Restart keeping the PWM register contents. See: [`enable_restart_and_disable()`](Pca9685::enable_restart_and_disable).
+//! - Check whether a restart is pending after waking the device. See: [`is_restart_pending()`](Pca9685::is_restart_pending).
+//! - Restart without a delay implementation by polling with a timestamp. See: [`restart_poll()`](Pca9685::restart_poll).
+//! - Suspend the controller and later restore the exact output state. See: [`suspend()`](Pca9685::suspend).
+//! - Display a level as a bar graph on consecutive channels. See: [`set_bar_graph()`](Pca9685::set_bar_graph).
+//! - Set the pulse width or angle of a servo. See: [`set_servo_angle()`](Pca9685::set_servo_angle).
+//! - Find the pulse width endpoints of a servo step by step. See: [`CalibrationSession`].
+//! - Switch servos off after holding a position for some time. See: [`set_servo_idle_timeout()`](Pca9685::set_servo_idle_timeout).
+//! - Control DC motors through an H-bridge. See: [`DcMotor`].
+//! - Use channel map presets for popular boards. See: [`boards`].
+//! - Split writes for I2C implementations with small buffers. See: [`set_max_write_len()`](Pca9685::set_max_write_len).
+//! - Write registers one at a time for devices mishandling auto-increment. See: [`set_single_register_writes()`](Pca9685::set_single_register_writes).
+//! - Work around subtle misbehavior of clone chips. See: [`set_quirks()`](Pca9685::set_quirks).
+//! - Avoid glitches when changing the PWM control values. See: [`set_update_ordering()`](Pca9685::set_update_ordering).
+//! - Write a frame in chunks spread across several calls. See: [`start_chunked_commit()`](Pca9685::start_chunked_commit).
+//! - Write safety-critical channels first when a commit is split. See: [`set_channel_priority()`](Pca9685::set_channel_priority).
+//! - Select the rounding applied in conversions. See: [`set_rounding()`](Pca9685::set_rounding).
+//! - Reject operations which do not match the intended use of a channel. See: [`set_channel_mode()`](Pca9685::set_channel_mode).
+//! - Blink channels slower than the minimum PWM frequency. See: [`start_slow_blink()`](Pca9685::start_slow_blink).
+//! - Play looping tables of duty cycles on channels. See: [`start_waveform()`](Pca9685::start_waveform).
+//! - Report numeric status codes by blinking a channel. See: [`start_blink_code()`](Pca9685::start_blink_code).
+//! - Drive two channels with the same duty cycle and a fixed phase offset. See: [`set_phase_pair_duty()`](Pca9685::set_phase_pair_duty).
+//! - Render pixels of LED strips onto channels. See: [`Segment`].
+//! - Render colors of the `rgb` crate onto pixels (`rgb` only). See: `IntoPixel`.
+//! - Generate frames fading between two frames. See: [`FrameRamp`].
+//! - Play sequences of pre-encoded frames at a fixed interval. See: [`FramePlayer`].
+//! - Shape fades with easing functions. See: [`Easing`].
+//! - Play timelines of any length and store named scenes (`alloc` only). See: `Timeline` and `SceneStore`.
+//! - Write a safe frame if the application stops sending updates. See: [`enable_failsafe()`](Pca9685::enable_failsafe).
+//! - Get notified whenever the output of a channel changes. See: [`set_output_observer()`](Pca9685::set_output_observer).
+//! - Detect channels which have not been written for some time. See: [`stale_channels()`](Pca9685::stale_channels).
+//! - Give up on operations which do not complete in time (`async` only). See: `with_timeout()`.
+//! - Use the register map of the device. See: [`regs`].
+//! - Estimate the current drawn by the loads. See: [`estimated_current_ma()`](Pca9685::estimated_current_ma).
+//! - Enable or disable register auto-increment explicitly. See: [`enable_auto_increment()`](Pca9685::enable_auto_increment).
+//! - Get the contents of the mode registers. See: [`mode1_flags()`](Pca9685::mode1_flags).
+//! - Check a frame against all constraints without bus access. See: [`validate_frame()`](Pca9685::validate_frame).
+//! - Attach labels to channels for readable diagnostics. See: [`set_channel_label()`](Pca9685::set_channel_label).
+//! - Get a human-readable summary of the device state (`std` only). See: `report()`.
 //!
 //! [Introductory blog post](https://blog.eldruin.com/pca9685-pwm-led-servo-controller-driver-in-rust/)
 //!
@@ -48,6 +142,14 @@
 //!
 //! Datasheet: [PCA9685](https://www.nxp.com/docs/en/data-sheet/PCA9685.pdf)
 //!
+//! ## Panics
+//!
+//! No method of this driver panics. Invalid input and arithmetic overflow
+//! are reported as `Error::InvalidInputData` instead. A violated internal
+//! invariant, which would be a bug in this driver, is reported as
+//! `Error::Internal`. This is enforced with Clippy lints denying indexing,
+//! unwrapping and unchecked arithmetic.
+//!
 //! ## Usage examples (see also examples folder)
 //!
 //! To use this driver, import this crate and an `embedded_hal` implementation,
@@ -76,11 +178,15 @@
 //!
 //! ```no_run
 //! use linux_embedded_hal::I2cdev;
-//! use pwm_pca9685::{Address, Pca9685};
+//! use pwm_pca9685::{AddressPins, Pca9685};
 //!
 //! let dev = I2cdev::new("/dev/i2c-1").unwrap();
-//! let (a5, a4, a3, a2, a1, a0) = (false, true, false, true, true, false);
-//! let address = (a5, a4, a3, a2, a1, a0);
+//! let address = AddressPins {
+//!     a4: true,
+//!     a2: true,
+//!     a1: true,
+//!     ..AddressPins::default()
+//! };
 //! let pwm = Pca9685::new(dev, address).unwrap();
 //! ```
 //!
@@ -108,6 +214,7 @@
 //! // Turn on channel 1 at 200, then off at 3271. These values comes from:
 //! // 0.000814 (seconds) * 60 (Hz) * 4096 (resolution) = 200
 //! // 4096 * 0.75 + 200 = 3272
+//! // The delay can also be computed at runtime with `pwm.us_to_ticks(814)`.
 //! pwm.set_channel_on_off(Channel::C1, 200, 3272).unwrap();
 //! ```
 //!
@@ -303,16 +410,135 @@
 //! ```
 
 #![deny(missing_docs, unsafe_code)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::panic,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing,
+        clippy::arithmetic_side_effects,
+        clippy::unreachable,
+        clippy::todo,
+        clippy::unimplemented
+    )
+)]
 #![no_std]
 
-mod config;
-mod register_access;
-use crate::register_access::Register;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod address_override;
+mod bar_graph;
+mod blink_code;
+pub mod boards;
+mod brightness;
+pub mod calc;
+mod channel_set;
 mod channels;
+mod chunked;
+#[cfg(feature = "rgb")]
+mod color;
+mod config;
+pub mod consts;
+mod current;
+mod device_config;
 mod device_impl;
+mod duty;
+mod easing;
+mod failsafe;
+mod fixed;
+mod frame;
+mod handle;
+mod labels;
+mod limits;
+mod master_dimming;
+mod math;
+mod mode_flags;
+mod motor;
+mod observer;
+mod phase_pair;
+mod playback;
+mod prescale;
+mod profile;
+mod quirks;
+mod ramp;
+mod register_access;
+pub mod regs;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "alloc")]
+mod scene;
+mod segment;
+mod sequenced;
+#[cfg(all(feature = "std", not(feature = "async")))]
+mod service;
+mod servo;
+#[cfg(all(feature = "std", not(feature = "async")))]
+mod shared;
+mod slow_blink;
+mod snapshot;
+mod staleness;
+mod sweep;
+mod tick;
+#[cfg(feature = "fugit")]
+mod time;
+#[cfg(feature = "alloc")]
+mod timeline;
+#[cfg(feature = "async")]
+mod timeout;
 mod types;
+mod validate;
+mod waveform;
+pub use crate::address_override::AtAddress;
+pub use crate::bar_graph::BarGraphStyle;
+pub use crate::blink_code::BlinkCode;
+pub use crate::brightness::BrightnessCurve;
+pub use crate::channel_set::ChannelSet;
+#[cfg(feature = "rgb")]
+pub use crate::color::IntoPixel;
+pub use crate::device_config::{ConfigDiff, DeviceConfig};
+pub use crate::duty::{Duty, IntoDuty, Percent};
+pub use crate::easing::Easing;
+pub use crate::fixed::Q16;
+pub use crate::frame::{EncodedFrame, Frame};
+#[cfg(feature = "async")]
+pub use crate::handle::AsyncPwmChannel;
+pub use crate::handle::ChannelHandle;
+pub use crate::labels::{ChannelName, LabeledError};
+pub use crate::limits::ChannelLimits;
+pub use crate::master_dimming::MasterDimming;
+pub use crate::mode_flags::{Mode1Flags, Mode2Flags};
+pub use crate::motor::{DcMotor, MotorCommand};
+pub use crate::observer::OutputObserver;
+pub use crate::phase_pair::PhasePair;
+pub use crate::playback::FramePlayer;
+pub use crate::prescale::Prescale;
+pub use crate::profile::PROFILE_SLOTS;
+#[cfg(not(feature = "async"))]
+pub use crate::quirks::QuirkDelay;
+pub use crate::quirks::Quirks;
+pub use crate::ramp::FrameRamp;
+#[cfg(feature = "alloc")]
+pub use crate::scene::SceneStore;
+pub use crate::segment::Segment;
+pub use crate::sequenced::Stagger;
+#[cfg(all(feature = "std", not(feature = "async")))]
+pub use crate::service::{Pca9685Service, ServiceCommand, ServiceSender};
+pub use crate::servo::{CalibrationSession, ServoConfig};
+#[cfg(all(feature = "std", not(feature = "async")))]
+pub use crate::shared::{SharedChannel, SharedPca9685};
+pub use crate::slow_blink::SlowBlink;
+pub use crate::snapshot::DeviceSnapshot;
+#[cfg(feature = "alloc")]
+pub use crate::timeline::{Keyframe, Timeline};
+#[cfg(feature = "async")]
+pub use crate::timeout::with_timeout;
 pub use crate::types::{
-    Address, Channel, ChannelOnOffControl, DisabledOutputValue, Error, OutputDriver,
-    OutputLogicState, OutputStateChange, Pca9685, ProgrammableAddress,
+    Address, AddressPins, Channel, ChannelMode, ChannelOnOffControl, DisabledOutputValue, Error,
+    OutputDriver, OutputLogicState, OutputStateChange, Pca9685, Priority, ProgrammableAddress,
+    Rounding, Topology, UpdateOrdering,
 };
+pub use crate::validate::{ChannelViolations, FrameViolations};
+pub use crate::waveform::Waveform;
 pub use nb;
@@ -0,0 +1,130 @@
+//! Per-channel input deadbands and output clamps
+
+use crate::{Channel, Error, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Limits applied to the counter values of a channel by the high-level
+/// setters
+///
+/// These are applied by [`set_channel_duty()`](Pca9685::set_channel_duty),
+/// [`set_servo_pulse_us()`](Pca9685::set_servo_pulse_us) and
+/// [`set_servo_angle()`](Pca9685::set_servo_angle) after converting their
+/// input into a counter value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChannelLimits {
+    /// Minimum counter value. Lower values are raised to this one.
+    pub min_count: u16,
+    /// Maximum counter value. Higher values are lowered to this one.
+    pub max_count: u16,
+    /// Changes of fewer counter steps than this from the value last written
+    /// are ignored and nothing is written.
+    pub deadband: u16,
+}
+
+/// No clamping and no deadband.
+impl Default for ChannelLimits {
+    fn default() -> Self {
+        ChannelLimits {
+            min_count: 0,
+            max_count: 4095,
+            deadband: 0,
+        }
+    }
+}
+
+/// Counter values to write after applying the channel limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Limited {
+    /// All channels are within their deadband.
+    Skip,
+    /// Write the same value to all requested channels.
+    Uniform(u16),
+    /// Write a different value to each channel, skipping `None`.
+    PerChannel([Option<u16>; 16]),
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Set the limits applied to the counter values of a channel.
+    ///
+    /// `Channel::All` sets the limits of every channel.
+    ///
+    /// Returns `Error::InvalidInputData` if `min_count` is greater than
+    /// `max_count` or if `max_count` is greater than 4095.
+    /// This does not have any effect on the hardware.
+    pub fn set_channel_limits(
+        &mut self,
+        channel: Channel,
+        limits: ChannelLimits,
+    ) -> Result<(), Error<E>> {
+        if limits.min_count > limits.max_count || limits.max_count > 4095 {
+            return Err(Error::InvalidInputData);
+        }
+        for (i, slot) in self.limits.iter_mut().enumerate() {
+            if channel.index().is_some_and(|index| index != i) {
+                continue;
+            }
+            *slot = limits;
+        }
+        Ok(())
+    }
+}
+
+impl<I2C> Pca9685<I2C> {
+    /// Get the limits applied to the counter values of a channel.
+    ///
+    /// Returns `None` for `Channel::All`.
+    pub fn channel_limits(&self, channel: Channel) -> Option<ChannelLimits> {
+        channel.index().and_then(|i| self.limits.get(i)).copied()
+    }
+
+    /// Apply the limits of the channels selected by `channel` to a counter
+    /// value.
+    pub(crate) fn apply_limits(&self, channel: Channel, count: u16) -> Limited {
+        let mut counts = [None; 16];
+        for (i, ((slot, limits), cached)) in counts
+            .iter_mut()
+            .zip(self.limits)
+            .zip(self.outputs.channels())
+            .enumerate()
+        {
+            if channel.index().is_some_and(|index| index != i) {
+                continue;
+            }
+            let count = count.clamp(limits.min_count, limits.max_count);
+            let within_deadband = !cached.full_on
+                && !cached.full_off
+                && cached.active_counts().abs_diff(count) < limits.deadband;
+            if !within_deadband {
+                *slot = Some(count);
+            }
+        }
+        match channel.index() {
+            Some(i) => match counts.get(i).copied().flatten() {
+                Some(count) => Limited::Uniform(count),
+                None => Limited::Skip,
+            },
+            None => match counts {
+                [Some(first), ref rest @ ..] if rest.iter().all(|c| *c == Some(first)) => {
+                    Limited::Uniform(first)
+                }
+                _ if counts.iter().all(Option::is_none) => Limited::Skip,
+                _ => Limited::PerChannel(counts),
+            },
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Master dimming through a PWM signal on the `OE` pin
+
+use crate::{DisabledOutputValue, Error, OutputDriver, OutputLogicState, Pca9685, Percent};
+use embedded_hal::pwm::SetDutyCycle;
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Global brightness control through a PWM signal driving the active-low
+/// output enable (`OE`) pin
+///
+/// While `OE` is high the outputs are disabled, so the PWM signal scales the
+/// brightness of all channels on top of their own 12-bit duty cycles. The PWM
+/// frequency of the pin should be well above the frequency of the device to
+/// avoid beating.
+///
+/// Created with [`enable_master_dimming()`](Pca9685::enable_master_dimming).
+#[derive(Debug)]
+pub struct MasterDimming<P> {
+    oe: P,
+    level: Percent,
+}
+
+impl<P: SetDutyCycle> MasterDimming<P> {
+    /// Set the global brightness in percent.
+    ///
+    /// 100% keeps the outputs enabled all the time and 0% keeps them
+    /// disabled.
+    pub fn set_master_dimming(&mut self, level: Percent) -> Result<(), P::Error> {
+        self.oe
+            .set_duty_cycle_percent(100_u8.saturating_sub(level.value()))?;
+        self.level = level;
+        Ok(())
+    }
+
+    /// Get the global brightness last set.
+    pub fn master_dimming(&self) -> Percent {
+        self.level
+    }
+
+    /// Destroy the master dimming, return the `OE` pin.
+    pub fn release(self) -> P {
+        self.oe
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Use a PWM pin driving `OE` for global brightness control.
+    ///
+    /// The disabled output value is configured so that disabled outputs are
+    /// off for the current output driver and output logic state:
+    /// - High-impedance for `OutputDriver::OpenDrain`.
+    /// - Zero for `OutputDriver::TotemPole` with direct output logic.
+    /// - One for `OutputDriver::TotemPole` with inverted output logic.
+    ///
+    /// The `OE` pin is not changed until the first call to
+    /// [`set_master_dimming()`](MasterDimming::set_master_dimming).
+    pub async fn enable_master_dimming<P: SetDutyCycle>(
+        &mut self,
+        oe: P,
+    ) -> Result<MasterDimming<P>, Error<E>> {
+        let flags = self.mode2_flags();
+        let value = match (flags.output_driver(), flags.output_logic_state()) {
+            (OutputDriver::OpenDrain, _) => DisabledOutputValue::HighImpedance,
+            (OutputDriver::TotemPole, OutputLogicState::Direct) => DisabledOutputValue::Zero,
+            (OutputDriver::TotemPole, OutputLogicState::Inverted) => {
+                DisabledOutputValue::OutputDriver
+            }
+        };
+        self.set_disabled_output_value(value).await?;
+        Ok(MasterDimming {
+            oe,
+            level: Percent::new(100).unwrap_or_default(),
+        })
+    }
+}
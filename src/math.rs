@@ -0,0 +1,46 @@
+use crate::Rounding;
+
+/// Divide `num` by `den` rounding the result as selected.
+///
+/// Returns `None` if `den` is zero or if `Rounding::Exact` is selected and
+/// the division has a remainder.
+pub(crate) fn div_rounded(num: u64, den: u64, rounding: Rounding) -> Option<u64> {
+    let quotient = num.checked_div(den)?;
+    let remainder = num.checked_rem(den)?;
+    match rounding {
+        Rounding::Nearest if remainder >= den.checked_sub(remainder)? => quotient.checked_add(1),
+        Rounding::Up if remainder != 0 => quotient.checked_add(1),
+        Rounding::Exact if remainder != 0 => None,
+        _ => Some(quotient),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_round_to_nearest() {
+        assert_eq!(Some(2), div_rounded(7, 4, Rounding::Nearest));
+        assert_eq!(Some(2), div_rounded(6, 4, Rounding::Nearest));
+        assert_eq!(Some(1), div_rounded(5, 4, Rounding::Nearest));
+    }
+
+    #[test]
+    fn can_round_down_and_up() {
+        assert_eq!(Some(1), div_rounded(7, 4, Rounding::Down));
+        assert_eq!(Some(2), div_rounded(5, 4, Rounding::Up));
+        assert_eq!(Some(2), div_rounded(8, 4, Rounding::Up));
+    }
+
+    #[test]
+    fn exact_rounding_rejects_remainder() {
+        assert_eq!(Some(2), div_rounded(8, 4, Rounding::Exact));
+        assert_eq!(None, div_rounded(9, 4, Rounding::Exact));
+    }
+
+    #[test]
+    fn zero_denominator_fails() {
+        assert_eq!(None, div_rounded(1, 0, Rounding::Nearest));
+    }
+}
@@ -0,0 +1,277 @@
+//! Contents of the MODE1 and MODE2 registers
+
+use crate::{
+    regs::{mode1, mode2},
+    DisabledOutputValue, OutputDriver, OutputLogicState, OutputStateChange, ProgrammableAddress,
+};
+use core::ops::{BitAnd, BitOr, Not};
+
+macro_rules! impl_flags {
+    ($T:ident, $valid:expr) => {
+        impl $T {
+            /// Create flags from the raw register contents.
+            ///
+            /// Returns `None` if any reserved bit is set.
+            pub const fn from_bits(bits: u8) -> Option<Self> {
+                if bits | $valid != $valid {
+                    None
+                } else {
+                    Some($T(bits))
+                }
+            }
+
+            /// Create flags from the raw register contents ignoring reserved bits.
+            pub const fn from_bits_truncate(bits: u8) -> Self {
+                $T(bits & $valid)
+            }
+
+            /// No flags set.
+            pub const fn empty() -> Self {
+                $T(0)
+            }
+
+            /// Raw register contents.
+            pub const fn bits(self) -> u8 {
+                self.0
+            }
+
+            /// Returns whether all flags in `other` are set.
+            pub const fn contains(self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            /// Set the flags in `other`.
+            pub fn insert(&mut self, other: Self) {
+                self.0 |= other.0;
+            }
+
+            /// Clear the flags in `other`.
+            pub fn remove(&mut self, other: Self) {
+                self.0 &= !other.0;
+            }
+
+            /// Set or clear the flags in `other`.
+            pub fn set(&mut self, other: Self, value: bool) {
+                if value {
+                    self.insert(other);
+                } else {
+                    self.remove(other);
+                }
+            }
+        }
+
+        impl From<$T> for u8 {
+            fn from(flags: $T) -> Self {
+                flags.0
+            }
+        }
+
+        impl BitOr for $T {
+            type Output = Self;
+
+            fn bitor(self, rhs: Self) -> Self {
+                $T(self.0 | rhs.0)
+            }
+        }
+
+        impl BitAnd for $T {
+            type Output = Self;
+
+            fn bitand(self, rhs: Self) -> Self {
+                $T(self.0 & rhs.0)
+            }
+        }
+
+        impl Not for $T {
+            type Output = Self;
+
+            fn not(self) -> Self {
+                $T(!self.0 & $valid)
+            }
+        }
+    };
+}
+
+/// Contents of the `MODE1` register
+///
+/// The default value corresponds to the power-up state of the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mode1Flags(u8);
+
+impl Mode1Flags {
+    /// Restart enabled.
+    pub const RESTART: Self = Mode1Flags(mode1::RESTART);
+    /// Use the EXTCLK pin clock.
+    pub const EXTCLK: Self = Mode1Flags(mode1::EXTCLK);
+    /// Register auto-increment enabled.
+    pub const AI: Self = Mode1Flags(mode1::AI);
+    /// Low power mode. Oscillator off.
+    pub const SLEEP: Self = Mode1Flags(mode1::SLEEP);
+    /// Respond to I2C-bus subaddress 1.
+    pub const SUB1: Self = Mode1Flags(mode1::SUB1);
+    /// Respond to I2C-bus subaddress 2.
+    pub const SUB2: Self = Mode1Flags(mode1::SUB2);
+    /// Respond to I2C-bus subaddress 3.
+    pub const SUB3: Self = Mode1Flags(mode1::SUB3);
+    /// Respond to the LED All Call I2C-bus address.
+    pub const ALLCALL: Self = Mode1Flags(mode1::ALLCALL);
+}
+
+impl_flags!(Mode1Flags, 0xFF);
+
+impl Default for Mode1Flags {
+    fn default() -> Self {
+        Mode1Flags::SLEEP | Mode1Flags::ALLCALL
+    }
+}
+
+impl From<u8> for Mode1Flags {
+    fn from(bits: u8) -> Self {
+        Mode1Flags(bits)
+    }
+}
+
+impl From<ProgrammableAddress> for Mode1Flags {
+    /// Flag enabling the response to the programmable address.
+    fn from(address: ProgrammableAddress) -> Self {
+        match address {
+            ProgrammableAddress::Subaddress1 => Mode1Flags::SUB1,
+            ProgrammableAddress::Subaddress2 => Mode1Flags::SUB2,
+            ProgrammableAddress::Subaddress3 => Mode1Flags::SUB3,
+            ProgrammableAddress::AllCall => Mode1Flags::ALLCALL,
+        }
+    }
+}
+
+/// Contents of the `MODE2` register
+///
+/// The default value corresponds to the power-up state of the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mode2Flags(u8);
+
+impl Mode2Flags {
+    /// Output logic state inverted.
+    pub const INVRT: Self = Mode2Flags(mode2::INVRT);
+    /// Outputs change on ACK.
+    pub const OCH: Self = Mode2Flags(mode2::OCH);
+    /// Totem pole output structure.
+    pub const OUTDRV: Self = Mode2Flags(mode2::OUTDRV);
+    /// Output value when `OE` = 1: bit 1.
+    pub const OUTNE1: Self = Mode2Flags(mode2::OUTNE1);
+    /// Output value when `OE` = 1: bit 0.
+    pub const OUTNE0: Self = Mode2Flags(mode2::OUTNE0);
+
+    /// Output logic state.
+    pub fn output_logic_state(self) -> OutputLogicState {
+        if self.contains(Mode2Flags::INVRT) {
+            OutputLogicState::Inverted
+        } else {
+            OutputLogicState::Direct
+        }
+    }
+
+    /// Output state change behavior.
+    pub fn output_change_behavior(self) -> OutputStateChange {
+        if self.contains(Mode2Flags::OCH) {
+            OutputStateChange::OnAck
+        } else {
+            OutputStateChange::OnStop
+        }
+    }
+
+    /// Output driver configuration.
+    pub fn output_driver(self) -> OutputDriver {
+        if self.contains(Mode2Flags::OUTDRV) {
+            OutputDriver::TotemPole
+        } else {
+            OutputDriver::OpenDrain
+        }
+    }
+
+    /// Value of the outputs when they are disabled.
+    pub fn disabled_output_value(self) -> DisabledOutputValue {
+        if self.contains(Mode2Flags::OUTNE1) {
+            DisabledOutputValue::HighImpedance
+        } else if self.contains(Mode2Flags::OUTNE0) {
+            DisabledOutputValue::OutputDriver
+        } else {
+            DisabledOutputValue::Zero
+        }
+    }
+
+    /// Create flags from the high-level configuration.
+    pub fn from_config(
+        logic_state: OutputLogicState,
+        change_behavior: OutputStateChange,
+        driver: OutputDriver,
+        disabled_value: DisabledOutputValue,
+    ) -> Self {
+        let mut flags = Mode2Flags::empty();
+        flags.set(Mode2Flags::INVRT, logic_state == OutputLogicState::Inverted);
+        flags.set(Mode2Flags::OCH, change_behavior == OutputStateChange::OnAck);
+        flags.set(Mode2Flags::OUTDRV, driver == OutputDriver::TotemPole);
+        match disabled_value {
+            DisabledOutputValue::Zero => (),
+            DisabledOutputValue::OutputDriver => flags.insert(Mode2Flags::OUTNE0),
+            DisabledOutputValue::HighImpedance => flags.insert(Mode2Flags::OUTNE1),
+        }
+        flags
+    }
+}
+
+impl_flags!(Mode2Flags, 0x1F);
+
+impl Default for Mode2Flags {
+    fn default() -> Self {
+        Mode2Flags::OUTDRV
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_power_up_state() {
+        assert_eq!(0b0001_0001, Mode1Flags::default().bits());
+        assert_eq!(0b0000_0100, Mode2Flags::default().bits());
+    }
+
+    #[test]
+    fn can_set_and_clear_flags() {
+        let mut flags = Mode1Flags::default();
+        flags.set(Mode1Flags::SLEEP, false);
+        flags.insert(Mode1Flags::AI | Mode1Flags::RESTART);
+        assert_eq!(0b1010_0001, flags.bits());
+        assert!(flags.contains(Mode1Flags::AI));
+        assert!(!flags.contains(Mode1Flags::AI | Mode1Flags::SLEEP));
+    }
+
+    #[test]
+    fn mode2_rejects_reserved_bits() {
+        assert_eq!(None, Mode2Flags::from_bits(0b0010_0000));
+        assert_eq!(
+            Mode2Flags::OUTDRV,
+            Mode2Flags::from_bits_truncate(0b1110_0100)
+        );
+        assert_eq!(0b0001_1011, (!Mode2Flags::OUTDRV).bits());
+    }
+
+    #[test]
+    fn mode2_converts_to_and_from_config() {
+        let flags = Mode2Flags::from_config(
+            OutputLogicState::Inverted,
+            OutputStateChange::OnAck,
+            OutputDriver::OpenDrain,
+            DisabledOutputValue::HighImpedance,
+        );
+        assert_eq!(0b0001_1010, flags.bits());
+        assert_eq!(OutputLogicState::Inverted, flags.output_logic_state());
+        assert_eq!(OutputStateChange::OnAck, flags.output_change_behavior());
+        assert_eq!(OutputDriver::OpenDrain, flags.output_driver());
+        assert_eq!(
+            DisabledOutputValue::HighImpedance,
+            flags.disabled_output_value()
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! DC motor control through an H-bridge
+
+use crate::{
+    boards::{adafruit_motor_hat, DcMotorPins},
+    ChannelOnOffControl, Error, Pca9685,
+};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// DC motor command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MotorCommand {
+    /// Turn forward.
+    Forward,
+    /// Turn backward.
+    Backward,
+    /// Short the motor terminals.
+    Brake,
+    /// Let the motor spin freely.
+    Release,
+}
+
+/// DC motor driven by an H-bridge connected to three channels
+#[derive(Debug)]
+pub struct DcMotor<'a, I2C> {
+    pwm: &'a mut Pca9685<I2C>,
+    pins: DcMotorPins,
+}
+
+impl<'a, I2C> DcMotor<'a, I2C> {
+    /// Create a DC motor on the given channels.
+    pub fn new(pwm: &'a mut Pca9685<I2C>, pins: DcMotorPins) -> Self {
+        DcMotor { pwm, pins }
+    }
+
+    /// Create a DC motor on the M1 output of an Adafruit Motor HAT.
+    pub fn motor1_on_motor_hat(pwm: &'a mut Pca9685<I2C>) -> Self {
+        Self::new(pwm, adafruit_motor_hat::MOTOR1)
+    }
+
+    /// Create a DC motor on the M2 output of an Adafruit Motor HAT.
+    pub fn motor2_on_motor_hat(pwm: &'a mut Pca9685<I2C>) -> Self {
+        Self::new(pwm, adafruit_motor_hat::MOTOR2)
+    }
+
+    /// Create a DC motor on the M3 output of an Adafruit Motor HAT.
+    pub fn motor3_on_motor_hat(pwm: &'a mut Pca9685<I2C>) -> Self {
+        Self::new(pwm, adafruit_motor_hat::MOTOR3)
+    }
+
+    /// Create a DC motor on the M4 output of an Adafruit Motor HAT.
+    pub fn motor4_on_motor_hat(pwm: &'a mut Pca9685<I2C>) -> Self {
+        Self::new(pwm, adafruit_motor_hat::MOTOR4)
+    }
+
+    /// Get the channels the motor is connected to.
+    pub fn pins(&self) -> DcMotorPins {
+        self.pins
+    }
+}
+
+fn level(high: bool) -> ChannelOnOffControl {
+    if high {
+        ChannelOnOffControl::full_on()
+    } else {
+        ChannelOnOffControl::full_off()
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "DcMotor",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<'a, I2C, E> DcMotor<'a, I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Set the motor speed in the range `[0-4095]`.
+    pub async fn set_speed(&mut self, speed: u16) -> Result<(), Error<E>> {
+        self.pwm.set_channel_on_off(self.pins.pwm, 0, speed).await
+    }
+
+    /// Set the direction inputs of the H-bridge.
+    pub async fn run(&mut self, command: MotorCommand) -> Result<(), Error<E>> {
+        let (in1, in2) = match command {
+            MotorCommand::Forward => (true, false),
+            MotorCommand::Backward => (false, true),
+            MotorCommand::Brake => (true, true),
+            MotorCommand::Release => (false, false),
+        };
+        self.pwm
+            .set_channel_value(self.pins.in2, level(in2))
+            .await?;
+        self.pwm.set_channel_value(self.pins.in1, level(in1)).await
+    }
+}
@@ -0,0 +1,61 @@
+//! Output change notifications
+
+use crate::{Channel, ChannelOnOffControl, Frame, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Function called with the channel, the old and the new control values
+/// whenever the driver changes the output of a channel
+///
+/// See: [`set_output_observer()`](Pca9685::set_output_observer).
+pub type OutputObserver = fn(Channel, ChannelOnOffControl, ChannelOnOffControl);
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Set a function to be called whenever the driver changes the output of
+    /// a channel, or remove it with `None`.
+    ///
+    /// The observer is called once per changed channel after the values have
+    /// been written to the device. Writes which do not change the control
+    /// values of a channel are not reported. This can be used to mirror the
+    /// output state to displays, logs or telemetry.
+    ///
+    /// This does not have any effect on the hardware.
+    pub fn set_output_observer(&mut self, observer: Option<OutputObserver>) {
+        self.observer = observer;
+    }
+
+    /// Store the control values written to the device and notify the
+    /// observer of the changed channels.
+    pub(crate) fn commit_outputs(&mut self, outputs: Frame) {
+        if let Some(observer) = self.observer {
+            for (i, (old, new)) in self
+                .outputs
+                .channels()
+                .iter()
+                .zip(outputs.channels())
+                .enumerate()
+            {
+                if old != new {
+                    if let Ok(channel) = Channel::try_from(i) {
+                        observer(channel, *old, *new);
+                    }
+                }
+            }
+        }
+        self.outputs = outputs;
+    }
+}
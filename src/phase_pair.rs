@@ -0,0 +1,148 @@
+//! Channel pairs with a fixed phase offset
+
+use crate::{channels::OutputKind, Channel, ChannelOnOffControl, Error, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Number of counter steps in a PWM cycle.
+const CYCLE: u16 = 4096;
+
+/// Two channels generating pulses of identical width with a fixed phase
+/// offset
+///
+/// The second channel is switched on `offset` counter steps after the first
+/// one, for example 1024 steps (90°) for quadrature signals.
+/// See [`set_phase_pair_duty()`](Pca9685::set_phase_pair_duty).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PhasePair {
+    first: Channel,
+    second: Channel,
+    offset: u16,
+}
+
+impl PhasePair {
+    /// Create a pair with an offset in counter steps in the range `[0-4095]`.
+    ///
+    /// Returns `None` if a channel is `Channel::All`, if both channels are
+    /// the same or if the offset is out of range.
+    pub fn new(first: Channel, second: Channel, offset: u16) -> Option<Self> {
+        if first.index()? == second.index()? || offset >= CYCLE {
+            return None;
+        }
+        Some(PhasePair {
+            first,
+            second,
+            offset,
+        })
+    }
+
+    /// Create a pair with an offset in degrees in the range `[0-359]`.
+    ///
+    /// The offset is rounded down to whole counter steps.
+    pub fn with_phase_deg(first: Channel, second: Channel, phase_deg: u16) -> Option<Self> {
+        if phase_deg >= 360 {
+            return None;
+        }
+        let offset = u32::from(phase_deg)
+            .checked_mul(u32::from(CYCLE))?
+            .checked_div(360)?;
+        Self::new(first, second, u16::try_from(offset).ok()?)
+    }
+
+    /// Create a pair with the second channel lagging 90°.
+    pub fn quadrature(first: Channel, second: Channel) -> Option<Self> {
+        Self::new(first, second, CYCLE / 4)
+    }
+
+    /// Get the channels of the pair.
+    pub fn channels(&self) -> (Channel, Channel) {
+        (self.first, self.second)
+    }
+
+    /// Get the phase offset in counter steps.
+    pub fn offset(&self) -> u16 {
+        self.offset
+    }
+
+    /// Control values of both channels for a pulse width of `duty` steps.
+    fn values(&self, duty: u16) -> Option<(ChannelOnOffControl, ChannelOnOffControl)> {
+        Some((
+            ChannelOnOffControl::duty_at_phase(duty, 0)?,
+            ChannelOnOffControl::duty_at_phase(duty, self.offset)?,
+        ))
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Set the pulse width of both channels of a pair in counter steps in
+    /// the range `[0-4095]`.
+    ///
+    /// Both channels get the same pulse width and keep their phase offset.
+    /// A pulse width of 0 sets both channels to full-off. If the channels are
+    /// adjacent, they are written in a single I2C transaction.
+    pub async fn set_phase_pair_duty(
+        &mut self,
+        pair: &PhasePair,
+        duty: u16,
+    ) -> Result<(), Error<E>> {
+        let (first, second) = pair.values(duty).ok_or(Error::InvalidInputData)?;
+        self.check_channel_mode(pair.first, OutputKind::of(&first))?;
+        self.check_channel_mode(pair.second, OutputKind::of(&second))?;
+        let a = pair.first.index().ok_or(Error::InvalidInputData)?;
+        let b = pair.second.index().ok_or(Error::InvalidInputData)?;
+        if a.checked_add(1) == Some(b) {
+            self.write_channels(a, &[first, second]).await
+        } else if b.checked_add(1) == Some(a) {
+            self.write_channels(b, &[second, first]).await
+        } else {
+            self.write_channels(a, &[first]).await?;
+            self.write_channels(b, &[second]).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_invalid_pairs() {
+        assert_eq!(None, PhasePair::new(Channel::C0, Channel::C0, 0));
+        assert_eq!(None, PhasePair::new(Channel::All, Channel::C0, 0));
+        assert_eq!(None, PhasePair::new(Channel::C0, Channel::C1, 4096));
+        assert_eq!(
+            None,
+            PhasePair::with_phase_deg(Channel::C0, Channel::C1, 360)
+        );
+    }
+
+    #[test]
+    fn converts_degrees_to_steps() {
+        let pair = PhasePair::with_phase_deg(Channel::C0, Channel::C1, 90).unwrap();
+        assert_eq!(1024, pair.offset());
+        let pair = PhasePair::with_phase_deg(Channel::C0, Channel::C1, 1).unwrap();
+        assert_eq!(11, pair.offset());
+    }
+
+    #[test]
+    fn second_pulse_wraps_around_cycle() {
+        let pair = PhasePair::new(Channel::C0, Channel::C1, 3000).unwrap();
+        let (first, second) = pair.values(2000).unwrap();
+        assert_eq!((0, 2000), (first.on, first.off));
+        assert_eq!((3000, 904), (second.on, second.off));
+    }
+}
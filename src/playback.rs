@@ -0,0 +1,125 @@
+//! Playback of pre-encoded frames
+
+use crate::{EncodedFrame, Error, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Sequence of pre-encoded frames played at a fixed interval
+///
+/// The frames are written with [`play_frames()`](Pca9685::play_frames),
+/// which has to be called periodically, for example next to
+/// [`tick()`](Pca9685::tick). Each frame is written as is in a single
+/// transaction, so that deterministic sequences can be prepared in advance
+/// and streamed with minimal work per frame.
+///
+/// ```no_run
+/// use linux_embedded_hal::I2cdev;
+/// use pwm_pca9685::{Address, EncodedFrame, Frame, FramePlayer, Pca9685};
+///
+/// let dev = I2cdev::new("/dev/i2c-1").unwrap();
+/// let mut pwm = Pca9685::new(dev, Address::default()).unwrap();
+/// let show = [
+///     EncodedFrame::new(&Frame::all_off()),
+///     EncodedFrame::new(&Frame::default()),
+/// ];
+/// let mut player = FramePlayer::new(&show, 40, 0).with_looping(true);
+/// # let now_ms = 0;
+/// pwm.play_frames(&mut player, now_ms).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FramePlayer<'a> {
+    frames: &'a [EncodedFrame],
+    interval_ms: u32,
+    start_ms: u32,
+    index: Option<usize>,
+    looping: bool,
+}
+
+impl<'a> FramePlayer<'a> {
+    /// Create a player starting at `now_ms` which holds each frame for
+    /// `interval_ms` milliseconds.
+    ///
+    /// This does not have any effect on the hardware.
+    pub fn new(frames: &'a [EncodedFrame], interval_ms: u32, now_ms: u32) -> Self {
+        FramePlayer {
+            frames,
+            interval_ms,
+            start_ms: now_ms,
+            index: None,
+            looping: false,
+        }
+    }
+
+    /// Select whether to start over after the last frame. Disabled by
+    /// default, in which case the last frame is kept.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Returns whether the last frame has been held for its interval.
+    ///
+    /// A looping player never finishes, unless it has no frames.
+    pub fn is_finished(&self, now_ms: u32) -> bool {
+        self.index_at(now_ms).is_none()
+    }
+
+    fn index_at(&self, now_ms: u32) -> Option<usize> {
+        let elapsed = now_ms.wrapping_sub(self.start_ms);
+        let steps = usize::try_from(elapsed.checked_div(self.interval_ms)?).ok()?;
+        if self.looping {
+            steps.checked_rem(self.frames.len())
+        } else {
+            Some(steps).filter(|s| *s < self.frames.len())
+        }
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Write the frame of a player due at `now_ms` if it has not been
+    /// written yet.
+    ///
+    /// The encoded bytes are written without checking the channel modes,
+    /// limits or update ordering. Nothing is written once the player has
+    /// finished or while the failsafe is triggered.
+    ///
+    /// Returns `Error::InvalidInputData` if `interval_ms` is 0.
+    pub async fn play_frames(
+        &mut self,
+        player: &mut FramePlayer<'_>,
+        now_ms: u32,
+    ) -> Result<(), Error<E>> {
+        if player.interval_ms == 0 {
+            return Err(Error::InvalidInputData);
+        }
+        if self.is_failsafe_triggered() {
+            return Ok(());
+        }
+        let Some(index) = player.index_at(now_ms) else {
+            return Ok(());
+        };
+        if player.index == Some(index) {
+            return Ok(());
+        }
+        let encoded = player.frames.get(index).ok_or(Error::InvalidInputData)?;
+        self.write_register_block(encoded.as_bytes()).await?;
+        player.index = Some(index);
+        self.commit_outputs(encoded.frame());
+        self.mark_updated(0, 16);
+        Ok(())
+    }
+}
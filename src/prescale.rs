@@ -0,0 +1,252 @@
+//! Prescale values and PWM frequencies
+
+use crate::{calc, config::BitFlagMode1, math::div_rounded, servo::us_to_counts, Pca9685};
+
+/// Internal oscillator frequency in Hz.
+pub(crate) const INTERNAL_OSC_HZ: u32 = 25_000_000;
+
+/// Prescale value in the range `[3-255]`
+///
+/// The PWM frequency obtained with the internal oscillator is
+/// `25 MHz / (4096 * (prescale + 1))`. The conversions are `const`, so that
+/// prescale values and tables of frequencies can be computed at compile
+/// time.
+///
+/// | Preset                           | Prescale | Frequency |
+/// |----------------------------------|----------|-----------|
+/// | [`MAX`](Self::MAX)               | 255      | 24 Hz     |
+/// | [`SERVO_50HZ`](Self::SERVO_50HZ) | 121      | 50 Hz     |
+/// | [`SERVO_60HZ`](Self::SERVO_60HZ) | 101      | 60 Hz     |
+/// | [`DEFAULT`](Self::DEFAULT)       | 30       | 197 Hz    |
+/// | [`LED_1KHZ`](Self::LED_1KHZ)     | 5        | 1017 Hz   |
+/// | [`MIN`](Self::MIN)               | 3        | 1526 Hz   |
+///
+/// ```
+/// use pwm_pca9685::Prescale;
+///
+/// const SERVO: Prescale = Prescale::SERVO_50HZ;
+/// assert_eq!(121, SERVO.value());
+/// assert_eq!(50, SERVO.frequency_hz());
+/// assert_eq!(Some(Prescale::SERVO_60HZ), Prescale::from_frequency_hz(60));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Prescale(u8);
+
+impl Prescale {
+    /// Lowest prescale value, corresponding to the highest frequency of about 1526 Hz.
+    pub const MIN: Prescale = Prescale(3);
+    /// Highest prescale value, corresponding to the lowest frequency of about 24 Hz.
+    pub const MAX: Prescale = Prescale(255);
+    /// Power-up prescale value, corresponding to about 200 Hz.
+    pub const DEFAULT: Prescale = Prescale(30);
+    /// 50 Hz, the usual frame rate of analog servos (20 ms period).
+    pub const SERVO_50HZ: Prescale = Prescale(121);
+    /// 60 Hz, also common for servos.
+    pub const SERVO_60HZ: Prescale = Prescale(101);
+    /// About 1 kHz, for flicker-free LED dimming.
+    pub const LED_1KHZ: Prescale = Prescale(5);
+
+    /// Create a prescale value.
+    ///
+    /// Returns `None` if `value` is lower than 3.
+    pub const fn new(value: u8) -> Option<Self> {
+        if value < 3 {
+            None
+        } else {
+            Some(Prescale(value))
+        }
+    }
+
+    /// Get the prescale value.
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+
+    /// Find the prescale value for a PWM frequency in Hz when using the
+    /// internal oscillator.
+    ///
+    /// The value is calculated as `round(25 MHz / (4096 * frequency_hz)) - 1`.
+    /// Returns `None` if the frequency is out of the range `[24-1526]` Hz.
+    pub const fn from_frequency_hz(frequency_hz: u32) -> Option<Self> {
+        if frequency_hz < 24 || frequency_hz > 1526 {
+            return None;
+        }
+        match calc::prescale_for_frequency(INTERNAL_OSC_HZ, frequency_hz) {
+            Some((prescale, _)) => Prescale::new(prescale),
+            None => None,
+        }
+    }
+
+    /// Find the prescale value for a PWM period in microseconds when using
+    /// the internal oscillator.
+    ///
+    /// The value is calculated as `round(period_us * 25 MHz / 4096 / 1 s) - 1`.
+    /// Returns `None` if the period is out of the range
+    /// `[574-42024]` µs.
+    pub const fn from_period_us(period_us: u32) -> Option<Self> {
+        match calc::prescale_for_period_us(INTERNAL_OSC_HZ, period_us) {
+            Some(prescale) => Prescale::new(prescale),
+            None => None,
+        }
+    }
+
+    /// PWM frequency in Hz when using the internal oscillator, rounded to
+    /// the nearest integer.
+    pub const fn frequency_hz(self) -> u32 {
+        match calc::frequency_for_prescale(INTERNAL_OSC_HZ, self.0) {
+            Some(frequency) => frequency,
+            None => 0,
+        }
+    }
+}
+
+/// Power-up prescale value.
+impl Default for Prescale {
+    fn default() -> Self {
+        Prescale::DEFAULT
+    }
+}
+
+impl From<Prescale> for u8 {
+    fn from(prescale: Prescale) -> Self {
+        prescale.0
+    }
+}
+
+impl<I2C> Pca9685<I2C> {
+    /// Get the prescale value last written by this driver.
+    ///
+    /// This does not read the device. See
+    /// [`read_prescale()`](Pca9685::read_prescale).
+    pub fn get_prescale(&self) -> u8 {
+        self.prescale
+    }
+
+    /// Get the PWM frequency in Hz resulting from the prescale value last
+    /// written by this driver, rounded to the nearest integer.
+    ///
+    /// Returns `None` if the external clock is in use, since its frequency
+    /// is unknown.
+    ///
+    /// This does not read the device.
+    pub fn frequency(&self) -> Option<u32> {
+        if self.config.is_high(BitFlagMode1::ExtClk) {
+            return None;
+        }
+        calc::frequency_for_prescale(self.oscillator_hz(), self.prescale)
+    }
+
+    /// Convert a number of counter steps into microseconds using the prescale
+    /// value last written by this driver and the oscillator frequency.
+    ///
+    /// The result is rounded as configured with
+    /// [`set_rounding()`](Pca9685::set_rounding).
+    ///
+    /// Returns `None` if the external clock is in use or if the conversion
+    /// is not exact with `Rounding::Exact`.
+    pub fn ticks_to_us(&self, ticks: u16) -> Option<u32> {
+        if self.config.is_high(BitFlagMode1::ExtClk) {
+            return None;
+        }
+        let num = u64::from(ticks)
+            .checked_mul(u64::from(self.prescale).checked_add(1)?)?
+            .checked_mul(1_000_000)?;
+        let us = div_rounded(num, u64::from(self.oscillator_hz()), self.rounding)?;
+        u32::try_from(us).ok()
+    }
+
+    /// Convert a duration in microseconds into counter steps using the
+    /// prescale value last written by this driver and the oscillator
+    /// frequency.
+    ///
+    /// This is useful to compute delays of the `on` counter, for example.
+    /// The result is rounded as configured with
+    /// [`set_rounding()`](Pca9685::set_rounding).
+    ///
+    /// Returns `None` if the external clock is in use, if the result is
+    /// greater than 4095 or if the conversion is not exact with
+    /// `Rounding::Exact`.
+    pub fn us_to_ticks(&self, us: u32) -> Option<u16> {
+        if self.config.is_high(BitFlagMode1::ExtClk) {
+            return None;
+        }
+        us_to_counts(
+            self.oscillator_hz(),
+            self.prescale,
+            u64::from(us),
+            1,
+            self.rounding,
+        )
+        .and_then(|ticks| u16::try_from(ticks).ok())
+        .filter(|ticks| *ticks <= 4095)
+    }
+
+    /// Get the frequency of the internal oscillator in Hz assumed in
+    /// conversions, including the correction set with
+    /// [`set_oscillator_correction_ppm()`](Pca9685::set_oscillator_correction_ppm).
+    pub fn oscillator_hz(&self) -> u32 {
+        let corrected = i64::from(INTERNAL_OSC_HZ)
+            .checked_mul(i64::from(self.oscillator_correction_ppm).saturating_add(1_000_000))
+            .and_then(|hz| hz.checked_div(1_000_000));
+        corrected
+            .and_then(|hz| u32::try_from(hz).ok())
+            .unwrap_or(INTERNAL_OSC_HZ)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_match_their_frequency() {
+        assert_eq!(Some(Prescale::SERVO_50HZ), Prescale::from_frequency_hz(50));
+        assert_eq!(Some(Prescale::SERVO_60HZ), Prescale::from_frequency_hz(60));
+        assert_eq!(Some(Prescale::LED_1KHZ), Prescale::from_frequency_hz(1000));
+        assert_eq!(Some(Prescale::MIN), Prescale::from_frequency_hz(1526));
+        assert_eq!(Prescale::new(253), Prescale::from_frequency_hz(24));
+        assert_eq!(Some(Prescale::DEFAULT), Prescale::from_frequency_hz(200));
+    }
+
+    #[test]
+    fn rejects_out_of_range_values() {
+        assert_eq!(None, Prescale::new(2));
+        assert_eq!(None, Prescale::from_frequency_hz(0));
+        assert_eq!(None, Prescale::from_frequency_hz(23));
+        assert_eq!(None, Prescale::from_frequency_hz(1700));
+    }
+
+    #[test]
+    fn can_convert_period() {
+        assert_eq!(Some(Prescale::SERVO_50HZ), Prescale::from_period_us(20_000));
+        assert_eq!(Some(Prescale::MIN), Prescale::from_period_us(655));
+        assert_eq!(Some(Prescale::MAX), Prescale::from_period_us(41_943));
+        assert_eq!(None, Prescale::from_period_us(573));
+        assert_eq!(None, Prescale::from_period_us(42_025));
+    }
+
+    #[test]
+    fn calculates_frequency() {
+        assert_eq!(1526, Prescale::MIN.frequency_hz());
+        assert_eq!(24, Prescale::MAX.frequency_hz());
+        assert_eq!(197, Prescale::DEFAULT.frequency_hz());
+        assert_eq!(1017, Prescale::LED_1KHZ.frequency_hz());
+    }
+
+    #[test]
+    fn matches_calc_with_internal_oscillator() {
+        for value in 3..=255 {
+            let prescale = Prescale::new(value).unwrap();
+            assert_eq!(
+                calc::frequency_for_prescale(INTERNAL_OSC_HZ, value),
+                Some(prescale.frequency_hz())
+            );
+        }
+        for frequency_hz in 24..=1526 {
+            assert_eq!(
+                calc::prescale_for_frequency(INTERNAL_OSC_HZ, frequency_hz).map(|(p, _)| p),
+                Prescale::from_frequency_hz(frequency_hz).map(Prescale::value)
+            );
+        }
+    }
+}
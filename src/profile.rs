@@ -0,0 +1,152 @@
+//! Stored output profiles
+
+use crate::{
+    channels::OutputKind, types::CHANNELS, DeviceSnapshot, Error, Frame, FrameRamp, Pca9685,
+};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Number of output profiles which can be stored in the driver.
+pub const PROFILE_SLOTS: usize = 4;
+
+/// Stored profiles and crossfade state.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Profiles {
+    slots: [Option<DeviceSnapshot>; PROFILE_SLOTS],
+    fade: Option<ProfileFade>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ProfileFade {
+    ramp: FrameRamp,
+    start_ms: u32,
+    duration_ms: u32,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Store an output profile in one of the [`PROFILE_SLOTS`] slots.
+    ///
+    /// A profile holds the mode and prescale configuration as well as the
+    /// control values of all channels, for example for "day" and "night"
+    /// lighting. The current state can be stored with
+    /// [`snapshot()`](Pca9685::snapshot).
+    ///
+    /// Returns `Error::InvalidInputData` if the slot is out of range.
+    /// This does not have any effect on the hardware.
+    pub fn store_profile(&mut self, slot: usize, profile: DeviceSnapshot) -> Result<(), Error<E>> {
+        let stored = self
+            .profiles
+            .slots
+            .get_mut(slot)
+            .ok_or(Error::InvalidInputData)?;
+        *stored = Some(profile);
+        Ok(())
+    }
+
+    /// Get the output profile stored in a slot.
+    pub fn profile(&self, slot: usize) -> Option<DeviceSnapshot> {
+        self.profiles.slots.get(slot).copied().flatten()
+    }
+
+    /// Switch to a stored output profile.
+    ///
+    /// The configuration is applied with [`apply_config()`](Pca9685::apply_config)
+    /// and the channels are written in a single transaction covering the
+    /// channels whose values differ from the current outputs. Nothing is
+    /// written if the profile is already active. Any running profile
+    /// crossfade is stopped.
+    ///
+    /// Returns `Error::InvalidInputData` if no profile is stored in the slot.
+    pub async fn switch_profile(&mut self, slot: usize) -> Result<(), Error<E>> {
+        let profile = self.profile(slot).ok_or(Error::InvalidInputData)?;
+        self.profiles.fade = None;
+        self.apply_config(profile.config).await?;
+        self.write_frame_changes(&profile.outputs).await
+    }
+
+    /// Switch to a stored output profile fading the channels from their
+    /// current values over `duration_ms` milliseconds.
+    ///
+    /// The configuration is applied immediately. The fade is advanced by
+    /// [`tick()`](Pca9685::tick), which writes the channels that changed at
+    /// each call. `now_ms` is the current time in milliseconds from a
+    /// monotonic clock.
+    ///
+    /// Returns `Error::InvalidInputData` if no profile is stored in the slot.
+    pub async fn crossfade_to_profile(
+        &mut self,
+        slot: usize,
+        duration_ms: u32,
+        now_ms: u32,
+    ) -> Result<(), Error<E>> {
+        let profile = self.profile(slot).ok_or(Error::InvalidInputData)?;
+        self.apply_config(profile.config).await?;
+        self.profiles.fade = Some(ProfileFade {
+            ramp: FrameRamp::new(self.outputs, profile.outputs, 1),
+            start_ms: now_ms,
+            duration_ms,
+        });
+        Ok(())
+    }
+
+    /// Returns whether a profile crossfade is in progress.
+    pub fn is_profile_crossfade_active(&self) -> bool {
+        self.profiles.fade.is_some()
+    }
+
+    pub(crate) async fn service_profile_fade(&mut self, now_ms: u32) -> Result<(), Error<E>> {
+        if let Some(fade) = self.profiles.fade {
+            let elapsed = now_ms.wrapping_sub(fade.start_ms);
+            let frame = fade.ramp.at(elapsed, fade.duration_ms);
+            self.write_frame_changes(&frame).await?;
+            if elapsed >= fade.duration_ms {
+                self.profiles.fade = None;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn stop_profile_fade(&mut self) {
+        self.profiles.fade = None;
+    }
+
+    /// Write the range of channels whose values differ from the current
+    /// outputs in a single transaction.
+    pub(crate) async fn write_frame_changes(&mut self, frame: &Frame) -> Result<(), Error<E>> {
+        let mut changed: Option<(usize, usize)> = None;
+        for (i, ((value, current), channel)) in frame
+            .channels()
+            .iter()
+            .zip(self.outputs.channels())
+            .zip(CHANNELS)
+            .enumerate()
+        {
+            if value != current {
+                self.check_channel_mode(channel, OutputKind::of(value))?;
+                changed = Some(changed.map_or((i, i), |(first, _)| (first, i)));
+            }
+        }
+        if let Some((first, last)) = changed {
+            let values = frame
+                .channels()
+                .get(first..=last)
+                .ok_or(Error::InvalidInputData)?;
+            self.write_channels(first, values).await?;
+        }
+        Ok(())
+    }
+}
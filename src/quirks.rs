@@ -0,0 +1,152 @@
+//! Workarounds for clone chips
+
+use crate::{config::BitFlagMode1, Error, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Function blocking for the given number of microseconds
+///
+/// This is only available without the `async` feature, since a blocking
+/// delay would stall the executor inside the async write methods.
+///
+/// See: [`set_quirks()`](Pca9685::set_quirks).
+#[cfg(not(feature = "async"))]
+pub type QuirkDelay = fn(u32);
+
+/// Workarounds for PCA9685 clones which subtly misbehave
+///
+/// All workarounds are disabled by default, which matches the behavior of
+/// the original device. The delays are not applied if the `async` feature is
+/// enabled. See: [`set_quirks()`](Pca9685::set_quirks).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Quirks {
+    /// Time to wait after writing the `MODE1` or `MODE2` register in
+    /// microseconds.
+    pub mode_write_delay_us: u32,
+    /// Time to wait between two consecutive auto-increment transactions in
+    /// microseconds.
+    pub auto_increment_gap_us: u32,
+    /// Write the `MODE1` register with auto-increment enabled before every
+    /// n-th auto-increment transaction, even if it is already enabled.
+    /// 0 never writes it again.
+    pub reassert_auto_increment_every: u16,
+}
+
+impl Quirks {
+    /// Conservative settings to start with when a clone loses writes or
+    /// stops auto-incrementing.
+    ///
+    /// This waits 500 µs after mode register writes and 100 µs between
+    /// auto-increment transactions and re-asserts auto-increment before every
+    /// auto-increment transaction.
+    pub const CONSERVATIVE: Quirks = Quirks {
+        mode_write_delay_us: 500,
+        auto_increment_gap_us: 100,
+        reassert_auto_increment_every: 1,
+    };
+}
+
+/// Selected workarounds and the state needed to apply them
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct QuirkState {
+    quirks: Quirks,
+    #[cfg(not(feature = "async"))]
+    delay: Option<QuirkDelay>,
+    /// Number of auto-increment transactions since auto-increment was last
+    /// asserted.
+    auto_increment_writes: u16,
+    /// Whether the last transaction was an auto-increment transaction.
+    after_auto_increment: bool,
+}
+
+impl QuirkState {
+    #[cfg(not(feature = "async"))]
+    fn delay_us(&self, us: u32) {
+        if let Some(delay) = self.delay.filter(|_| us > 0) {
+            delay(us);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn delay_us(&self, _us: u32) {}
+}
+
+impl<I2C> Pca9685<I2C> {
+    /// Select the workarounds applied for clone chips.
+    ///
+    /// Delays are performed with the `delay` function, which blocks the
+    /// caller. Without it, only the auto-increment re-assertion is applied.
+    ///
+    /// This does not have any effect on the hardware.
+    #[cfg(not(feature = "async"))]
+    pub fn set_quirks(&mut self, quirks: Quirks, delay: Option<QuirkDelay>) {
+        self.quirks = QuirkState {
+            quirks,
+            delay,
+            ..QuirkState::default()
+        };
+    }
+
+    /// Select the workarounds applied for clone chips.
+    ///
+    /// Only the auto-increment re-assertion is applied. The delays of
+    /// `quirks` are ignored, since they would block the executor.
+    ///
+    /// This does not have any effect on the hardware.
+    #[cfg(feature = "async")]
+    pub fn set_quirks(&mut self, quirks: Quirks) {
+        self.quirks = QuirkState {
+            quirks,
+            ..QuirkState::default()
+        };
+    }
+
+    /// Get the workarounds applied for clone chips.
+    pub fn quirks(&self) -> Quirks {
+        self.quirks.quirks
+    }
+
+    /// Apply the workarounds after writing a mode register.
+    pub(crate) fn after_mode_write(&mut self) {
+        self.quirks.delay_us(self.quirks.quirks.mode_write_delay_us);
+        self.quirks.after_auto_increment = false;
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Apply the workarounds before an auto-increment transaction.
+    pub(crate) async fn before_auto_increment_write(&mut self) -> Result<(), Error<E>> {
+        let every = self.quirks.quirks.reassert_auto_increment_every;
+        if every > 0 {
+            if self.quirks.auto_increment_writes.saturating_add(1) >= every {
+                let config = self.config;
+                self.write_mode1(config.with_high(BitFlagMode1::AutoInc))
+                    .await?;
+                self.quirks.auto_increment_writes = 0;
+            } else {
+                self.quirks.auto_increment_writes =
+                    self.quirks.auto_increment_writes.saturating_add(1);
+            }
+        }
+        if self.quirks.after_auto_increment {
+            self.quirks
+                .delay_us(self.quirks.quirks.auto_increment_gap_us);
+        }
+        self.quirks.after_auto_increment = true;
+        Ok(())
+    }
+}
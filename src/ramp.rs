@@ -0,0 +1,251 @@
+//! Interpolation between frames
+
+use crate::{
+    easing::PROGRESS_SCALE, math::div_rounded, ChannelOnOffControl, Easing, Frame, Rounding, Q16,
+};
+
+/// Number of counter steps in a PWM cycle.
+const CYCLE: u16 = 4096;
+
+/// Sequence of frames interpolated between two endpoints
+///
+/// This does not communicate with the device. The frames can be written
+/// at any cadence, for example with [`set_all_channels()`](crate::Pca9685::set_all_channels).
+///
+/// The pulse width and the `ON` counter of each channel are interpolated
+/// linearly by default. The progress can be shaped with an easing function.
+/// See: [`with_easing()`](FrameRamp::with_easing). Full-off corresponds to a pulse width of 0 and full-on to a
+/// pulse width of a whole cycle.
+///
+/// ```
+/// use pwm_pca9685::{ChannelOnOffControl, Frame, FrameRamp};
+///
+/// let from = Frame::all_off();
+/// let to = Frame::new([ChannelOnOffControl::full_on(); 16]);
+/// let mut ramp = FrameRamp::new(from, to, 4);
+/// let first = ramp.next().unwrap();
+/// assert_eq!(Some(1024), first.channels().first().map(|c| c.off));
+/// assert_eq!(Some(to), ramp.last());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameRamp {
+    from: Frame,
+    to: Frame,
+    steps: u32,
+    step: u32,
+    easing: Easing,
+}
+
+impl FrameRamp {
+    /// Create a ramp from `from` to `to` in `steps` steps.
+    ///
+    /// Iterating the ramp yields `steps` frames excluding `from` and ending
+    /// with `to`. A ramp with 0 steps yields `to` only.
+    pub fn new(from: Frame, to: Frame, steps: u32) -> Self {
+        FrameRamp {
+            from,
+            to,
+            steps: steps.max(1),
+            step: 0,
+            easing: Easing::Linear,
+        }
+    }
+
+    /// Shape the progress of the ramp with an easing function.
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Get the frame after `elapsed` out of a total `duration` in any unit of
+    /// time.
+    ///
+    /// The elapsed time is clamped to the duration.
+    pub fn at(&self, elapsed: u32, duration: u32) -> Frame {
+        if elapsed >= duration {
+            return self.to;
+        }
+        let (elapsed, duration) = match self.easing {
+            Easing::Linear => (elapsed, duration),
+            easing => (easing.progress(elapsed, duration) as u32, PROGRESS_SCALE),
+        };
+        let mut channels = *self.from.channels();
+        for (channel, to) in channels.iter_mut().zip(self.to.channels()) {
+            *channel = interpolate_channel(channel, to, elapsed, duration);
+        }
+        Frame::new(channels)
+    }
+}
+
+impl Iterator for FrameRamp {
+    type Item = Frame;
+
+    fn next(&mut self) -> Option<Frame> {
+        if self.step >= self.steps {
+            return None;
+        }
+        self.step = self.step.saturating_add(1);
+        Some(self.at(self.step, self.steps))
+    }
+}
+
+impl Frame {
+    /// Get the frame at a fraction `t` of the way from this frame to `other`.
+    ///
+    /// The channels are interpolated with integer arithmetic as in
+    /// [`FrameRamp`]. `t` is clamped to the range `[0-1]`, where 0 yields this
+    /// frame and 1 yields `other`.
+    ///
+    /// ```
+    /// use pwm_pca9685::{ChannelOnOffControl, Frame, Q16};
+    ///
+    /// let to = Frame::new([ChannelOnOffControl::full_on(); 16]);
+    /// let frame = Frame::all_off().lerp(&to, Q16::from_ratio(1, 4).unwrap());
+    /// assert_eq!(Some(1024), frame.channels().first().map(|c| c.off));
+    /// ```
+    pub fn lerp(&self, other: &Frame, t: Q16) -> Frame {
+        let num = t.min(Q16::ONE).to_bits();
+        let den = Q16::ONE.to_bits();
+        let mut channels = *self.channels();
+        for (channel, to) in channels.iter_mut().zip(other.channels()) {
+            *channel = interpolate_channel(channel, to, num, den);
+        }
+        Frame::new(channels)
+    }
+}
+
+/// Pulse start and width in counter steps.
+fn pulse(value: &ChannelOnOffControl, other: &ChannelOnOffControl) -> (u16, u16) {
+    if value.full_off {
+        (pulse_start(other), 0)
+    } else if value.full_on {
+        (pulse_start(other), CYCLE)
+    } else {
+        let width = value.off.wrapping_sub(value.on) % CYCLE;
+        (value.on, width)
+    }
+}
+
+/// Pulse start of a channel keeping the phase of full-on and full-off
+/// channels at 0.
+fn pulse_start(value: &ChannelOnOffControl) -> u16 {
+    if value.full_off || value.full_on {
+        0
+    } else {
+        value.on
+    }
+}
+
+/// Interpolate linearly between `a` and `b` at `num / den`.
+pub(crate) fn interpolate(a: u16, b: u16, num: u32, den: u32) -> u16 {
+    let (low, high) = (a.min(b), a.max(b));
+    let offset = u64::from(high.wrapping_sub(low))
+        .checked_mul(u64::from(num))
+        .and_then(|diff| div_rounded(diff, u64::from(den), Rounding::Nearest))
+        .and_then(|offset| u16::try_from(offset).ok())
+        .unwrap_or(0);
+    if a <= b {
+        a.saturating_add(offset)
+    } else {
+        a.saturating_sub(offset)
+    }
+}
+
+/// Interpolate the control values of a channel between `from` and `to` at
+/// `num / den`.
+pub(crate) fn interpolate_channel(
+    from: &ChannelOnOffControl,
+    to: &ChannelOnOffControl,
+    num: u32,
+    den: u32,
+) -> ChannelOnOffControl {
+    if num == 0 {
+        return *from;
+    }
+    if num >= den {
+        return *to;
+    }
+    let (from_on, from_width) = pulse(from, to);
+    let (to_on, to_width) = pulse(to, from);
+    let on = interpolate(from_on, to_on, num, den);
+    match interpolate(from_width, to_width, num, den) {
+        0 => ChannelOnOffControl::full_off(),
+        CYCLE => ChannelOnOffControl::full_on(),
+        width => ChannelOnOffControl {
+            on,
+            off: on.wrapping_add(width) % CYCLE,
+            ..Default::default()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn duty(on: u16, off: u16) -> ChannelOnOffControl {
+        ChannelOnOffControl {
+            on,
+            off,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn interpolates_pulse_width() {
+        let from = duty(0, 1000);
+        let to = duty(0, 3000);
+        assert_eq!(duty(0, 1500), interpolate_channel(&from, &to, 1, 4));
+        assert_eq!(duty(0, 2500), interpolate_channel(&to, &from, 1, 4));
+    }
+
+    #[test]
+    fn interpolates_from_full_off_keeping_phase() {
+        let from = ChannelOnOffControl::full_off();
+        let to = duty(1000, 3000);
+        assert_eq!(duty(1000, 2000), interpolate_channel(&from, &to, 1, 2));
+    }
+
+    #[test]
+    fn keeps_pulses_wrapping_around_the_cycle() {
+        let from = duty(4000, 100);
+        let to = duty(4000, 300);
+        assert_eq!(duty(4000, 200), interpolate_channel(&from, &to, 1, 2));
+    }
+
+    #[test]
+    fn returns_endpoints_exactly() {
+        let from = ChannelOnOffControl::full_on();
+        let to = duty(10, 20);
+        assert_eq!(from, interpolate_channel(&from, &to, 0, 4));
+        assert_eq!(to, interpolate_channel(&from, &to, 4, 4));
+    }
+
+    #[test]
+    fn ramp_yields_steps_frames() {
+        let to = Frame::new([duty(0, 400); 16]);
+        let ramp = FrameRamp::new(Frame::default(), to, 4);
+        assert_eq!(4, ramp.count());
+        assert_eq!(Frame::new([duty(0, 100); 16]), ramp.at(1, 4));
+        assert_eq!(to, ramp.at(5, 4));
+    }
+
+    #[test]
+    fn lerps_frames() {
+        let from = Frame::new([duty(0, 1000); 16]);
+        let to = Frame::new([duty(0, 3000); 16]);
+        let half = Q16::from_ratio(1, 2).unwrap();
+        assert_eq!(Frame::new([duty(0, 2000); 16]), from.lerp(&to, half));
+        assert_eq!(from, from.lerp(&to, Q16::ZERO));
+        assert_eq!(to, from.lerp(&to, Q16::from_int(2)));
+    }
+
+    #[test]
+    fn applies_easing() {
+        let from = Frame::new([duty(0, 1000); 16]);
+        let to = Frame::new([duty(0, 3000); 16]);
+        let ramp = FrameRamp::new(from, to, 4).with_easing(Easing::QuadIn);
+        let frame = ramp.at(1, 2);
+        assert_eq!(Some(1500), frame.channels().first().map(|c| c.off));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     config::{BitFlagMode1, Config},
-    Error, Pca9685,
+    regs, Error, Pca9685,
 };
 
 #[cfg(not(feature = "async"))]
@@ -8,51 +8,6 @@ use embedded_hal::i2c::I2c;
 #[cfg(feature = "async")]
 use embedded_hal_async::i2c::I2c as AsyncI2c;
 
-pub struct Register;
-impl Register {
-    pub const MODE1: u8 = 0x00;
-    pub const MODE2: u8 = 0x01;
-    pub const SUBADDR1: u8 = 0x02;
-    pub const SUBADDR2: u8 = 0x03;
-    pub const SUBADDR3: u8 = 0x04;
-    pub const ALL_CALL_ADDR: u8 = 0x05;
-    pub const C0_ON_L: u8 = 0x06;
-    pub const C0_OFF_L: u8 = 0x08;
-    pub const C1_ON_L: u8 = 0x0A;
-    pub const C1_OFF_L: u8 = 0x0C;
-    pub const C2_ON_L: u8 = 0x0E;
-    pub const C2_OFF_L: u8 = 0x10;
-    pub const C3_ON_L: u8 = 0x12;
-    pub const C3_OFF_L: u8 = 0x14;
-    pub const C4_ON_L: u8 = 0x16;
-    pub const C4_OFF_L: u8 = 0x18;
-    pub const C5_ON_L: u8 = 0x1A;
-    pub const C5_OFF_L: u8 = 0x1C;
-    pub const C6_ON_L: u8 = 0x1E;
-    pub const C6_OFF_L: u8 = 0x20;
-    pub const C7_ON_L: u8 = 0x22;
-    pub const C7_OFF_L: u8 = 0x24;
-    pub const C8_ON_L: u8 = 0x26;
-    pub const C8_OFF_L: u8 = 0x28;
-    pub const C9_ON_L: u8 = 0x2A;
-    pub const C9_OFF_L: u8 = 0x2C;
-    pub const C10_ON_L: u8 = 0x2E;
-    pub const C10_OFF_L: u8 = 0x30;
-    pub const C11_ON_L: u8 = 0x32;
-    pub const C11_OFF_L: u8 = 0x34;
-    pub const C12_ON_L: u8 = 0x36;
-    pub const C12_OFF_L: u8 = 0x38;
-    pub const C13_ON_L: u8 = 0x3A;
-    pub const C13_OFF_L: u8 = 0x3C;
-    pub const C14_ON_L: u8 = 0x3E;
-    pub const C14_OFF_L: u8 = 0x40;
-    pub const C15_ON_L: u8 = 0x42;
-    pub const C15_OFF_L: u8 = 0x44;
-    pub const ALL_C_ON_L: u8 = 0xFA;
-    pub const ALL_C_OFF_L: u8 = 0xFC;
-    pub const PRE_SCALE: u8 = 0xFE;
-}
-
 #[maybe_async_cfg::maybe(
     sync(
         cfg(not(feature = "async")),
@@ -66,53 +21,109 @@ where
     I2C: AsyncI2c<Error = E>,
 {
     pub(crate) async fn write_mode2(&mut self, config: Config) -> Result<(), Error<E>> {
+        let mode2 = self
+            .merge_foreign_bits(regs::MODE2, self.config.mode2, config.mode2, 0)
+            .await?;
         self.i2c
-            .write(self.address, &[Register::MODE2, config.mode2])
+            .write(self.address, &[regs::MODE2, mode2])
             .await
             .map_err(Error::I2C)?;
-        self.config.mode2 = config.mode2;
+        self.config.mode2 = mode2;
+        self.after_mode_write();
         Ok(())
     }
 
     pub(crate) async fn write_mode1(&mut self, config: Config) -> Result<(), Error<E>> {
+        let mode1 = self
+            .merge_foreign_bits(
+                regs::MODE1,
+                self.config.mode1,
+                config.mode1,
+                BitFlagMode1::Restart as u8,
+            )
+            .await?;
+        self.write_mode1_register(mode1).await
+    }
+
+    /// Write the `MODE1` register without merging the bits changed by others.
+    async fn write_mode1_register(&mut self, mode1: u8) -> Result<(), Error<E>> {
         self.i2c
-            .write(self.address, &[Register::MODE1, config.mode1])
+            .write(self.address, &[regs::MODE1, mode1])
             .await
             .map_err(Error::I2C)?;
-        self.config.mode1 = config.mode1;
+        self.config.mode1 = mode1;
+        self.after_mode_write();
         Ok(())
     }
 
-    pub(crate) async fn enable_auto_increment(&mut self) -> Result<(), Error<E>> {
+    /// In multi-master mode, combine the bits changed by this driver with
+    /// the current contents of the register, except for the `ignored` bits.
+    async fn merge_foreign_bits(
+        &mut self,
+        register: u8,
+        cached: u8,
+        target: u8,
+        ignored: u8,
+    ) -> Result<u8, Error<E>> {
+        if !self.multi_master {
+            return Ok(target);
+        }
+        let changed = cached ^ target;
+        let current = self.read_register(register).await? & !ignored;
+        Ok((current & !changed) | (target & changed))
+    }
+
+    /// Returns whether register auto-increment was enabled in the `MODE1`
+    /// register last written by this driver.
+    ///
+    /// This does not read the device.
+    pub fn is_auto_increment_enabled(&self) -> bool {
+        self.config.is_high(BitFlagMode1::AutoInc)
+    }
+
+    /// Enable register auto-increment if it is not enabled yet.
+    ///
+    /// This is done automatically before writing several registers in a
+    /// single transaction, so calling this is normally not necessary.
+    /// In multi-master mode, the `MODE1` register is read first.
+    pub async fn enable_auto_increment(&mut self) -> Result<(), Error<E>> {
+        if self.multi_master {
+            self.config.mode1 =
+                self.read_register(regs::MODE1).await? & !(BitFlagMode1::Restart as u8);
+        }
         if self.config.is_low(BitFlagMode1::AutoInc) {
-            let config = self.config;
-            self.write_mode1(config.with_high(BitFlagMode1::AutoInc))
-                .await
+            // In multi-master mode, the register contents were just read.
+            let config = self.config.with_high(BitFlagMode1::AutoInc);
+            self.write_mode1_register(config.mode1).await
         } else {
             Ok(())
         }
     }
 
+    /// Disable register auto-increment.
+    ///
+    /// Auto-increment is enabled again automatically by the next operation
+    /// which writes several registers in a single transaction.
+    pub async fn disable_auto_increment(&mut self) -> Result<(), Error<E>> {
+        let config = self.config;
+        self.write_mode1(config.with_low(BitFlagMode1::AutoInc))
+            .await
+    }
+
     pub(crate) async fn write_two_double_registers(
         &mut self,
         address: u8,
         value0: u16,
         value1: u16,
     ) -> Result<(), Error<E>> {
-        self.enable_auto_increment().await?;
-        self.i2c
-            .write(
-                self.address,
-                &[
-                    address,
-                    value0 as u8,
-                    (value0 >> 8) as u8,
-                    value1 as u8,
-                    (value1 >> 8) as u8,
-                ],
-            )
-            .await
-            .map_err(Error::I2C)
+        self.write_register_block(&[
+            address,
+            value0 as u8,
+            (value0 >> 8) as u8,
+            value1 as u8,
+            (value1 >> 8) as u8,
+        ])
+        .await
     }
 
     pub(crate) async fn write_double_register(
@@ -120,13 +131,69 @@ where
         address: u8,
         value: u16,
     ) -> Result<(), Error<E>> {
-        self.enable_auto_increment().await?;
+        self.write_register_block(&[address, value as u8, (value >> 8) as u8])
+            .await
+    }
+
+    /// Write consecutive registers starting at the register address in the
+    /// first byte of `data`.
+    ///
+    /// This is done in a single auto-increment transaction unless single
+    /// register writes have been selected.
+    pub(crate) async fn write_register_block(&mut self, data: &[u8]) -> Result<(), Error<E>> {
+        if !self.single_register_writes {
+            self.enable_auto_increment().await?;
+            self.before_auto_increment_write().await?;
+            return self.i2c.write(self.address, data).await.map_err(Error::I2C);
+        }
+        if let [first, payload @ ..] = data {
+            for (register, value) in (*first..=u8::MAX).zip(payload) {
+                self.i2c
+                    .write(self.address, &[register, *value])
+                    .await
+                    .map_err(Error::I2C)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Select whether registers are written one at a time instead of in
+    /// auto-increment transactions.
+    ///
+    /// Some clone chips and I2C bridges mishandle auto-increment. With this
+    /// enabled, every multi-byte write is performed as one transaction per
+    /// register and auto-increment is not enabled anymore. All operations keep
+    /// working but take more time. Since the outputs change at the end of
+    /// each transaction by default, a channel may briefly show a mix of its
+    /// old and new values. Disabled by default.
+    ///
+    /// This does not have any effect on the hardware.
+    pub fn set_single_register_writes(&mut self, enabled: bool) {
+        self.single_register_writes = enabled;
+    }
+
+    /// Write a value to any register of the device.
+    ///
+    /// **Use with care:** this bypasses all checks of the driver and does not
+    /// update any cached state, so the driver may overwrite the value later or
+    /// behave incorrectly afterwards. After writing the mode registers, call
+    /// [`sync_from_device()`](Pca9685::sync_from_device). Writing reserved
+    /// bits or registers may put the device in an undefined state.
+    pub async fn write_register_raw(&mut self, register: u8, value: u8) -> Result<(), Error<E>> {
         self.i2c
-            .write(self.address, &[address, value as u8, (value >> 8) as u8])
+            .write(self.address, &[register, value])
             .await
             .map_err(Error::I2C)
     }
 
+    /// Read the value of any register of the device.
+    ///
+    /// This does not update any state cached by the driver. Reading the
+    /// reserved registers returns undefined values.
+    pub async fn read_register_raw(&mut self, register: u8) -> Result<u8, Error<E>> {
+        self.read_register(register).await
+    }
+
     pub(crate) async fn read_register(&mut self, address: u8) -> Result<u8, Error<E>> {
         let mut data = [0];
         self.i2c
@@ -135,4 +202,27 @@ where
             .map_err(Error::I2C)
             .and(Ok(data[0]))
     }
+
+    /// Read consecutive registers starting at `address`.
+    ///
+    /// This is done in a single auto-increment transaction unless single
+    /// register writes have been selected.
+    pub(crate) async fn read_register_block(
+        &mut self,
+        address: u8,
+        data: &mut [u8],
+    ) -> Result<(), Error<E>> {
+        if !self.single_register_writes {
+            self.enable_auto_increment().await?;
+            return self
+                .i2c
+                .write_read(self.address, &[address], data)
+                .await
+                .map_err(Error::I2C);
+        }
+        for (register, value) in (address..=u8::MAX).zip(data) {
+            *value = self.read_register(register).await?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,129 @@
+//! Register map of the device
+//!
+//! These are the register addresses and bit masks used by this driver.
+//! They can be useful together with a raw register access or when
+//! simulating the device in tests.
+//!
+//! Each channel has four consecutive PWM control registers: `ON_L`, `ON_H`,
+//! `OFF_L` and `OFF_H`. Only the address of the low byte of each counter
+//! is listed here.
+
+/// Mode register 1.
+pub const MODE1: u8 = 0x00;
+/// Mode register 2.
+pub const MODE2: u8 = 0x01;
+/// I2C-bus subaddress 1.
+pub const SUBADDR1: u8 = 0x02;
+/// I2C-bus subaddress 2.
+pub const SUBADDR2: u8 = 0x03;
+/// I2C-bus subaddress 3.
+pub const SUBADDR3: u8 = 0x04;
+/// LED All Call I2C-bus address.
+pub const ALL_CALL_ADDR: u8 = 0x05;
+/// Channel 0 `ON` counter low byte.
+pub const C0_ON_L: u8 = 0x06;
+/// Channel 0 `OFF` counter low byte.
+pub const C0_OFF_L: u8 = 0x08;
+/// Channel 1 `ON` counter low byte.
+pub const C1_ON_L: u8 = 0x0A;
+/// Channel 1 `OFF` counter low byte.
+pub const C1_OFF_L: u8 = 0x0C;
+/// Channel 2 `ON` counter low byte.
+pub const C2_ON_L: u8 = 0x0E;
+/// Channel 2 `OFF` counter low byte.
+pub const C2_OFF_L: u8 = 0x10;
+/// Channel 3 `ON` counter low byte.
+pub const C3_ON_L: u8 = 0x12;
+/// Channel 3 `OFF` counter low byte.
+pub const C3_OFF_L: u8 = 0x14;
+/// Channel 4 `ON` counter low byte.
+pub const C4_ON_L: u8 = 0x16;
+/// Channel 4 `OFF` counter low byte.
+pub const C4_OFF_L: u8 = 0x18;
+/// Channel 5 `ON` counter low byte.
+pub const C5_ON_L: u8 = 0x1A;
+/// Channel 5 `OFF` counter low byte.
+pub const C5_OFF_L: u8 = 0x1C;
+/// Channel 6 `ON` counter low byte.
+pub const C6_ON_L: u8 = 0x1E;
+/// Channel 6 `OFF` counter low byte.
+pub const C6_OFF_L: u8 = 0x20;
+/// Channel 7 `ON` counter low byte.
+pub const C7_ON_L: u8 = 0x22;
+/// Channel 7 `OFF` counter low byte.
+pub const C7_OFF_L: u8 = 0x24;
+/// Channel 8 `ON` counter low byte.
+pub const C8_ON_L: u8 = 0x26;
+/// Channel 8 `OFF` counter low byte.
+pub const C8_OFF_L: u8 = 0x28;
+/// Channel 9 `ON` counter low byte.
+pub const C9_ON_L: u8 = 0x2A;
+/// Channel 9 `OFF` counter low byte.
+pub const C9_OFF_L: u8 = 0x2C;
+/// Channel 10 `ON` counter low byte.
+pub const C10_ON_L: u8 = 0x2E;
+/// Channel 10 `OFF` counter low byte.
+pub const C10_OFF_L: u8 = 0x30;
+/// Channel 11 `ON` counter low byte.
+pub const C11_ON_L: u8 = 0x32;
+/// Channel 11 `OFF` counter low byte.
+pub const C11_OFF_L: u8 = 0x34;
+/// Channel 12 `ON` counter low byte.
+pub const C12_ON_L: u8 = 0x36;
+/// Channel 12 `OFF` counter low byte.
+pub const C12_OFF_L: u8 = 0x38;
+/// Channel 13 `ON` counter low byte.
+pub const C13_ON_L: u8 = 0x3A;
+/// Channel 13 `OFF` counter low byte.
+pub const C13_OFF_L: u8 = 0x3C;
+/// Channel 14 `ON` counter low byte.
+pub const C14_ON_L: u8 = 0x3E;
+/// Channel 14 `OFF` counter low byte.
+pub const C14_OFF_L: u8 = 0x40;
+/// Channel 15 `ON` counter low byte.
+pub const C15_ON_L: u8 = 0x42;
+/// Channel 15 `OFF` counter low byte.
+pub const C15_OFF_L: u8 = 0x44;
+/// All channels `ON` counter low byte. Write-only.
+pub const ALL_C_ON_L: u8 = 0xFA;
+/// All channels `OFF` counter low byte. Write-only.
+pub const ALL_C_OFF_L: u8 = 0xFC;
+/// Prescaler for the PWM output frequency.
+pub const PRE_SCALE: u8 = 0xFE;
+
+/// Full-on/full-off bit in the `ON_H`/`OFF_H` registers.
+pub const FULL_ON_OFF: u8 = 0b0001_0000;
+
+/// Bit masks of the `MODE1` register
+pub mod mode1 {
+    /// Restart enabled.
+    pub const RESTART: u8 = 0b1000_0000;
+    /// Use the external clock.
+    pub const EXTCLK: u8 = 0b0100_0000;
+    /// Register auto-increment enabled.
+    pub const AI: u8 = 0b0010_0000;
+    /// Low power mode. Oscillator off.
+    pub const SLEEP: u8 = 0b0001_0000;
+    /// Respond to I2C-bus subaddress 1.
+    pub const SUB1: u8 = 0b0000_1000;
+    /// Respond to I2C-bus subaddress 2.
+    pub const SUB2: u8 = 0b0000_0100;
+    /// Respond to I2C-bus subaddress 3.
+    pub const SUB3: u8 = 0b0000_0010;
+    /// Respond to the LED All Call I2C-bus address.
+    pub const ALLCALL: u8 = 0b0000_0001;
+}
+
+/// Bit masks of the `MODE2` register
+pub mod mode2 {
+    /// Output logic state inverted.
+    pub const INVRT: u8 = 0b0001_0000;
+    /// Outputs change on ACK instead of on STOP.
+    pub const OCH: u8 = 0b0000_1000;
+    /// Totem pole output structure instead of open-drain.
+    pub const OUTDRV: u8 = 0b0000_0100;
+    /// Output value when `OE` = 1: bit 1.
+    pub const OUTNE1: u8 = 0b0000_0010;
+    /// Output value when `OE` = 1: bit 0.
+    pub const OUTNE0: u8 = 0b0000_0001;
+}
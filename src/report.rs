@@ -0,0 +1,92 @@
+//! Human-readable device state report
+
+use crate::{Channel, Mode1Flags, Mode2Flags, Pca9685};
+use core::fmt::Write;
+extern crate std;
+use std::string::String;
+
+const MODE1_NAMES: [(Mode1Flags, &str); 8] = [
+    (Mode1Flags::RESTART, "RESTART"),
+    (Mode1Flags::EXTCLK, "EXTCLK"),
+    (Mode1Flags::AI, "AI"),
+    (Mode1Flags::SLEEP, "SLEEP"),
+    (Mode1Flags::SUB1, "SUB1"),
+    (Mode1Flags::SUB2, "SUB2"),
+    (Mode1Flags::SUB3, "SUB3"),
+    (Mode1Flags::ALLCALL, "ALLCALL"),
+];
+
+const MODE2_NAMES: [(Mode2Flags, &str); 5] = [
+    (Mode2Flags::INVRT, "INVRT"),
+    (Mode2Flags::OCH, "OCH"),
+    (Mode2Flags::OUTDRV, "OUTDRV"),
+    (Mode2Flags::OUTNE1, "OUTNE1"),
+    (Mode2Flags::OUTNE0, "OUTNE0"),
+];
+
+fn flag_names<T: Copy>(flags: &[(T, &'static str)], contains: impl Fn(T) -> bool) -> String {
+    let names: std::vec::Vec<_> = flags
+        .iter()
+        .filter(|(flag, _)| contains(*flag))
+        .map(|(_, name)| *name)
+        .collect();
+    names.join(" ")
+}
+
+impl<I2C> Pca9685<I2C> {
+    /// Get a multi-line summary of the device state for logging during
+    /// bring-up.
+    ///
+    /// The summary contains the decoded mode registers, the prescale value
+    /// and PWM frequency as well as the control values and label of each
+    /// channel. It is based on the values last written by this driver.
+    ///
+    /// This does not read the device.
+    pub fn report(&self) -> String {
+        let mode1 = Mode1Flags::from(self.config.mode1);
+        let mode2 = Mode2Flags::from_bits_truncate(self.config.mode2);
+        let mut report = String::new();
+        // Writing to a `String` never fails.
+        let _ = writeln!(report, "PCA9685 at address {:#04x}", self.address);
+        let _ = writeln!(
+            report,
+            "MODE1: {:#04x} [{}]",
+            mode1.bits(),
+            flag_names(&MODE1_NAMES, |f| mode1.contains(f))
+        );
+        let _ = writeln!(
+            report,
+            "MODE2: {:#04x} [{}] {:?}, {:?}, {:?}, disabled outputs {:?}",
+            mode2.bits(),
+            flag_names(&MODE2_NAMES, |f| mode2.contains(f)),
+            mode2.output_logic_state(),
+            mode2.output_change_behavior(),
+            mode2.output_driver(),
+            mode2.disabled_output_value(),
+        );
+        let _ = match self.frequency() {
+            Some(frequency) => writeln!(report, "Prescale: {} ({} Hz)", self.prescale, frequency),
+            None => writeln!(report, "Prescale: {} (external clock)", self.prescale),
+        };
+        for (i, value) in self.outputs.channels().iter().enumerate() {
+            let Ok(channel) = Channel::try_from(i) else {
+                continue;
+            };
+            let _ = write!(
+                report,
+                "{}: on {} off {}",
+                self.channel_name(channel),
+                value.on,
+                value.off
+            );
+            if value.full_on {
+                report.push_str(" full-on");
+            }
+            if value.full_off {
+                report.push_str(" full-off");
+            }
+            report.push('\n');
+        }
+        report
+    }
+}
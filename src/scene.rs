@@ -0,0 +1,81 @@
+//! Named scenes
+
+use crate::Frame;
+use alloc::{string::String, vec::Vec};
+
+/// Store of frames by name
+///
+/// This does not communicate with the device. A scene can be written with
+/// [`set_all_channels()`](crate::Pca9685::set_all_channels).
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct SceneStore {
+    scenes: Vec<(String, Frame)>,
+}
+
+impl SceneStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store a scene, returning the frame previously stored with that name.
+    pub fn insert(&mut self, name: impl Into<String>, frame: Frame) -> Option<Frame> {
+        let name = name.into();
+        match self.scenes.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, stored)) => Some(core::mem::replace(stored, frame)),
+            None => {
+                self.scenes.push((name, frame));
+                None
+            }
+        }
+    }
+
+    /// Get a scene.
+    pub fn get(&self, name: &str) -> Option<&Frame> {
+        self.scenes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, frame)| frame)
+    }
+
+    /// Remove a scene.
+    pub fn remove(&mut self, name: &str) -> Option<Frame> {
+        let index = self.scenes.iter().position(|(n, _)| n == name)?;
+        Some(self.scenes.remove(index).1)
+    }
+
+    /// Iterate over the scene names in insertion order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.scenes.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Number of scenes.
+    pub fn len(&self) -> usize {
+        self.scenes.len()
+    }
+
+    /// Returns whether the store is empty.
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChannelOnOffControl;
+
+    #[test]
+    fn can_store_scenes() {
+        let mut store = SceneStore::new();
+        let on = Frame::new([ChannelOnOffControl::full_on(); 16]);
+        assert_eq!(None, store.insert("off", Frame::all_off()));
+        assert_eq!(None, store.insert("on", on));
+        assert_eq!(Some(on), store.insert("on", on));
+        assert_eq!(Some(&on), store.get("on"));
+        assert!(store.names().eq(["off", "on"]));
+        assert_eq!(Some(Frame::all_off()), store.remove("off"));
+        assert_eq!(None, store.get("off"));
+        assert_eq!(1, store.len());
+    }
+}
@@ -0,0 +1,180 @@
+//! LED strip segments
+
+use crate::{boards::RgbPins, ramp::interpolate, Channel, ChannelOnOffControl, Duty, Frame};
+
+/// Run of logical pixels mapped onto channels
+///
+/// Each pixel is made of `N` channels, for example 1 for single-color LEDs
+/// and 3 for RGB LEDs. The operations render into a [`Frame`] which can
+/// then be written to the device at once, for example with
+/// [`set_all_channels()`](crate::Pca9685::set_all_channels).
+///
+/// A duty cycle of 0 sets a channel to full-off.
+///
+/// ```
+/// use pwm_pca9685::{boards::rgb_consecutive, Duty, Frame, Segment};
+///
+/// let segment = Segment::from_rgb_pins(&rgb_consecutive::LEDS).unwrap();
+/// let mut frame = Frame::all_off();
+/// let red = [Duty::new(4095).unwrap(), Duty::default(), Duty::default()];
+/// segment.fill(&mut frame, red);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment<const N: usize> {
+    pixels: [[Channel; N]; 16],
+    len: usize,
+}
+
+impl<const N: usize> Segment<N> {
+    /// Create a segment from the channels of each pixel.
+    ///
+    /// Returns `None` if a channel is `Channel::All` or appears more than
+    /// once, or if there are no pixels.
+    pub fn new(pixels: &[[Channel; N]]) -> Option<Self> {
+        if pixels.is_empty() || N == 0 {
+            return None;
+        }
+        let mut used = [false; 16];
+        for channel in pixels.iter().flatten() {
+            let slot = channel.index().and_then(|i| used.get_mut(i))?;
+            if *slot {
+                return None;
+            }
+            *slot = true;
+        }
+        let mut segment = Segment {
+            pixels: [[Channel::All; N]; 16],
+            len: pixels.len(),
+        };
+        for (slot, pixel) in segment.pixels.iter_mut().zip(pixels) {
+            *slot = *pixel;
+        }
+        Some(segment)
+    }
+
+    /// Number of pixels.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the segment has no pixels.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the channels of a pixel.
+    pub fn pixel_channels(&self, index: usize) -> Option<[Channel; N]> {
+        self.pixels().get(index).copied()
+    }
+
+    /// Set the duty cycle of the channels of a pixel.
+    ///
+    /// Returns `false` if `index` is out of range.
+    pub fn set_pixel(&self, frame: &mut Frame, index: usize, duty: [Duty; N]) -> bool {
+        match self.pixel_channels(index) {
+            Some(channels) => {
+                for (channel, duty) in channels.iter().zip(duty) {
+                    frame.set(*channel, ChannelOnOffControl::from(duty));
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set all pixels to the same duty cycles.
+    pub fn fill(&self, frame: &mut Frame, duty: [Duty; N]) {
+        for index in 0..self.len {
+            self.set_pixel(frame, index, duty);
+        }
+    }
+
+    /// Set the pixels to a linear gradient from the first to the last pixel.
+    pub fn gradient(&self, frame: &mut Frame, from: [Duty; N], to: [Duty; N]) {
+        let last = self.len.saturating_sub(1).max(1) as u32;
+        for index in 0..self.len {
+            let mut duty = from;
+            for (d, to) in duty.iter_mut().zip(to) {
+                let count = interpolate(d.count(), to.count(), index as u32, last);
+                *d = Duty::new(count).unwrap_or(to);
+            }
+            self.set_pixel(frame, index, duty);
+        }
+    }
+
+    fn pixels(&self) -> &[[Channel; N]] {
+        self.pixels.get(..self.len).unwrap_or(&[])
+    }
+}
+
+impl Segment<3> {
+    /// Create a segment of RGB pixels.
+    ///
+    /// The duty cycles of each pixel are given in red, green, blue order.
+    ///
+    /// Returns `None` if a channel is `Channel::All` or appears more than
+    /// once, or if there are no pixels.
+    pub fn from_rgb_pins(pins: &[RgbPins]) -> Option<Self> {
+        let mut pixels = [[Channel::All; 3]; 16];
+        let pixels = pixels.get_mut(..pins.len())?;
+        for (pixel, pins) in pixels.iter_mut().zip(pins) {
+            *pixel = [pins.red, pins.green, pins.blue];
+        }
+        Segment::new(pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn duty(count: u16) -> Duty {
+        Duty::new(count).unwrap()
+    }
+
+    #[test]
+    fn rejects_invalid_channels() {
+        assert_eq!(None, Segment::<1>::new(&[]));
+        assert_eq!(None, Segment::new(&[[Channel::All]]));
+        assert_eq!(None, Segment::new(&[[Channel::C0], [Channel::C0]]));
+    }
+
+    #[test]
+    fn can_set_pixel() {
+        let segment = Segment::new(&[[Channel::C4, Channel::C2]]).unwrap();
+        let mut frame = Frame::all_off();
+        assert!(segment.set_pixel(&mut frame, 0, [duty(100), duty(0)]));
+        assert!(!segment.set_pixel(&mut frame, 1, [duty(100), duty(0)]));
+        assert_eq!(Some(100), frame.get(Channel::C4).map(|c| c.off));
+        assert_eq!(
+            Some(ChannelOnOffControl::full_off()),
+            frame.get(Channel::C2)
+        );
+    }
+
+    #[test]
+    fn can_render_gradient() {
+        let segment = Segment::new(&[[Channel::C0], [Channel::C1], [Channel::C2]]).unwrap();
+        let mut frame = Frame::all_off();
+        segment.gradient(&mut frame, [duty(4000)], [duty(1000)]);
+        let off = |c| frame.get(c).map(|v| v.off);
+        assert_eq!(Some(4000), off(Channel::C0));
+        assert_eq!(Some(2500), off(Channel::C1));
+        assert_eq!(Some(1000), off(Channel::C2));
+    }
+
+    #[test]
+    fn can_group_rgb_pins() {
+        let pins = RgbPins {
+            red: Channel::C9,
+            green: Channel::C10,
+            blue: Channel::C11,
+        };
+        let segment = Segment::from_rgb_pins(&[pins]).unwrap();
+        assert_eq!(1, segment.len());
+        assert_eq!(
+            Some([Channel::C9, Channel::C10, Channel::C11]),
+            segment.pixel_channels(0)
+        );
+    }
+}
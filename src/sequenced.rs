@@ -0,0 +1,63 @@
+//! Staged power-up sequencing
+
+use crate::{ChannelOnOffControl, Error, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+#[cfg(feature = "async")]
+use embedded_hal_async::{delay::DelayNs as AsyncDelayNs, i2c::I2c as AsyncI2c};
+
+/// Grouping of the channels brought up by
+/// [`enable_sequenced()`](Pca9685::enable_sequenced)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Stagger {
+    /// Number of consecutive channels brought up at once in the range `[1-16]`.
+    pub group_size: u8,
+    /// Delay between groups in microseconds.
+    pub delay_us: u32,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c")),
+        idents(AsyncDelayNs(sync = "DelayNs"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Enable the controller bringing the channels up one group at a time.
+    ///
+    /// All channels are set to full-off before enabling the controller. Then
+    /// the control values last written to each group of consecutive channels
+    /// are restored in order, waiting `stagger.delay_us` between groups. This
+    /// limits the inrush current of large loads at startup.
+    ///
+    /// Returns `Error::InvalidInputData` if the group size is out of range.
+    pub async fn enable_sequenced(
+        &mut self,
+        delay: &mut impl AsyncDelayNs,
+        stagger: Stagger,
+    ) -> Result<(), Error<E>> {
+        if !(1..=16).contains(&stagger.group_size) {
+            return Err(Error::InvalidInputData);
+        }
+        let target = self.outputs;
+        self.write_channels(0, &[ChannelOnOffControl::full_off(); 16])
+            .await?;
+        self.enable().await?;
+        let group_size = usize::from(stagger.group_size);
+        for (i, group) in target.channels().chunks(group_size).enumerate() {
+            if i > 0 {
+                delay.delay_us(stagger.delay_us).await;
+            }
+            let first = i.checked_mul(group_size).ok_or(Error::InvalidInputData)?;
+            self.write_channels(first, group).await?;
+        }
+        Ok(())
+    }
+}
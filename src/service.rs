@@ -0,0 +1,228 @@
+//! Background service thread
+
+use crate::{Easing, Error, Frame, FrameRamp, Pca9685};
+use embedded_hal::i2c::I2c;
+extern crate std;
+use std::{
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex, PoisonError,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// Command sent to a [`Pca9685Service`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ServiceCommand {
+    /// Write a frame to all channels, cancelling any running fade.
+    Frame(Frame),
+    /// Fade from the current outputs to a frame.
+    Fade {
+        /// Frame at the end of the fade.
+        to: Frame,
+        /// Duration of the fade in milliseconds.
+        duration_ms: u32,
+        /// Shape of the fade.
+        easing: Easing,
+    },
+    /// Enable the failsafe timeout. See: [`enable_failsafe()`](Pca9685::enable_failsafe).
+    EnableFailsafe {
+        /// Frame written when the timeout expires.
+        safe_frame: Frame,
+        /// Timeout in milliseconds.
+        timeout_ms: u32,
+    },
+    /// Disable the failsafe timeout.
+    DisableFailsafe,
+    /// Restart the failsafe timeout.
+    FeedFailsafe,
+}
+
+#[derive(Debug)]
+enum Message {
+    Command(ServiceCommand),
+    Stop,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Fade {
+    ramp: FrameRamp,
+    start_ms: u32,
+    duration_ms: u32,
+}
+
+/// Driver running on its own thread
+///
+/// The service owns the driver and processes [`ServiceCommand`]s received
+/// over a channel. Sending a command never blocks on I2C. Between commands,
+/// the service advances fades and calls [`tick()`](Pca9685::tick) every
+/// `period`, so that failsafe timeouts, blinking and other time-based
+/// features keep running. The service clock starts at 0 ms when it is spawned.
+///
+/// If an operation fails, the service keeps running and the error can be
+/// retrieved with [`take_error()`](Pca9685Service::take_error).
+///
+/// ```no_run
+/// use linux_embedded_hal::I2cdev;
+/// use pwm_pca9685::{Address, Easing, Frame, Pca9685, Pca9685Service, ServiceCommand};
+/// use std::time::Duration;
+///
+/// let dev = I2cdev::new("/dev/i2c-1").unwrap();
+/// let mut pwm = Pca9685::new(dev, Address::default()).unwrap();
+/// pwm.enable().unwrap();
+/// let service = Pca9685Service::spawn(pwm, Duration::from_millis(10));
+/// let sender = service.sender();
+/// std::thread::spawn(move || {
+///     let to = Frame::all_off();
+///     sender.send(ServiceCommand::Fade { to, duration_ms: 500, easing: Easing::SineInOut })
+/// });
+/// let pwm = service.stop().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Pca9685Service<I2C, E> {
+    sender: Sender<Message>,
+    error: Arc<Mutex<Option<Error<E>>>>,
+    thread: JoinHandle<Pca9685<I2C>>,
+}
+
+/// Cloneable sender of commands to a [`Pca9685Service`]
+#[derive(Debug)]
+pub struct ServiceSender {
+    sender: Sender<Message>,
+}
+
+impl Clone for ServiceSender {
+    fn clone(&self) -> Self {
+        ServiceSender {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl ServiceSender {
+    /// Send a command to the service.
+    ///
+    /// If the service has been stopped, the command is returned as the error.
+    pub fn send(&self, command: ServiceCommand) -> Result<(), ServiceCommand> {
+        self.sender
+            .send(Message::Command(command))
+            .map_err(|_| command)
+    }
+}
+
+impl<I2C, E> Pca9685Service<I2C, E>
+where
+    I2C: I2c<Error = E> + Send + 'static,
+    E: Send + 'static,
+{
+    /// Move the driver to a new thread which processes commands and
+    /// advances time-based features every `period`.
+    pub fn spawn(pwm: Pca9685<I2C>, period: Duration) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let error = Arc::new(Mutex::new(None));
+        let worker_error = Arc::clone(&error);
+        let thread = thread::spawn(move || run(pwm, receiver, period, &worker_error));
+        Pca9685Service {
+            sender,
+            error,
+            thread,
+        }
+    }
+
+    /// Send a command to the service.
+    ///
+    /// If the service thread has exited, the command is returned as the error.
+    pub fn send(&self, command: ServiceCommand) -> Result<(), ServiceCommand> {
+        self.sender().send(command)
+    }
+
+    /// Get a cloneable sender which can be moved to other threads.
+    pub fn sender(&self) -> ServiceSender {
+        ServiceSender {
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Take the last error produced by the service, if any.
+    pub fn take_error(&self) -> Option<Error<E>> {
+        self.error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+
+    /// Stop the service after processing the commands sent so far and get
+    /// the driver back.
+    ///
+    /// Returns `None` if the service thread panicked.
+    pub fn stop(self) -> Option<Pca9685<I2C>> {
+        // If the thread has already exited, joining it reports why.
+        let _ = self.sender.send(Message::Stop);
+        self.thread.join().ok()
+    }
+}
+
+fn run<I2C, E>(
+    mut pwm: Pca9685<I2C>,
+    receiver: Receiver<Message>,
+    period: Duration,
+    error: &Mutex<Option<Error<E>>>,
+) -> Pca9685<I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    let start = Instant::now();
+    let mut fade: Option<Fade> = None;
+    let record = |result: Result<(), Error<E>>| {
+        if let Err(e) = result {
+            *error.lock().unwrap_or_else(PoisonError::into_inner) = Some(e);
+        }
+    };
+    loop {
+        let message = receiver.recv_timeout(period);
+        let now_ms = start.elapsed().as_millis() as u32;
+        match message {
+            Ok(Message::Stop) | Err(RecvTimeoutError::Disconnected) => return pwm,
+            Err(RecvTimeoutError::Timeout) => (),
+            Ok(Message::Command(command)) => match command {
+                ServiceCommand::Frame(frame) => {
+                    fade = None;
+                    record(pwm.set_all_channels(frame.channels()));
+                }
+                ServiceCommand::Fade {
+                    to,
+                    duration_ms,
+                    easing,
+                } => {
+                    fade = Some(Fade {
+                        ramp: FrameRamp::new(pwm.outputs, to, 1).with_easing(easing),
+                        start_ms: now_ms,
+                        duration_ms,
+                    });
+                }
+                ServiceCommand::EnableFailsafe {
+                    safe_frame,
+                    timeout_ms,
+                } => pwm.enable_failsafe(safe_frame, timeout_ms, now_ms),
+                ServiceCommand::DisableFailsafe => pwm.disable_failsafe(),
+                ServiceCommand::FeedFailsafe => pwm.feed_failsafe(now_ms),
+            },
+        }
+        if let Some(f) = fade {
+            let elapsed = now_ms.wrapping_sub(f.start_ms);
+            let frame = f.ramp.at(elapsed, f.duration_ms);
+            if frame != pwm.outputs {
+                record(pwm.set_all_channels(frame.channels()));
+            }
+            if elapsed >= f.duration_ms {
+                fade = None;
+            }
+        }
+        let was_triggered = pwm.is_failsafe_triggered();
+        record(pwm.tick(now_ms));
+        if !was_triggered && pwm.is_failsafe_triggered() {
+            fade = None;
+        }
+    }
+}
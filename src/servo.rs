@@ -0,0 +1,395 @@
+//! Servo control
+
+use crate::{
+    channels::OutputKind, limits::Limited, math::div_rounded, Channel, ChannelOnOffControl, Error,
+    Pca9685, Rounding,
+};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Servo pulse width range configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ServoConfig {
+    /// Pulse width in microseconds corresponding to an angle of 0 degrees.
+    pub min_pulse_us: u16,
+    /// Pulse width in microseconds corresponding to `max_angle_deg`.
+    pub max_pulse_us: u16,
+    /// Maximum angle in degrees.
+    pub max_angle_deg: u16,
+}
+
+/// Pulse range of 1000-2000 µs for an angle range of 180 degrees.
+impl Default for ServoConfig {
+    fn default() -> Self {
+        ServoConfig {
+            min_pulse_us: 1000,
+            max_pulse_us: 2000,
+            max_angle_deg: 180,
+        }
+    }
+}
+
+impl ServoConfig {
+    /// Pulse width in microseconds for an angle in degrees.
+    ///
+    /// Returns `None` if the angle is out of range.
+    pub fn pulse_us(&self, angle_deg: u16) -> Option<u16> {
+        if angle_deg > self.max_angle_deg || self.max_angle_deg == 0 {
+            return None;
+        }
+        let min = i64::from(self.min_pulse_us);
+        let span = i64::from(self.max_pulse_us).checked_sub(min)?;
+        let max_angle = i64::from(self.max_angle_deg);
+        let offset = span.checked_mul(i64::from(angle_deg))?;
+        let half = offset.signum().checked_mul(max_angle)?.checked_div(2)?;
+        let offset = offset.checked_add(half)?.checked_div(max_angle)?;
+        u16::try_from(min.checked_add(offset)?).ok()
+    }
+}
+
+/// Idle timeout state of a servo channel.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ServoIdle {
+    timeout_ms: u32,
+    /// Whether the channel has been commanded since the timeout was set.
+    commanded: bool,
+    /// Time of the first tick after the last servo command.
+    since_ms: Option<u32>,
+    /// Whether the pulses are currently switched off.
+    idle: bool,
+}
+
+impl ServoIdle {
+    /// State of a channel not commanded since the timeout was set.
+    pub(crate) fn new(timeout_ms: u32) -> Self {
+        ServoIdle {
+            timeout_ms,
+            commanded: false,
+            since_ms: None,
+            idle: false,
+        }
+    }
+
+    pub(crate) fn timeout_ms(&self) -> u32 {
+        self.timeout_ms
+    }
+}
+
+/// Number of counter steps corresponding to a duration of
+/// `us_num / us_den` microseconds for a prescale value and an oscillator
+/// frequency.
+pub(crate) fn us_to_counts(
+    osc_hz: u32,
+    prescale: u8,
+    us_num: u64,
+    us_den: u64,
+    rounding: Rounding,
+) -> Option<u64> {
+    let num = us_num.checked_mul(u64::from(osc_hz))?;
+    let den = us_den
+        .checked_mul(1_000_000)?
+        .checked_mul(u64::from(prescale).checked_add(1)?)?;
+    div_rounded(num, den, rounding)
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Set the pulse width of a servo channel in microseconds.
+    ///
+    /// The channel is switched on at the beginning of each PWM cycle. The pulse
+    /// width is converted into counter steps using the last prescale value set,
+    /// the frequency of the internal oscillator and the selected rounding.
+    ///
+    /// Returns `Error::InvalidInputData` if the pulse does not fit in a cycle.
+    pub async fn set_servo_pulse_us(
+        &mut self,
+        channel: Channel,
+        pulse_us: u16,
+    ) -> Result<(), Error<E>> {
+        self.write_servo_pulse(channel, u64::from(pulse_us), 1)
+            .await
+    }
+
+    /// Set the angle of a servo channel in degrees.
+    ///
+    /// The angle is mapped linearly to the pulse width range of the servo
+    /// configuration. The selected rounding is applied once to the resulting
+    /// counter value. Returns `Error::InvalidInputData` if the angle is greater
+    /// than `config.max_angle_deg`.
+    pub async fn set_servo_angle(
+        &mut self,
+        channel: Channel,
+        config: &ServoConfig,
+        angle_deg: u16,
+    ) -> Result<(), Error<E>> {
+        if angle_deg > config.max_angle_deg || config.max_angle_deg == 0 {
+            return Err(Error::InvalidInputData);
+        }
+        let max_angle = i64::from(config.max_angle_deg);
+        let min = i64::from(config.min_pulse_us);
+        // pulse width in microseconds multiplied by max_angle
+        let pulse = i64::from(config.max_pulse_us)
+            .checked_sub(min)
+            .and_then(|span| span.checked_mul(i64::from(angle_deg)))
+            .and_then(|offset| min.checked_mul(max_angle)?.checked_add(offset))
+            .and_then(|pulse| u64::try_from(pulse).ok())
+            .ok_or(Error::InvalidInputData)?;
+        self.write_servo_pulse(channel, pulse, max_angle as u64)
+            .await
+    }
+
+    pub(crate) async fn write_servo_pulse(
+        &mut self,
+        channel: Channel,
+        us_num: u64,
+        us_den: u64,
+    ) -> Result<(), Error<E>> {
+        let off = us_to_counts(
+            self.oscillator_hz(),
+            self.prescale,
+            us_num,
+            us_den,
+            self.rounding,
+        )
+        .filter(|off| *off <= 4095)
+        .ok_or(Error::InvalidInputData)?;
+        self.check_channel_mode(channel, OutputKind::Servo)?;
+        let pulse = |off| ChannelOnOffControl {
+            off,
+            ..Default::default()
+        };
+        let mut written = [channel.index().is_none(); 16];
+        match (self.apply_limits(channel, off as u16), channel.index()) {
+            (Limited::Skip, _) => return Ok(()),
+            (Limited::Uniform(off), Some(i)) => {
+                self.write_channels(i, &[pulse(off)]).await?;
+                if let Some(w) = written.get_mut(i) {
+                    *w = true;
+                }
+            }
+            (Limited::Uniform(off), None) => self.write_channels(0, &[pulse(off); 16]).await?,
+            (Limited::PerChannel(counts), _) => {
+                let mut values = *self.outputs.channels();
+                for ((value, count), w) in values.iter_mut().zip(counts).zip(&mut written) {
+                    *w = count.is_some();
+                    if let Some(off) = count {
+                        *value = pulse(off);
+                    }
+                }
+                self.write_channels(0, &values).await?;
+            }
+        }
+        for (state, written) in self.servo_idle.iter_mut().zip(written) {
+            if !written {
+                continue;
+            }
+            if let Some(state) = state {
+                state.commanded = true;
+                state.since_ms = None;
+                state.idle = false;
+            }
+        }
+        Ok(())
+    }
+
+    /// Set a channel to full-off once its servo position has not been
+    /// commanded for `timeout_ms` milliseconds, or disable this with `None`.
+    ///
+    /// This reduces buzz, heat and power draw of servos holding a static
+    /// position. The timeout starts on the first call to
+    /// [`tick()`](Pca9685::tick) after each call to
+    /// [`set_servo_pulse_us()`](Pca9685::set_servo_pulse_us) or
+    /// [`set_servo_angle()`](Pca9685::set_servo_angle), and the next of these
+    /// calls enables the pulses again. Channels which have not been commanded
+    /// since the timeout was set are not affected. `Channel::All` configures
+    /// every channel.
+    ///
+    /// This does not have any effect on the hardware.
+    pub fn set_servo_idle_timeout(&mut self, channel: Channel, timeout_ms: Option<u32>) {
+        for (i, state) in self.servo_idle.iter_mut().enumerate() {
+            if channel.index().is_some_and(|index| index != i) {
+                continue;
+            }
+            *state = timeout_ms.map(ServoIdle::new);
+        }
+    }
+
+    /// Returns whether the pulses of a servo channel have been switched off
+    /// by the idle timeout.
+    ///
+    /// Returns `false` for `Channel::All`.
+    pub fn is_servo_idle(&self, channel: Channel) -> bool {
+        channel
+            .index()
+            .and_then(|i| self.servo_idle.get(i))
+            .is_some_and(|state| state.is_some_and(|s| s.idle))
+    }
+
+    pub(crate) async fn service_servo_idle(&mut self, now_ms: u32) -> Result<(), Error<E>> {
+        for i in 0..self.servo_idle.len() {
+            let Some(Some(state)) = self.servo_idle.get_mut(i) else {
+                continue;
+            };
+            if state.idle || !state.commanded {
+                continue;
+            }
+            let since_ms = *state.since_ms.get_or_insert(now_ms);
+            if now_ms.wrapping_sub(since_ms) >= state.timeout_ms {
+                state.idle = true;
+                self.write_channels(i, &[ChannelOnOffControl::full_off()])
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Guided search for the pulse width endpoints of a servo
+///
+/// Starting from an initial configuration, the minimum and maximum pulse
+/// widths are nudged in small steps while the servo is watched. Each nudge
+/// moves the servo to the new endpoint, so that the endpoint can be pushed
+/// until the servo reaches its mechanical limit and backed off from there.
+/// [`accept()`](CalibrationSession::accept) then yields the calibrated
+/// [`ServoConfig`].
+#[derive(Debug)]
+pub struct CalibrationSession<'a, I2C> {
+    pwm: &'a mut Pca9685<I2C>,
+    channel: Channel,
+    config: ServoConfig,
+}
+
+impl<'a, I2C> CalibrationSession<'a, I2C> {
+    /// Start calibrating the servo on a channel from an initial configuration.
+    ///
+    /// This does not have any effect on the hardware.
+    pub fn new(pwm: &'a mut Pca9685<I2C>, channel: Channel, initial: ServoConfig) -> Self {
+        CalibrationSession {
+            pwm,
+            channel,
+            config: initial,
+        }
+    }
+
+    /// Get the configuration found so far.
+    pub fn config(&self) -> ServoConfig {
+        self.config
+    }
+
+    /// Finish the calibration and get the configuration found.
+    pub fn accept(self) -> ServoConfig {
+        self.config
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "CalibrationSession",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<'a, I2C, E> CalibrationSession<'a, I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Change the minimum pulse width by `delta_us` microseconds and move
+    /// the servo there.
+    ///
+    /// Returns `Error::InvalidInputData` and keeps the current value if the
+    /// minimum pulse width would not be shorter than the maximum.
+    pub async fn nudge_min(&mut self, delta_us: i16) -> Result<(), Error<E>> {
+        let min = self
+            .config
+            .min_pulse_us
+            .checked_add_signed(delta_us)
+            .filter(|min| *min < self.config.max_pulse_us)
+            .ok_or(Error::InvalidInputData)?;
+        self.pwm.set_servo_pulse_us(self.channel, min).await?;
+        self.config.min_pulse_us = min;
+        Ok(())
+    }
+
+    /// Change the maximum pulse width by `delta_us` microseconds and move
+    /// the servo there.
+    ///
+    /// Returns `Error::InvalidInputData` and keeps the current value if the
+    /// maximum pulse width would not be longer than the minimum.
+    pub async fn nudge_max(&mut self, delta_us: i16) -> Result<(), Error<E>> {
+        let max = self
+            .config
+            .max_pulse_us
+            .checked_add_signed(delta_us)
+            .filter(|max| *max > self.config.min_pulse_us)
+            .ok_or(Error::InvalidInputData)?;
+        self.pwm.set_servo_pulse_us(self.channel, max).await?;
+        self.config.max_pulse_us = max;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OSC: u32 = crate::prescale::INTERNAL_OSC_HZ;
+
+    #[test]
+    fn can_convert_us_to_counts() {
+        // 1 ms = 247.52 counts
+        assert_eq!(
+            Some(248),
+            us_to_counts(OSC, 100, 1000, 1, Rounding::Nearest)
+        );
+        assert_eq!(Some(247), us_to_counts(OSC, 100, 1000, 1, Rounding::Down));
+        // 1 ms = 806.45 counts
+        assert_eq!(Some(806), us_to_counts(OSC, 30, 1000, 1, Rounding::Nearest));
+        assert_eq!(Some(807), us_to_counts(OSC, 30, 1000, 1, Rounding::Up));
+        assert_eq!(None, us_to_counts(OSC, 30, 1000, 1, Rounding::Exact));
+    }
+
+    #[test]
+    fn can_convert_angle_to_pulse() {
+        let config = ServoConfig::default();
+        assert_eq!(Some(1000), config.pulse_us(0));
+        assert_eq!(Some(1500), config.pulse_us(90));
+        assert_eq!(Some(2000), config.pulse_us(180));
+        assert_eq!(None, config.pulse_us(181));
+    }
+
+    #[test]
+    fn can_convert_angle_to_pulse_reversed_range() {
+        let config = ServoConfig {
+            min_pulse_us: 2000,
+            max_pulse_us: 1000,
+            max_angle_deg: 90,
+        };
+        assert_eq!(Some(2000), config.pulse_us(0));
+        assert_eq!(Some(1500), config.pulse_us(45));
+        assert_eq!(Some(1000), config.pulse_us(90));
+    }
+
+    #[test]
+    fn can_convert_angle_to_pulse_full_range() {
+        let config = ServoConfig {
+            min_pulse_us: 0,
+            max_pulse_us: u16::MAX,
+            max_angle_deg: u16::MAX,
+        };
+        assert_eq!(Some(u16::MAX), config.pulse_us(u16::MAX));
+    }
+}
@@ -0,0 +1,134 @@
+//! Thread-safe shared driver
+
+use crate::{Channel, Error, IntoDuty, Pca9685};
+use embedded_hal::{
+    i2c::I2c,
+    pwm::{ErrorType, SetDutyCycle},
+};
+extern crate std;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Driver shared between threads
+///
+/// Clones refer to the same device. Each operation locks the driver for its
+/// duration.
+///
+/// ```no_run
+/// use linux_embedded_hal::I2cdev;
+/// use pwm_pca9685::{Address, Channel, Pca9685, SharedPca9685};
+///
+/// let dev = I2cdev::new("/dev/i2c-1").unwrap();
+/// let pwm = SharedPca9685::new(Pca9685::new(dev, Address::default()).unwrap());
+/// let mut led = pwm.channel(Channel::C0);
+/// std::thread::spawn(move || led.set_duty(2047_u16).unwrap());
+/// pwm.lock().enable().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct SharedPca9685<I2C> {
+    inner: Arc<Mutex<Pca9685<I2C>>>,
+}
+
+impl<I2C> Clone for SharedPca9685<I2C> {
+    fn clone(&self) -> Self {
+        SharedPca9685 {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<I2C> SharedPca9685<I2C> {
+    /// Share a driver.
+    pub fn new(pwm: Pca9685<I2C>) -> Self {
+        SharedPca9685 {
+            inner: Arc::new(Mutex::new(pwm)),
+        }
+    }
+
+    /// Lock the driver for exclusive access.
+    ///
+    /// If another thread panicked while holding the lock, the driver is
+    /// returned anyway.
+    pub fn lock(&self) -> MutexGuard<'_, Pca9685<I2C>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Get the driver back if this is the last reference to it.
+    ///
+    /// Otherwise the shared driver is returned as the error.
+    pub fn try_into_inner(self) -> Result<Pca9685<I2C>, Self> {
+        Arc::try_unwrap(self.inner)
+            .map(|mutex| mutex.into_inner().unwrap_or_else(PoisonError::into_inner))
+            .map_err(|inner| SharedPca9685 { inner })
+    }
+
+    /// Get a cloneable handle to a single channel.
+    ///
+    /// `Channel::All` controls all channels at once.
+    pub fn channel(&self, channel: Channel) -> SharedChannel<I2C> {
+        SharedChannel {
+            pwm: self.clone(),
+            channel,
+        }
+    }
+}
+
+/// Cloneable handle to a single channel of a [`SharedPca9685`]
+///
+/// This implements [`SetDutyCycle`] with the same values as
+/// [`ChannelHandle`](crate::ChannelHandle).
+#[derive(Debug)]
+pub struct SharedChannel<I2C> {
+    pwm: SharedPca9685<I2C>,
+    channel: Channel,
+}
+
+impl<I2C> Clone for SharedChannel<I2C> {
+    fn clone(&self) -> Self {
+        SharedChannel {
+            pwm: self.pwm.clone(),
+            channel: self.channel,
+        }
+    }
+}
+
+impl<I2C, E> SharedChannel<I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    /// Get the channel controlled by this handle.
+    pub fn channel_id(&self) -> Channel {
+        self.channel
+    }
+
+    /// Set the duty cycle. See: [`set_channel_duty()`](Pca9685::set_channel_duty).
+    pub fn set_duty(&mut self, duty: impl IntoDuty) -> Result<(), Error<E>> {
+        self.pwm.lock().set_channel_duty(self.channel, duty)
+    }
+
+    /// Set the _on_ and _off_ counters. See: [`set_channel_on_off()`](Pca9685::set_channel_on_off).
+    pub fn set_on_off(&mut self, on: u16, off: u16) -> Result<(), Error<E>> {
+        self.pwm.lock().set_channel_on_off(self.channel, on, off)
+    }
+}
+
+impl<I2C, E> ErrorType for SharedChannel<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: core::fmt::Debug,
+{
+    type Error = Error<E>;
+}
+
+impl<I2C, E> SetDutyCycle for SharedChannel<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: core::fmt::Debug,
+{
+    fn max_duty_cycle(&self) -> u16 {
+        self.pwm.lock().channel(self.channel).max_duty_cycle()
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.pwm.lock().channel(self.channel).set_duty_cycle(duty)
+    }
+}
@@ -0,0 +1,146 @@
+//! Blinking slower than the minimum PWM frequency
+
+use crate::{Channel, ChannelOnOffControl, Error, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Slow blink timing
+///
+/// A blinking channel alternates between its duty cycle for `on_ms`
+/// milliseconds and full-off for the rest of each period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SlowBlink {
+    /// Blink period in milliseconds.
+    pub period_ms: u32,
+    /// Time the channel is on in each period in milliseconds.
+    pub on_ms: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BlinkState {
+    blink: SlowBlink,
+    start_ms: u32,
+    duty: ChannelOnOffControl,
+    original: ChannelOnOffControl,
+    lit: bool,
+}
+
+impl BlinkState {
+    fn is_lit_at(&self, now_ms: u32) -> bool {
+        let elapsed = now_ms.wrapping_sub(self.start_ms);
+        elapsed.checked_rem(self.blink.period_ms).unwrap_or(0) < self.blink.on_ms
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Start blinking a channel at a rate below the minimum PWM frequency.
+    ///
+    /// The channel alternates between its current duty cycle and full-off on
+    /// each call to [`tick()`](Pca9685::tick). If the channel is currently
+    /// full-off, it is set to full-on while lit. The period starts at `now_ms`
+    /// with the channel lit. `Channel::All` starts blinking every channel.
+    ///
+    /// Nothing is written to the device by this method. Blinking stops when
+    /// the failsafe timeout is triggered.
+    ///
+    /// Returns `Error::InvalidInputData` if the period is 0 or `on_ms` is
+    /// greater than the period.
+    pub fn start_slow_blink(
+        &mut self,
+        channel: Channel,
+        blink: SlowBlink,
+        now_ms: u32,
+    ) -> Result<(), Error<E>> {
+        if blink.period_ms == 0 || blink.on_ms > blink.period_ms {
+            return Err(Error::InvalidInputData);
+        }
+        let outputs = self.outputs;
+        for (i, (state, value)) in self
+            .slow_blink
+            .iter_mut()
+            .zip(outputs.channels())
+            .enumerate()
+        {
+            if channel.index().is_some_and(|index| index != i) {
+                continue;
+            }
+            let duty = if value.full_off {
+                ChannelOnOffControl::full_on()
+            } else {
+                *value
+            };
+            *state = Some(BlinkState {
+                blink,
+                start_ms: now_ms,
+                duty,
+                original: *value,
+                lit: !value.full_off,
+            });
+        }
+        Ok(())
+    }
+
+    /// Stop blinking a channel and restore the control values it had when
+    /// blinking started.
+    ///
+    /// `Channel::All` stops blinking every channel.
+    pub async fn stop_slow_blink(&mut self, channel: Channel) -> Result<(), Error<E>> {
+        for i in 0..self.slow_blink.len() {
+            if channel.index().is_some_and(|index| index != i) {
+                continue;
+            }
+            let state = self.slow_blink.get_mut(i).and_then(Option::take);
+            if let Some(state) = state {
+                if self.outputs.channels().get(i) != Some(&state.original) {
+                    self.write_channels(i, &[state.original]).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether a channel is blinking.
+    ///
+    /// Returns `false` for `Channel::All`.
+    pub fn is_slow_blinking(&self, channel: Channel) -> bool {
+        channel
+            .index()
+            .and_then(|i| self.slow_blink.get(i))
+            .is_some_and(Option::is_some)
+    }
+
+    pub(crate) async fn service_slow_blink(&mut self, now_ms: u32) -> Result<(), Error<E>> {
+        for i in 0..self.slow_blink.len() {
+            let Some(Some(state)) = self.slow_blink.get(i).copied() else {
+                continue;
+            };
+            let lit = state.is_lit_at(now_ms);
+            if lit != state.lit {
+                let value = if lit {
+                    state.duty
+                } else {
+                    ChannelOnOffControl::full_off()
+                };
+                self.write_channels(i, &[value]).await?;
+                if let Some(Some(state)) = self.slow_blink.get_mut(i) {
+                    state.lit = lit;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+//! Binary snapshot of the driver state
+
+use crate::{ChannelOnOffControl, DeviceConfig, Error, Frame, Mode1Flags, Mode2Flags, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Version of the snapshot layout.
+const FORMAT_VERSION: u8 = 1;
+
+/// Configuration and channel control values of the device
+///
+/// The snapshot can be converted to and from bytes with a stable layout, so
+/// that it can be stored in EEPROM or flash memory:
+///
+/// | Byte    | Content                                                  |
+/// |---------|----------------------------------------------------------|
+/// | 0       | Layout version (1)                                       |
+/// | 1       | `MODE1` register                                         |
+/// | 2       | `MODE2` register                                         |
+/// | 3       | `PRE_SCALE` register                                     |
+/// | 4-67    | `ON_L`, `ON_H`, `OFF_L` and `OFF_H` of channels 0 to 15  |
+///
+/// See: [`snapshot()`](Pca9685::snapshot) and [`restore()`](Pca9685::restore).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeviceSnapshot {
+    /// Mode and prescale register contents.
+    pub config: DeviceConfig,
+    /// Control values of each channel.
+    pub outputs: Frame,
+}
+
+impl DeviceSnapshot {
+    /// Size of the snapshot in bytes.
+    pub const SIZE: usize = 68;
+
+    /// Convert the snapshot into bytes.
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        let [version, mode1, mode2, prescale, channels @ ..] = &mut bytes;
+        *version = FORMAT_VERSION;
+        *mode1 = self.config.mode1.bits();
+        *mode2 = self.config.mode2.bits();
+        *prescale = self.config.prescale;
+        for (chunk, value) in channels.chunks_exact_mut(4).zip(self.outputs.channels()) {
+            chunk.copy_from_slice(&value.encode());
+        }
+        bytes
+    }
+
+    /// Create a snapshot from bytes.
+    ///
+    /// Returns `None` if the layout version is not supported, if reserved
+    /// mode register bits are set or if the prescale value is lower than 3.
+    pub fn from_bytes(bytes: &[u8; Self::SIZE]) -> Option<Self> {
+        let [version, mode1, mode2, prescale, channels @ ..] = bytes;
+        if *version != FORMAT_VERSION || *prescale < 3 {
+            return None;
+        }
+        let mut outputs = [ChannelOnOffControl::default(); 16];
+        for (value, chunk) in outputs.iter_mut().zip(channels.chunks_exact(4)) {
+            *value = ChannelOnOffControl::decode(chunk.try_into().ok()?);
+        }
+        Some(DeviceSnapshot {
+            config: DeviceConfig {
+                mode1: Mode1Flags::from_bits(*mode1)?,
+                mode2: Mode2Flags::from_bits(*mode2)?,
+                prescale: *prescale,
+            },
+            outputs: Frame::new(outputs),
+        })
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Get a snapshot of the configuration and channel control values last
+    /// written by this driver.
+    ///
+    /// This does not read the device.
+    pub fn snapshot(&self) -> DeviceSnapshot {
+        DeviceSnapshot {
+            config: self.device_config(),
+            outputs: self.outputs,
+        }
+    }
+
+    /// Restore a snapshot.
+    ///
+    /// The configuration is applied with [`apply_config()`](Pca9685::apply_config)
+    /// and then all channels are written at once.
+    pub async fn restore(&mut self, snapshot: &DeviceSnapshot) -> Result<(), Error<E>> {
+        self.apply_config(snapshot.config).await?;
+        self.set_all_channels(snapshot.outputs.channels()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_round_trip() {
+        let mut outputs = Frame::all_off();
+        outputs.set(crate::Channel::C3, ChannelOnOffControl::full_on());
+        let snapshot = DeviceSnapshot {
+            config: DeviceConfig::default(),
+            outputs,
+        };
+        let bytes = snapshot.to_bytes();
+        assert_eq!([1, 0x11, 0x04, 0x1E, 0, 0, 0, 0x10], bytes[..8]);
+        assert_eq!(Some(snapshot), DeviceSnapshot::from_bytes(&bytes));
+    }
+
+    #[test]
+    fn rejects_invalid_bytes() {
+        let valid = DeviceSnapshot {
+            config: DeviceConfig::default(),
+            outputs: Frame::all_off(),
+        }
+        .to_bytes();
+        let mut bytes = valid;
+        bytes[0] = 2;
+        assert_eq!(None, DeviceSnapshot::from_bytes(&bytes));
+        let mut bytes = valid;
+        bytes[2] = 0x20;
+        assert_eq!(None, DeviceSnapshot::from_bytes(&bytes));
+        let mut bytes = valid;
+        bytes[3] = 2;
+        assert_eq!(None, DeviceSnapshot::from_bytes(&bytes));
+    }
+}
@@ -0,0 +1,63 @@
+//! Per-channel staleness tracking
+
+use crate::{Channel, Pca9685};
+
+/// Time of the last write to each channel
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Staleness {
+    /// Time passed to the last call to `tick()`.
+    clock_ms: Option<u32>,
+    /// Time of the last write to each channel.
+    updated_ms: [Option<u32>; 16],
+}
+
+impl<I2C> Pca9685<I2C> {
+    /// Get the time in milliseconds elapsed between the last write to a
+    /// channel and the last call to [`tick()`](Pca9685::tick).
+    ///
+    /// Writes are timestamped with the time passed to the last call to
+    /// `tick()`. Channels which have not been written since the first call
+    /// count from that call.
+    ///
+    /// Returns `None` for `Channel::All` or if `tick()` has not been called yet.
+    pub fn channel_age_ms(&self, channel: Channel) -> Option<u32> {
+        let now_ms = self.staleness.clock_ms?;
+        let updated_ms = channel
+            .index()
+            .and_then(|i| self.staleness.updated_ms.get(i))
+            .copied()
+            .flatten()?;
+        Some(now_ms.wrapping_sub(updated_ms))
+    }
+
+    /// Iterate over the channels which have not been written for more than
+    /// `max_age_ms` milliseconds.
+    ///
+    /// This helps detecting control paths which stopped producing updates.
+    /// Unlike the failsafe, this does not have any effect on the hardware.
+    /// See [`channel_age_ms()`](Pca9685::channel_age_ms) for how the age is
+    /// measured. No channel is reported before the first call to
+    /// [`tick()`](Pca9685::tick).
+    pub fn stale_channels(&self, max_age_ms: u32) -> impl Iterator<Item = Channel> + '_ {
+        (0..16_u8)
+            .filter_map(|i| Channel::try_from(i).ok())
+            .filter(move |c| self.channel_age_ms(*c).is_some_and(|age| age > max_age_ms))
+    }
+
+    /// Record the current time for staleness tracking.
+    pub(crate) fn advance_staleness_clock(&mut self, now_ms: u32) {
+        self.staleness.clock_ms = Some(now_ms);
+        for updated_ms in self.staleness.updated_ms.iter_mut() {
+            updated_ms.get_or_insert(now_ms);
+        }
+    }
+
+    /// Record a write to `count` consecutive channels starting at the channel
+    /// with index `first`.
+    pub(crate) fn mark_updated(&mut self, first: usize, count: usize) {
+        let now_ms = self.staleness.clock_ms;
+        for updated_ms in self.staleness.updated_ms.iter_mut().skip(first).take(count) {
+            *updated_ms = now_ms;
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! PWM frequency sweeps
+
+use crate::{config::BitFlagMode1, Error, Pca9685};
+use core::ops::RangeInclusive;
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+#[cfg(feature = "async")]
+use embedded_hal_async::{delay::DelayNs as AsyncDelayNs, i2c::I2c as AsyncI2c};
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c")),
+        idents(AsyncDelayNs(sync = "DelayNs"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Step the prescale value through a range holding each value for
+    /// `dwell_ms` milliseconds.
+    ///
+    /// This is useful for finding resonances of motors or buzzers and for
+    /// flicker testing of LED drivers. The channels keep their control
+    /// values: after each prescale write, the oscillator is given 500us to
+    /// stabilize and the PWM channels are restarted. The original prescale
+    /// value is restored at the end of the sweep.
+    ///
+    /// Returns `Error::InvalidInputData` if the range is empty or contains
+    /// values lower than 3 and `Error::Asleep` if the controller is disabled.
+    pub async fn sweep_frequency(
+        &mut self,
+        prescales: RangeInclusive<u8>,
+        dwell_ms: u32,
+        delay: &mut impl AsyncDelayNs,
+    ) -> Result<(), Error<E>> {
+        if prescales.is_empty() || *prescales.start() < 3 {
+            return Err(Error::InvalidInputData);
+        }
+        if self.config.is_high(BitFlagMode1::Sleep) {
+            return Err(Error::Asleep);
+        }
+        let original = self.prescale;
+        for prescale in prescales {
+            self.set_prescale_and_restart(prescale, delay).await?;
+            delay.delay_ms(dwell_ms).await;
+        }
+        self.set_prescale_and_restart(original, delay).await
+    }
+
+    async fn set_prescale_and_restart(
+        &mut self,
+        prescale: u8,
+        delay: &mut impl AsyncDelayNs,
+    ) -> Result<(), Error<E>> {
+        self.set_prescale(prescale).await?;
+        delay.delay_us(500).await;
+        let previous = self.config;
+        self.write_mode1(previous.with_high(BitFlagMode1::Restart))
+            .await?;
+        // Writing the restart bit high again would clear it.
+        self.config = previous;
+        Ok(())
+    }
+}
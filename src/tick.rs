@@ -0,0 +1,49 @@
+use crate::{Error, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Service the time-based features of the driver.
+    ///
+    /// This must be called periodically with the current time in milliseconds
+    /// from a monotonic clock when using any of the following features:
+    /// - Failsafe timeout. See: [`enable_failsafe()`](Pca9685::enable_failsafe).
+    /// - Chunked frame commits. One chunk is written per call.
+    ///   See: [`start_chunked_commit()`](Pca9685::start_chunked_commit).
+    /// - Slow blinking. See: [`start_slow_blink()`](Pca9685::start_slow_blink).
+    /// - Blink codes. See: [`start_blink_code()`](Pca9685::start_blink_code).
+    /// - Waveform playback. See: [`start_waveform()`](Pca9685::start_waveform).
+    /// - Profile crossfades. See: [`crossfade_to_profile()`](Pca9685::crossfade_to_profile).
+    /// - Servo idle timeouts. See: [`set_servo_idle_timeout()`](Pca9685::set_servo_idle_timeout).
+    /// - Staleness tracking. See: [`stale_channels()`](Pca9685::stale_channels).
+    ///
+    /// The clock is allowed to wrap around. Nothing is written to the device
+    /// if no time-based feature needs it.
+    pub async fn tick(&mut self, now_ms: u32) -> Result<(), Error<E>> {
+        self.advance_staleness_clock(now_ms);
+        self.service_failsafe(now_ms).await?;
+        if self.chunked_commit.is_some() {
+            self.commit_next_chunk().await?;
+        }
+        self.service_slow_blink(now_ms).await?;
+        self.service_blink_code(now_ms).await?;
+        self.service_waveforms(now_ms).await?;
+        self.service_profile_fade(now_ms).await?;
+        self.service_servo_idle(now_ms).await?;
+        Ok(())
+    }
+}
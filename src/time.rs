@@ -0,0 +1,64 @@
+//! Timing setters taking `fugit` rates and durations
+
+use crate::{Channel, Error, Pca9685};
+use fugit::{Duration, Rate};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Set the prescale value for a PWM frequency.
+    ///
+    /// The frequency is truncated to whole Hz. See:
+    /// [`set_frequency_hz()`](Pca9685::set_frequency_hz).
+    pub async fn set_frequency<const NOM: u32, const DENOM: u32>(
+        &mut self,
+        frequency: Rate<u32, NOM, DENOM>,
+    ) -> Result<(), Error<E>> {
+        let frequency_hz = u16::try_from(frequency.to_Hz()).map_err(|_| Error::InvalidInputData)?;
+        self.set_frequency_hz(frequency_hz).await
+    }
+
+    /// Set the prescale value for a PWM period.
+    ///
+    /// The period is truncated to whole microseconds. See:
+    /// [`set_period_us()`](Pca9685::set_period_us).
+    pub async fn set_period<const NOM: u32, const DENOM: u32>(
+        &mut self,
+        period: Duration<u32, NOM, DENOM>,
+    ) -> Result<(), Error<E>> {
+        self.set_period_us(period.to_micros()).await
+    }
+
+    /// Set the pulse width of a servo channel.
+    ///
+    /// The pulse width is converted into counter steps without intermediate
+    /// rounding to microseconds. Otherwise this behaves like
+    /// [`set_servo_pulse_us()`](Pca9685::set_servo_pulse_us).
+    pub async fn set_servo_pulse<const NOM: u32, const DENOM: u32>(
+        &mut self,
+        channel: Channel,
+        pulse: Duration<u32, NOM, DENOM>,
+    ) -> Result<(), Error<E>> {
+        // pulse width in microseconds multiplied by DENOM
+        let us_num = u64::from(pulse.ticks())
+            .checked_mul(u64::from(NOM))
+            .and_then(|ticks| ticks.checked_mul(1_000_000))
+            .ok_or(Error::InvalidInputData)?;
+        self.write_servo_pulse(channel, us_num, u64::from(DENOM))
+            .await
+    }
+}
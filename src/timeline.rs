@@ -0,0 +1,104 @@
+//! Animation timelines
+
+use crate::{Easing, Frame, FrameRamp};
+use alloc::vec::Vec;
+
+/// Transition to a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Keyframe {
+    /// Frame reached at the end of the transition.
+    pub frame: Frame,
+    /// Duration of the transition in milliseconds.
+    pub duration_ms: u32,
+    /// Easing applied to the transition.
+    pub easing: Easing,
+}
+
+/// Sequence of transitions between frames of any length
+///
+/// This does not communicate with the device. The frames can be written at
+/// any cadence, for example with
+/// [`set_all_channels()`](crate::Pca9685::set_all_channels).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Timeline {
+    start: Frame,
+    keyframes: Vec<Keyframe>,
+}
+
+impl Timeline {
+    /// Create an empty timeline starting at a frame.
+    pub fn new(start: Frame) -> Self {
+        Timeline {
+            start,
+            keyframes: Vec::new(),
+        }
+    }
+
+    /// Append a transition to a frame.
+    pub fn push(&mut self, keyframe: Keyframe) {
+        self.keyframes.push(keyframe);
+    }
+
+    /// Get the transitions.
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    /// Total duration in milliseconds.
+    ///
+    /// This saturates at `u32::MAX`.
+    pub fn duration_ms(&self) -> u32 {
+        self.keyframes
+            .iter()
+            .fold(0, |total: u32, k| total.saturating_add(k.duration_ms))
+    }
+
+    /// Get the frame `elapsed_ms` milliseconds after the start.
+    ///
+    /// The last frame is returned once the timeline has ended.
+    pub fn frame_at(&self, elapsed_ms: u32) -> Frame {
+        let mut from = self.start;
+        let mut remaining = elapsed_ms;
+        for keyframe in &self.keyframes {
+            if remaining < keyframe.duration_ms {
+                return FrameRamp::new(from, keyframe.frame, 1)
+                    .with_easing(keyframe.easing)
+                    .at(remaining, keyframe.duration_ms);
+            }
+            remaining = remaining.saturating_sub(keyframe.duration_ms);
+            from = keyframe.frame;
+        }
+        from
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChannelOnOffControl;
+
+    fn frame(off: u16) -> Frame {
+        Frame::new([ChannelOnOffControl::duty(off).unwrap(); 16])
+    }
+
+    #[test]
+    fn plays_keyframes_in_order() {
+        let mut timeline = Timeline::new(frame(1000));
+        timeline.push(Keyframe {
+            frame: frame(2000),
+            duration_ms: 100,
+            easing: Easing::Linear,
+        });
+        timeline.push(Keyframe {
+            frame: frame(1000),
+            duration_ms: 200,
+            easing: Easing::Linear,
+        });
+        assert_eq!(300, timeline.duration_ms());
+        assert_eq!(frame(1000), timeline.frame_at(0));
+        assert_eq!(frame(1500), timeline.frame_at(50));
+        assert_eq!(frame(2000), timeline.frame_at(100));
+        assert_eq!(frame(1500), timeline.frame_at(200));
+        assert_eq!(frame(1000), timeline.frame_at(1000));
+    }
+}
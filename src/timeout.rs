@@ -0,0 +1,51 @@
+//! Timeouts for asynchronous operations
+
+use crate::Error;
+use core::{
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+};
+use embedded_hal_async::delay::DelayNs;
+
+/// Run an operation of the driver with a timeout.
+///
+/// Returns `Error::Timeout` if `operation` does not complete within
+/// `timeout_us` microseconds, for example because the bus hangs or a device
+/// stretches the clock indefinitely.
+///
+/// The operation is dropped when the timeout expires, which may leave an I2C
+/// transaction unfinished. The device state cached by the driver may then not
+/// match the device anymore.
+///
+/// ```no_run
+/// # async fn example<I2C: embedded_hal_async::i2c::I2c, D: embedded_hal_async::delay::DelayNs>(
+/// #     mut pwm: pwm_pca9685::Pca9685<I2C>,
+/// #     mut delay: D,
+/// # ) {
+/// use pwm_pca9685::{with_timeout, Channel};
+///
+/// let result = with_timeout(&mut delay, 10_000, pwm.set_channel_on(Channel::C0, 0)).await;
+/// # }
+/// ```
+pub async fn with_timeout<T, E, F>(
+    delay: &mut impl DelayNs,
+    timeout_us: u32,
+    operation: F,
+) -> Result<T, Error<E>>
+where
+    F: Future<Output = Result<T, Error<E>>>,
+{
+    let mut operation = pin!(operation);
+    let mut timeout = pin!(delay.delay_us(timeout_us));
+    poll_fn(|cx| {
+        if let Poll::Ready(result) = operation.as_mut().poll(cx) {
+            return Poll::Ready(result);
+        }
+        if timeout.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(Err(Error::Timeout));
+        }
+        Poll::Pending
+    })
+    .await
+}
@@ -1,4 +1,9 @@
-use crate::config::Config;
+use crate::{
+    blink_code::BlinkCodeState, brightness::BrightnessCurve, chunked::ChunkedCommit,
+    config::Config, failsafe::FailsafeState, limits::ChannelLimits, observer::OutputObserver,
+    profile::Profiles, quirks::QuirkState, regs, servo::ServoIdle, slow_blink::BlinkState,
+    staleness::Staleness, waveform::WaveformState, ConfigDiff, Frame,
+};
 use core::convert::TryFrom;
 use core::fmt::{Display, Formatter};
 
@@ -16,15 +21,91 @@ pub struct Pca9685<I2C> {
     pub(crate) address: u8,
     /// Current device configuration.
     pub(crate) config: Config,
+    /// Last prescale value written.
+    pub(crate) prescale: u8,
+    /// Measured deviation of the internal oscillator in ppm.
+    pub(crate) oscillator_correction_ppm: i32,
+    /// Rounding applied in conversions.
+    pub(crate) rounding: Rounding,
+    /// Curve applied to brightness levels.
+    pub(crate) brightness_curve: BrightnessCurve,
+    /// Intended use of each channel.
+    pub(crate) channel_modes: [ChannelMode; 16],
+    /// Priority class of each channel.
+    pub(crate) priorities: [Priority; 16],
+    /// Last PWM control values written to each channel.
+    pub(crate) outputs: Frame,
+    /// Order in which the PWM control registers are updated.
+    pub(crate) update_ordering: UpdateOrdering,
+    /// Maximum number of channels written in a single transaction.
+    pub(crate) max_write_channels: usize,
+    /// Whether the mode registers are read before each modification.
+    pub(crate) multi_master: bool,
+    /// Current drawn by the load of each channel while active in mA.
+    pub(crate) channel_current_ma: [u16; 16],
+    /// Deadband and clamps of each channel.
+    pub(crate) limits: [ChannelLimits; 16],
+    /// Whether duty cycle setters use the full-on/full-off flags at the extremes.
+    pub(crate) promote_full_on_off: bool,
+    /// Whether registers are written one at a time without auto-increment.
+    pub(crate) single_register_writes: bool,
+    /// Workarounds applied for clone chips.
+    pub(crate) quirks: QuirkState,
+    /// Whether out-of-range counter values are clamped instead of rejected.
+    pub(crate) clamp_out_of_range: bool,
+    /// Whether a value has been clamped since the flag was last taken.
+    pub(crate) clamped: bool,
+    /// Debug label of each channel.
+    pub(crate) labels: [Option<&'static str>; 16],
+    /// Failsafe timeout state.
+    pub(crate) failsafe: Option<FailsafeState>,
+    /// Frame being written in chunks.
+    pub(crate) chunked_commit: Option<ChunkedCommit>,
+    /// Time at which the oscillator was started for a polled restart.
+    pub(crate) restart_started_us: Option<u32>,
+    /// Control values recorded when the controller was suspended.
+    pub(crate) suspended: Option<Frame>,
+    /// Slow blink state of each channel.
+    pub(crate) slow_blink: [Option<BlinkState>; 16],
+    /// Blink code being played on a status channel.
+    pub(crate) blink_code: Option<BlinkCodeState>,
+    /// Function notified of output changes.
+    pub(crate) observer: Option<OutputObserver>,
+    /// Idle timeout state of each servo channel.
+    pub(crate) servo_idle: [Option<ServoIdle>; 16],
+    /// Waveform played on each channel.
+    pub(crate) waveforms: [Option<WaveformState>; 16],
+    /// Stored output profiles and crossfade state.
+    pub(crate) profiles: Profiles,
+    /// Time of the last write to each channel.
+    pub(crate) staleness: Staleness,
 }
 
 /// All possible errors in this crate
 #[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Error<E> {
     /// I²C bus error
     I2C(E),
     /// Invalid input data provided
     InvalidInputData,
+    /// The operation does not match the mode configured for the channel
+    ChannelModeMismatch(Channel),
+    /// The operation requires the oscillator but the device is asleep
+    Asleep,
+    /// The contents read back from the device do not match the expected values
+    VerificationFailed,
+    /// An input value was outside the valid range and has been clamped
+    Clamped,
+    /// The outputs are locked after an emergency stop
+    EmergencyStopped,
+    /// The operation did not complete in time
+    Timeout,
+    /// The mode registers of the device differ from the configuration cached
+    /// by the driver
+    ConfigMismatch(ConfigDiff),
+    /// An internal invariant of the driver does not hold. This is a bug.
+    Internal,
 }
 
 // Implement Display for Error<E> if E also implements Display
@@ -33,6 +114,21 @@ impl<E: Display> Display for Error<E> {
         match self {
             Error::I2C(e) => write!(f, "I²C bus error: {}", e),
             Error::InvalidInputData => write!(f, "Invalid input data provided"),
+            Error::ChannelModeMismatch(c) => {
+                write!(f, "Operation does not match the mode of {}", c)
+            }
+            Error::Asleep => write!(f, "Device is asleep"),
+            Error::VerificationFailed => write!(f, "Verification failed"),
+            Error::Clamped => write!(f, "Input value clamped"),
+            Error::EmergencyStopped => write!(f, "Outputs locked after an emergency stop"),
+            Error::Timeout => write!(f, "Operation timed out"),
+            Error::ConfigMismatch(diff) => write!(
+                f,
+                "Configuration mismatch: MODE1 bits {:#04x}, MODE2 bits {:#04x}",
+                diff.mode1.bits(),
+                diff.mode2.bits()
+            ),
+            Error::Internal => write!(f, "Internal driver error"),
         }
     }
 }
@@ -108,6 +204,45 @@ impl_try_from_for_channel!(u8);
 impl_try_from_for_channel!(u16);
 impl_try_from_for_channel!(usize);
 
+impl Display for Channel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.index() {
+            Some(i) => write!(f, "channel {}", i),
+            None => write!(f, "all channels"),
+        }
+    }
+}
+
+/// Individual channels in index order.
+pub(crate) const CHANNELS: [Channel; 16] = [
+    Channel::C0,
+    Channel::C1,
+    Channel::C2,
+    Channel::C3,
+    Channel::C4,
+    Channel::C5,
+    Channel::C6,
+    Channel::C7,
+    Channel::C8,
+    Channel::C9,
+    Channel::C10,
+    Channel::C11,
+    Channel::C12,
+    Channel::C13,
+    Channel::C14,
+    Channel::C15,
+];
+
+impl Channel {
+    /// Index of the channel in the range [0-15] or `None` for `Channel::All`.
+    pub(crate) fn index(self) -> Option<usize> {
+        match self {
+            Channel::All => None,
+            _ => Some(self as usize),
+        }
+    }
+}
+
 /// Output logic state inversion
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum OutputLogicState {
@@ -122,6 +257,15 @@ pub enum OutputLogicState {
     Inverted,
 }
 
+impl Display for OutputLogicState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            OutputLogicState::Direct => write!(f, "direct"),
+            OutputLogicState::Inverted => write!(f, "inverted"),
+        }
+    }
+}
+
 /// Output state change behavior
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum OutputStateChange {
@@ -136,6 +280,15 @@ pub enum OutputStateChange {
     OnAck,
 }
 
+impl Display for OutputStateChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            OutputStateChange::OnStop => write!(f, "on STOP"),
+            OutputStateChange::OnAck => write!(f, "on ACK"),
+        }
+    }
+}
+
 /// Output driver configuration
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum OutputDriver {
@@ -146,6 +299,15 @@ pub enum OutputDriver {
     OpenDrain,
 }
 
+impl Display for OutputDriver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            OutputDriver::TotemPole => write!(f, "totem pole"),
+            OutputDriver::OpenDrain => write!(f, "open-drain"),
+        }
+    }
+}
+
 /// Value set to all outputs when the output drivers are disabled (`OE` = 1).
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DisabledOutputValue {
@@ -161,6 +323,127 @@ pub enum DisabledOutputValue {
     HighImpedance,
 }
 
+impl Display for DisabledOutputValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DisabledOutputValue::Zero => write!(f, "zero"),
+            DisabledOutputValue::OutputDriver => write!(f, "dependent on the output driver"),
+            DisabledOutputValue::HighImpedance => write!(f, "high-impedance"),
+        }
+    }
+}
+
+/// Circuit connected to the outputs
+///
+/// Selects the output logic state, output driver and disabled output value
+/// recommended in section 7.7 "Using the PCA9685 with and without external
+/// drivers" of the datasheet. See: [`set_output_topology()`](Pca9685::set_output_topology).
+///
+/// | Topology            | `INVRT` | `OUTDRV`   | `OUTNE` (`OE` = 1) |
+/// |---------------------|---------|------------|--------------------|
+/// | `ExternalNMosDriver`| 0       | totem pole | 0 (load off)       |
+/// | `ExternalPMosDriver`| 1       | totem pole | 1 (load off)       |
+/// | `LedToVccOpenDrain` | 1       | open-drain | high-impedance     |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Topology {
+    /// Load switched by an external N-type transistor driven by the output.
+    ExternalNMosDriver,
+    /// Load switched by an external P-type transistor driven by the output.
+    ExternalPMosDriver,
+    /// LED connected between the supply and the output, without an external
+    /// driver. The output sinks the LED current.
+    LedToVccOpenDrain,
+}
+
+impl Topology {
+    /// Output logic state, output driver and disabled output value.
+    pub(crate) fn settings(self) -> (OutputLogicState, OutputDriver, DisabledOutputValue) {
+        match self {
+            Topology::ExternalNMosDriver => (
+                OutputLogicState::Direct,
+                OutputDriver::TotemPole,
+                DisabledOutputValue::Zero,
+            ),
+            Topology::ExternalPMosDriver => (
+                OutputLogicState::Inverted,
+                OutputDriver::TotemPole,
+                DisabledOutputValue::OutputDriver,
+            ),
+            Topology::LedToVccOpenDrain => (
+                OutputLogicState::Inverted,
+                OutputDriver::OpenDrain,
+                DisabledOutputValue::HighImpedance,
+            ),
+        }
+    }
+}
+
+/// Intended use of a channel
+///
+/// Operations which do not match the configured mode of a channel are
+/// rejected with `Error::ChannelModeMismatch`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelMode {
+    /// Any operation is allowed (default).
+    #[default]
+    Any,
+    /// PWM output. Servo operations are rejected.
+    Pwm,
+    /// Digital output. Only full-on and full-off are allowed.
+    Digital,
+    /// Servo output. Only servo operations and full-off are allowed.
+    Servo,
+}
+
+/// Priority class of a channel
+///
+/// When a commit is split across several transactions, channels with a
+/// higher priority are written first. Channels with the same priority are
+/// written in ascending order.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Written first, for example for safety-critical enable lines.
+    High,
+    /// Written after channels with high priority (default).
+    #[default]
+    Normal,
+    /// Written last.
+    Low,
+}
+
+/// Order in which the PWM control registers are updated
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UpdateOrdering {
+    /// Write the new values directly (default).
+    #[default]
+    Direct,
+    /// Avoid glitches when changing the PWM control values.
+    ///
+    /// If a channel keeps generating pulses but its `ON` counter changes or
+    /// its `OFF` counter moves earlier, the output can be held high for up to
+    /// a full cycle if the new switching point has already been passed in the
+    /// current cycle. This is visible as a flash on LEDs.
+    ///
+    /// In this mode such channels are set to full-off in a separate
+    /// transaction before writing the new values. At most the current pulse
+    /// is shortened. Writing these channels takes an additional transaction.
+    GlitchFree,
+}
+
+/// Rounding applied when converting values into register values
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// Round to the nearest value (default).
+    #[default]
+    Nearest,
+    /// Round down.
+    Down,
+    /// Round up.
+    Up,
+    /// Do not round. Inexact conversions return `Error::InvalidInputData`.
+    Exact,
+}
+
 /// Additional programmable address types (volatile programming)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ProgrammableAddress {
@@ -174,6 +457,17 @@ pub enum ProgrammableAddress {
     AllCall,
 }
 
+impl Display for ProgrammableAddress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ProgrammableAddress::Subaddress1 => write!(f, "subaddress 1"),
+            ProgrammableAddress::Subaddress2 => write!(f, "subaddress 2"),
+            ProgrammableAddress::Subaddress3 => write!(f, "subaddress 3"),
+            ProgrammableAddress::AllCall => write!(f, "LED all call address"),
+        }
+    }
+}
+
 /// I2C device address
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Address(pub(crate) u8);
@@ -192,18 +486,79 @@ impl From<u8> for Address {
     }
 }
 
-/// Compute device address from address bits
+/// Compute device address from address bits in the order `(a5, a4, a3, a2, a1, a0)`
 impl From<(bool, bool, bool, bool, bool, bool)> for Address {
     fn from(a: (bool, bool, bool, bool, bool, bool)) -> Self {
-        Address(
-            DEVICE_BASE_ADDRESS
-                | ((a.0 as u8) << 5)
-                | ((a.1 as u8) << 4)
-                | ((a.2 as u8) << 3)
-                | ((a.3 as u8) << 2)
-                | ((a.4 as u8) << 1)
-                | a.5 as u8,
-        )
+        Address::from(AddressPins::from(a))
+    }
+}
+
+/// Compute device address from the state of the address pins
+impl From<AddressPins> for Address {
+    fn from(pins: AddressPins) -> Self {
+        Address(DEVICE_BASE_ADDRESS | pins.bits())
+    }
+}
+
+/// State of the hardware address pins `A5`-`A0`
+///
+/// A pin is `true` if it is tied high.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AddressPins {
+    /// Address pin `A5`.
+    pub a5: bool,
+    /// Address pin `A4`.
+    pub a4: bool,
+    /// Address pin `A3`.
+    pub a3: bool,
+    /// Address pin `A2`.
+    pub a2: bool,
+    /// Address pin `A1`.
+    pub a1: bool,
+    /// Address pin `A0`.
+    pub a0: bool,
+}
+
+impl AddressPins {
+    /// Create the pin state from a mask of closed solder jumpers where bit 0
+    /// corresponds to `A0` and bit 5 to `A5`.
+    ///
+    /// A closed jumper ties the pin high, as on most breakout boards. Bits 6
+    /// and 7 are ignored.
+    pub const fn from_solder_jumpers(mask: u8) -> Self {
+        AddressPins {
+            a5: mask & 0b10_0000 != 0,
+            a4: mask & 0b01_0000 != 0,
+            a3: mask & 0b00_1000 != 0,
+            a2: mask & 0b00_0100 != 0,
+            a1: mask & 0b00_0010 != 0,
+            a0: mask & 0b00_0001 != 0,
+        }
+    }
+
+    /// Get the pin state as a mask where bit 0 corresponds to `A0` and bit 5
+    /// to `A5`.
+    pub const fn bits(self) -> u8 {
+        ((self.a5 as u8) << 5)
+            | ((self.a4 as u8) << 4)
+            | ((self.a3 as u8) << 3)
+            | ((self.a2 as u8) << 2)
+            | ((self.a1 as u8) << 1)
+            | self.a0 as u8
+    }
+}
+
+/// Pin state in the order `(a5, a4, a3, a2, a1, a0)`
+impl From<(bool, bool, bool, bool, bool, bool)> for AddressPins {
+    fn from((a5, a4, a3, a2, a1, a0): (bool, bool, bool, bool, bool, bool)) -> Self {
+        AddressPins {
+            a5,
+            a4,
+            a3,
+            a2,
+            a1,
+            a0,
+        }
     }
 }
 
@@ -220,6 +575,147 @@ pub struct ChannelOnOffControl {
     pub full_off: bool,
 }
 
+impl ChannelOnOffControl {
+    /// Create control values switching the channel on at the start of each
+    /// PWM cycle and off at the counter value `off`.
+    ///
+    /// Returns `None` if `off` is greater than 4095.
+    pub const fn duty(off: u16) -> Option<Self> {
+        if off > 4095 {
+            return None;
+        }
+        Some(ChannelOnOffControl {
+            on: 0,
+            off,
+            full_on: false,
+            full_off: false,
+        })
+    }
+
+    /// Create control values switching the channel on at the counter value
+    /// `phase` and off `duty` counter steps later.
+    ///
+    /// The `off` counter wraps around the end of the PWM cycle as described
+    /// in section 7.3.3 "LED output and PWM control" of the datasheet. A duty
+    /// cycle of 0 sets the channel to full-off.
+    ///
+    /// Returns `None` if `duty` or `phase` is greater than 4095.
+    pub const fn duty_at_phase(duty: u16, phase: u16) -> Option<Self> {
+        if duty > 4095 || phase > 4095 {
+            return None;
+        }
+        if duty == 0 {
+            return Some(ChannelOnOffControl::full_off());
+        }
+        Some(ChannelOnOffControl {
+            on: phase,
+            off: phase.wrapping_add(duty) & 0x0FFF,
+            full_on: false,
+            full_off: false,
+        })
+    }
+
+    /// Create control values setting the channel to full-on.
+    pub const fn full_on() -> Self {
+        ChannelOnOffControl {
+            on: 0,
+            off: 0,
+            full_on: true,
+            full_off: false,
+        }
+    }
+
+    /// Create control values setting the channel to full-off.
+    pub const fn full_off() -> Self {
+        ChannelOnOffControl {
+            on: 0,
+            off: 0,
+            full_on: false,
+            full_off: true,
+        }
+    }
+
+    /// Set the counter value to switch the channel on.
+    ///
+    /// Returns `None` if `on` is greater than 4095.
+    pub const fn with_on(self, on: u16) -> Option<Self> {
+        if on > 4095 {
+            return None;
+        }
+        Some(ChannelOnOffControl { on, ..self })
+    }
+
+    /// Set the counter value to switch the channel off.
+    ///
+    /// Returns `None` if `off` is greater than 4095.
+    pub const fn with_off(self, off: u16) -> Option<Self> {
+        if off > 4095 {
+            return None;
+        }
+        Some(ChannelOnOffControl { off, ..self })
+    }
+
+    /// Number of counter steps per PWM cycle during which the output is
+    /// active, in the range `[0-4096]`.
+    ///
+    /// The full-off flag takes precedence over the full-on flag. The
+    /// output logic state is not taken into account.
+    pub const fn active_counts(&self) -> u16 {
+        if self.full_off {
+            0
+        } else if self.full_on {
+            4096
+        } else {
+            self.off.wrapping_sub(self.on) & 0x0FFF
+        }
+    }
+
+    /// Contents of the `ON_L`, `ON_H`, `OFF_L` and `OFF_H` registers for
+    /// these control values.
+    ///
+    /// The full-on and full-off flags are encoded in bit 4 of `ON_H` and
+    /// `OFF_H`. Counter values are truncated to 12 bits.
+    pub const fn encode(&self) -> [u8; 4] {
+        let [on_l, on_h] = (self.on & 0x0FFF).to_le_bytes();
+        let [off_l, off_h] = (self.off & 0x0FFF).to_le_bytes();
+        let full_on = if self.full_on { regs::FULL_ON_OFF } else { 0 };
+        let full_off = if self.full_off { regs::FULL_ON_OFF } else { 0 };
+        [on_l, on_h | full_on, off_l, off_h | full_off]
+    }
+
+    /// Create control values from the contents of the `ON_L`, `ON_H`,
+    /// `OFF_L` and `OFF_H` registers.
+    ///
+    /// Reserved bits are ignored.
+    pub const fn decode(registers: [u8; 4]) -> Self {
+        let [on_l, on_h, off_l, off_h] = registers;
+        ChannelOnOffControl {
+            on: u16::from_le_bytes([on_l, on_h & 0x0F]),
+            off: u16::from_le_bytes([off_l, off_h & 0x0F]),
+            full_on: on_h & regs::FULL_ON_OFF != 0,
+            full_off: off_h & regs::FULL_ON_OFF != 0,
+        }
+    }
+}
+
+impl TryFrom<(u16, u16)> for ChannelOnOffControl {
+    type Error = ();
+
+    /// Create control values from the `(on, off)` counter values.
+    ///
+    /// Will return an empty error if a value is greater than 4095.
+    fn try_from((on, off): (u16, u16)) -> Result<Self, Self::Error> {
+        if on > 4095 || off > 4095 {
+            return Err(());
+        }
+        Ok(ChannelOnOffControl {
+            on,
+            off,
+            ..Default::default()
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,6 +734,83 @@ mod tests {
     default_test!(default_out_driver, OutputDriver, TotemPole);
     default_test!(default_disabled_out_value, DisabledOutputValue, Zero);
 
+    #[test]
+    fn can_get_active_counts() {
+        assert_eq!(0, ChannelOnOffControl::full_off().active_counts());
+        assert_eq!(4096, ChannelOnOffControl::full_on().active_counts());
+        let value = ChannelOnOffControl::duty(1000).unwrap();
+        assert_eq!(1000, value.active_counts());
+        assert_eq!(3096, value.with_on(2000).unwrap().active_counts());
+    }
+
+    #[test]
+    fn can_build_channel_on_off_control() {
+        let value = ChannelOnOffControl::duty(2047)
+            .unwrap()
+            .with_on(100)
+            .unwrap();
+        assert_eq!(
+            ChannelOnOffControl {
+                on: 100,
+                off: 2047,
+                ..Default::default()
+            },
+            value
+        );
+        assert_eq!(None, ChannelOnOffControl::duty(4096));
+        assert_eq!(None, value.with_on(4096));
+        assert_eq!(None, value.with_off(4096));
+        assert!(ChannelOnOffControl::full_on().full_on);
+        assert!(ChannelOnOffControl::full_off().full_off);
+    }
+
+    #[test]
+    fn can_convert_channel_on_off_control_from_tuple() {
+        assert_eq!(
+            Ok(ChannelOnOffControl {
+                on: 4095,
+                off: 1,
+                ..Default::default()
+            }),
+            ChannelOnOffControl::try_from((4095, 1))
+        );
+        assert_eq!(Err(()), ChannelOnOffControl::try_from((4096, 1)));
+        assert_eq!(Err(()), ChannelOnOffControl::try_from((0, 4096)));
+    }
+
+    #[test]
+    fn can_encode_channel_on_off_control() {
+        let value = ChannelOnOffControl {
+            on: 0x123,
+            off: 0xABC,
+            ..Default::default()
+        };
+        assert_eq!([0x23, 0x01, 0xBC, 0x0A], value.encode());
+        assert_eq!(
+            [0, 0b0001_0000, 0, 0],
+            ChannelOnOffControl::full_on().encode()
+        );
+        assert_eq!(
+            [0, 0, 0, 0b0001_0000],
+            ChannelOnOffControl::full_off().encode()
+        );
+    }
+
+    #[test]
+    fn can_decode_channel_on_off_control() {
+        let value = ChannelOnOffControl {
+            on: 0x123,
+            off: 0xABC,
+            full_on: true,
+            full_off: true,
+        };
+        assert_eq!(value, ChannelOnOffControl::decode(value.encode()));
+        assert_eq!(
+            ChannelOnOffControl::full_off(),
+            ChannelOnOffControl::decode([0, 0b1110_0000, 0, 0b1111_0000])
+        );
+    }
+
     #[test]
     fn can_get_default_address() {
         let addr = Address::default();
@@ -274,4 +847,64 @@ mod std_tests {
 
         assert_eq!(expected, actual)
     }
+
+    #[test]
+    fn test_display_implementation_channel_mode_mismatch() {
+        let expected = "Operation does not match the mode of channel 3";
+        let error = Error::<TestError>::ChannelModeMismatch(Channel::C3);
+        let actual = format!("{}", error);
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_display_implementation_timeout() {
+        let expected = "Operation timed out";
+        let error = Error::<TestError>::Timeout;
+        let actual = format!("{}", error);
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_display_implementation_channel() {
+        assert_eq!("channel 0", format!("{}", Channel::C0));
+        assert_eq!("channel 15", format!("{}", Channel::C15));
+        assert_eq!("all channels", format!("{}", Channel::All));
+    }
+
+    #[test]
+    fn test_display_implementation_output_configuration() {
+        assert_eq!("inverted", format!("{}", OutputLogicState::Inverted));
+        assert_eq!("on ACK", format!("{}", OutputStateChange::OnAck));
+        assert_eq!("open-drain", format!("{}", OutputDriver::OpenDrain));
+        assert_eq!(
+            "high-impedance",
+            format!("{}", DisabledOutputValue::HighImpedance)
+        );
+    }
+
+    #[test]
+    fn test_display_implementation_programmable_address() {
+        assert_eq!(
+            "subaddress 2",
+            format!("{}", ProgrammableAddress::Subaddress2)
+        );
+        assert_eq!(
+            "LED all call address",
+            format!("{}", ProgrammableAddress::AllCall)
+        );
+    }
+
+    #[test]
+    fn duty_at_phase_wraps_around() {
+        let value = ChannelOnOffControl::duty_at_phase(1000, 3500).unwrap();
+        assert_eq!((3500, 404), (value.on, value.off));
+        assert_eq!(1000, value.active_counts());
+        assert_eq!(
+            Some(ChannelOnOffControl::full_off()),
+            ChannelOnOffControl::duty_at_phase(0, 100)
+        );
+        assert_eq!(None, ChannelOnOffControl::duty_at_phase(100, 4096));
+    }
 }
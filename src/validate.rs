@@ -0,0 +1,103 @@
+//! Frame validation without bus access
+
+use crate::{channels::OutputKind, Channel, Frame, Pca9685};
+
+/// Constraints violated by the control values of a channel
+///
+/// See: [`validate_frame()`](Pca9685::validate_frame).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChannelViolations {
+    /// The `on` counter value is greater than 4095.
+    pub on_out_of_range: bool,
+    /// The `off` counter value is greater than 4095.
+    pub off_out_of_range: bool,
+    /// Both the full-on and full-off flags are set.
+    pub full_on_and_full_off: bool,
+    /// The values do not match the configured channel mode.
+    pub mode_mismatch: bool,
+    /// The active counts are below the configured minimum counter value.
+    pub below_min_count: bool,
+    /// The active counts are above the configured maximum counter value.
+    pub above_max_count: bool,
+}
+
+impl ChannelViolations {
+    /// Whether no constraint is violated.
+    pub const fn is_empty(&self) -> bool {
+        !(self.on_out_of_range
+            || self.off_out_of_range
+            || self.full_on_and_full_off
+            || self.mode_mismatch
+            || self.below_min_count
+            || self.above_max_count)
+    }
+}
+
+/// Constraints violated by the channels of a frame
+///
+/// See: [`validate_frame()`](Pca9685::validate_frame).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameViolations {
+    channels: [ChannelViolations; 16],
+}
+
+impl FrameViolations {
+    /// Get the violations of a channel.
+    ///
+    /// Returns `None` for `Channel::All`.
+    pub fn get(&self, channel: Channel) -> Option<ChannelViolations> {
+        channel.index().and_then(|i| self.channels.get(i)).copied()
+    }
+
+    /// Iterate over the channels violating any constraint together with
+    /// their violations.
+    pub fn iter(&self) -> impl Iterator<Item = (Channel, ChannelViolations)> + '_ {
+        self.channels
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| !v.is_empty())
+            .filter_map(|(i, v)| Channel::try_from(i).ok().map(|c| (c, *v)))
+    }
+}
+
+impl<I2C> Pca9685<I2C> {
+    /// Check a frame against all constraints without writing anything.
+    ///
+    /// The counter values must be in the range `[0-4095]` and the full-on and
+    /// full-off flags must not be set at the same time. The values must also
+    /// match the channel modes set with
+    /// [`set_channel_mode()`](Pca9685::set_channel_mode) and, for channels
+    /// using the counters, the active counts must be within the limits set
+    /// with [`set_channel_limits()`](Pca9685::set_channel_limits).
+    ///
+    /// This is useful to check values coming from configuration files or the
+    /// network before writing them. Every violation is reported.
+    pub fn validate_frame(&self, frame: &Frame) -> Result<(), FrameViolations> {
+        let mut violations = FrameViolations::default();
+        for (((v, value), mode), limits) in violations
+            .channels
+            .iter_mut()
+            .zip(frame.channels())
+            .zip(self.channel_modes)
+            .zip(self.limits)
+        {
+            let kind = OutputKind::of(value);
+            let counts_checked =
+                kind == OutputKind::Counters && value.on <= 4095 && value.off <= 4095;
+            let counts = value.active_counts();
+            *v = ChannelViolations {
+                on_out_of_range: value.on > 4095,
+                off_out_of_range: value.off > 4095,
+                full_on_and_full_off: value.full_on && value.full_off,
+                mode_mismatch: !mode.allows(kind),
+                below_min_count: counts_checked && counts < limits.min_count,
+                above_max_count: counts_checked && counts > limits.max_count,
+            };
+        }
+        if violations.channels.iter().all(ChannelViolations::is_empty) {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
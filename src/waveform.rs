@@ -0,0 +1,195 @@
+//! Looping waveform tables
+
+use crate::{Channel, ChannelOnOffControl, Duty, Error, Pca9685};
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Table of duty cycles played in a loop
+///
+/// Each value is held for `step_ms` milliseconds. A duty cycle of 0 sets
+/// the channel to full-off.
+///
+/// ```
+/// use pwm_pca9685::{Duty, Waveform};
+///
+/// const fn duty(count: u16) -> Duty {
+///     match Duty::new(count) {
+///         Some(duty) => duty,
+///         None => Duty::new(4095).unwrap(),
+///     }
+/// }
+///
+/// static HEARTBEAT: [Duty; 6] = [duty(4095), duty(0), duty(4095), duty(0), duty(0), duty(0)];
+/// let waveform = Waveform {
+///     table: &HEARTBEAT,
+///     step_ms: 150,
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Waveform {
+    /// Duty cycles played in order.
+    pub table: &'static [Duty],
+    /// Time each duty cycle is held in milliseconds.
+    pub step_ms: u32,
+}
+
+impl Waveform {
+    fn value_at(&self, elapsed_ms: u32) -> Option<(usize, ChannelOnOffControl)> {
+        let steps = usize::try_from(elapsed_ms.checked_div(self.step_ms)?).ok()?;
+        let index = steps.checked_rem(self.table.len())?;
+        let duty = self.table.get(index)?;
+        Some((index, ChannelOnOffControl::from(*duty)))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct WaveformState {
+    waveform: Waveform,
+    start_ms: u32,
+    index: Option<usize>,
+    original: ChannelOnOffControl,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        cfg(not(feature = "async")),
+        self = "Pca9685",
+        idents(AsyncI2c(sync = "I2c"))
+    ),
+    async(feature = "async", keep_self)
+)]
+impl<I2C, E> Pca9685<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Start playing a waveform on a channel.
+    ///
+    /// The table starts at `now_ms` and is advanced on each call to
+    /// [`tick()`](Pca9685::tick). The channels whose value changed in a tick
+    /// are written together in a single I2C transaction. `Channel::All`
+    /// starts the waveform on every channel.
+    ///
+    /// Nothing is written to the device by this method. Playback stops when
+    /// the failsafe timeout is triggered.
+    ///
+    /// Returns `Error::InvalidInputData` if the table is empty or `step_ms`
+    /// is 0.
+    pub fn start_waveform(
+        &mut self,
+        channel: Channel,
+        waveform: Waveform,
+        now_ms: u32,
+    ) -> Result<(), Error<E>> {
+        if waveform.table.is_empty() || waveform.step_ms == 0 {
+            return Err(Error::InvalidInputData);
+        }
+        let outputs = self.outputs;
+        for (i, (state, value)) in self
+            .waveforms
+            .iter_mut()
+            .zip(outputs.channels())
+            .enumerate()
+        {
+            if channel.index().is_some_and(|index| index != i) {
+                continue;
+            }
+            *state = Some(WaveformState {
+                waveform,
+                start_ms: now_ms,
+                index: None,
+                original: *value,
+            });
+        }
+        Ok(())
+    }
+
+    /// Stop playing the waveform of a channel and restore the control values
+    /// it had when playback started.
+    ///
+    /// `Channel::All` stops every channel.
+    pub async fn stop_waveform(&mut self, channel: Channel) -> Result<(), Error<E>> {
+        for i in 0..self.waveforms.len() {
+            if channel.index().is_some_and(|index| index != i) {
+                continue;
+            }
+            let state = self.waveforms.get_mut(i).and_then(Option::take);
+            if let Some(state) = state {
+                if self.outputs.channels().get(i) != Some(&state.original) {
+                    self.write_channels(i, &[state.original]).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether a waveform is being played on a channel.
+    ///
+    /// Returns `false` for `Channel::All`.
+    pub fn is_waveform_playing(&self, channel: Channel) -> bool {
+        channel
+            .index()
+            .and_then(|i| self.waveforms.get(i))
+            .is_some_and(Option::is_some)
+    }
+
+    pub(crate) async fn service_waveforms(&mut self, now_ms: u32) -> Result<(), Error<E>> {
+        let mut frame = self.outputs;
+        let mut changed: Option<(usize, usize)> = None;
+        for (i, state) in self.waveforms.iter_mut().enumerate() {
+            let Some(state) = state else {
+                continue;
+            };
+            let elapsed = now_ms.wrapping_sub(state.start_ms);
+            let Some((index, value)) = state.waveform.value_at(elapsed) else {
+                continue;
+            };
+            if state.index != Some(index) {
+                state.index = Some(index);
+                if let Ok(channel) = Channel::try_from(i) {
+                    frame.set(channel, value);
+                }
+                changed = Some(changed.map_or((i, i), |(first, _)| (first, i)));
+            }
+        }
+        if let Some((first, last)) = changed {
+            let values = frame
+                .channels()
+                .get(first..=last)
+                .ok_or(Error::InvalidInputData)?;
+            self.write_channels(first, values).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn duty(count: u16) -> Duty {
+        match Duty::new(count) {
+            Some(duty) => duty,
+            None => panic!(),
+        }
+    }
+
+    static TABLE: [Duty; 3] = [duty(4095), duty(0), duty(4095)];
+
+    #[test]
+    fn loops_over_table() {
+        let waveform = Waveform {
+            table: &TABLE,
+            step_ms: 10,
+        };
+        assert_eq!(Some(0), waveform.value_at(9).map(|v| v.0));
+        assert_eq!(Some(1), waveform.value_at(10).map(|v| v.0));
+        assert_eq!(Some(0), waveform.value_at(30).map(|v| v.0));
+        assert_eq!(
+            Some(ChannelOnOffControl::full_off()),
+            waveform.value_at(15).map(|v| v.1)
+        );
+    }
+}
@@ -1,9 +1,11 @@
 use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
-use pwm_pca9685::{Address, ProgrammableAddress as ProgAddr};
+use pwm_pca9685::{
+    Address, AddressPins, Channel, ChannelOnOffControl, ProgrammableAddress as ProgAddr,
+};
 
 mod common;
 use self::common::{
-    assert_invalid_input_data, destroy, new, BitFlags, Register, DEV_ADDR, MODE1_DEFAULT,
+    assert_invalid_input_data, destroy, new, BitFlags, Register, DEV_ADDR, MODE1_AI, MODE1_DEFAULT,
 };
 
 invalid_test!(cannot_set_address_0, set_address, 0);
@@ -109,3 +111,43 @@ fn can_generate_alternative_addresses() {
         Address::from((true, true, true, true, true, true))
     );
 }
+
+#[test]
+fn can_generate_address_from_pins() {
+    let pins = AddressPins {
+        a4: true,
+        a0: true,
+        ..AddressPins::default()
+    };
+    assert_eq!(Address::from(0b101_0001), Address::from(pins));
+    assert_eq!(pins, AddressPins::from_solder_jumpers(0b1101_0001));
+    assert_eq!(
+        pins,
+        AddressPins::from((false, true, false, false, false, true))
+    );
+    assert_eq!(0b01_0001, pins.bits());
+}
+
+#[test]
+fn can_write_to_other_address_temporarily() {
+    let all_call = 0b111_0000;
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0, 8]),
+        I2cTrans::write(all_call, vec![Register::C0_OFF_L, 0, 0x18]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0, 4]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_on_off(Channel::C0, 0, 2048).unwrap();
+    pwm.at_address(all_call)
+        .unwrap()
+        .set_channel_full_off(Channel::C0)
+        .unwrap();
+    assert_eq!(
+        Some(ChannelOnOffControl::duty(2048).unwrap()),
+        pwm.snapshot().outputs.get(Channel::C0)
+    );
+    pwm.set_channel_on_off(Channel::C0, 0, 1024).unwrap();
+    assert!(pwm.at_address(0x80).is_err());
+    destroy(pwm);
+}
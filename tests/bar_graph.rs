@@ -0,0 +1,94 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{BarGraphStyle, Channel, ChannelMode, Error};
+
+mod common;
+use crate::common::{assert_invalid_input_data, destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+#[test]
+fn can_set_bar_graph() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::C4_ON_L,
+                0,
+                0b0001_0000,
+                0,
+                0,
+                0,
+                0,
+                0x71,
+                0x07,
+                0,
+                0,
+                0,
+                0b0001_0000,
+            ],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    // 2000 * 3 = 6000 = 4095 + 1905
+    pwm.set_bar_graph(Channel::C4, 3, 2000, BarGraphStyle::Fractional)
+        .unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_stepped_bar_graph_at_last_channel() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C15_ON_L, 0, 0b0001_0000, 0, 0]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_bar_graph(Channel::C15, 1, 4095, BarGraphStyle::Stepped)
+        .unwrap();
+    destroy(pwm);
+}
+
+invalid_test!(
+    cannot_set_bar_graph_all,
+    set_bar_graph,
+    Channel::All,
+    1,
+    0,
+    BarGraphStyle::Stepped
+);
+
+invalid_test!(
+    cannot_set_bar_graph_past_last_channel,
+    set_bar_graph,
+    Channel::C14,
+    3,
+    0,
+    BarGraphStyle::Stepped
+);
+
+invalid_test!(
+    cannot_set_bar_graph_empty,
+    set_bar_graph,
+    Channel::C0,
+    0,
+    0,
+    BarGraphStyle::Stepped
+);
+
+invalid_test!(
+    cannot_set_bar_graph_invalid_level,
+    set_bar_graph,
+    Channel::C0,
+    4,
+    4096,
+    BarGraphStyle::Stepped
+);
+
+#[test]
+fn bar_graph_checks_channel_modes() {
+    let mut pwm = new(&[]);
+    pwm.set_channel_mode(Channel::C1, ChannelMode::Digital);
+    assert_eq!(
+        Err(Error::ChannelModeMismatch(Channel::C1)),
+        pwm.set_bar_graph(Channel::C0, 4, 2000, BarGraphStyle::Fractional)
+    );
+    destroy(pwm);
+}
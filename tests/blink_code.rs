@@ -0,0 +1,69 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{BlinkCode, Channel};
+
+mod common;
+use crate::common::{assert_invalid_input_data, destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+const CODE: BlinkCode = BlinkCode {
+    count: 2,
+    on_ms: 100,
+    off_ms: 100,
+    pause_ms: 500,
+};
+
+#[test]
+fn plays_code_on_full_off_channel() {
+    let on = vec![Register::C7_ON_L, 0, 0b0001_0000, 0, 0];
+    let off = vec![Register::C7_ON_L, 0, 0, 0, 0b0001_0000];
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, on.clone()),
+        I2cTrans::write(DEV_ADDR, off.clone()),
+        I2cTrans::write(DEV_ADDR, on.clone()),
+        I2cTrans::write(DEV_ADDR, off.clone()),
+        I2cTrans::write(DEV_ADDR, on),
+    ];
+    let mut pwm = new(&trans);
+    pwm.start_blink_code(Channel::C7, CODE, 1000).unwrap();
+    assert!(pwm.is_blink_code_active());
+    pwm.tick(1000).unwrap();
+    pwm.tick(1100).unwrap();
+    pwm.tick(1200).unwrap();
+    pwm.tick(1300).unwrap();
+    pwm.tick(1800).unwrap();
+    pwm.tick(1900).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn stop_restores_duty() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0, 0, 0b0001_0000]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0, 0, 8]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_on_off(Channel::C1, 0, 2048).unwrap();
+    pwm.start_blink_code(Channel::C1, CODE, 0).unwrap();
+    pwm.tick(50).unwrap();
+    pwm.tick(150).unwrap();
+    pwm.stop_blink_code().unwrap();
+    assert!(!pwm.is_blink_code_active());
+    pwm.tick(200).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_start_invalid_code() {
+    let mut pwm = new(&[]);
+    assert_invalid_input_data(pwm.start_blink_code(Channel::All, CODE, 0));
+    let empty = BlinkCode { count: 0, ..CODE };
+    assert_invalid_input_data(pwm.start_blink_code(Channel::C0, empty, 0));
+    let long = BlinkCode {
+        pause_ms: u32::MAX,
+        ..CODE
+    };
+    assert_invalid_input_data(pwm.start_blink_code(Channel::C0, long, 0));
+    destroy(pwm);
+}
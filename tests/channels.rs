@@ -1,9 +1,17 @@
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
 use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
-use pwm_pca9685::{Channel, ChannelOnOffControl};
+use pwm_pca9685::{
+    duty, BrightnessCurve, Channel, ChannelLimits, ChannelMode, ChannelOnOffControl, ChannelSet,
+    ChannelViolations, Duty, Error, Frame, Mode2Flags, OutputLogicState, OutputStateChange,
+    Percent, Quirks,
+};
 use std::convert::TryFrom;
+use std::sync::atomic::{AtomicU32, Ordering};
 
 mod common;
-use self::common::{assert_invalid_input_data, destroy, new, Register, DEV_ADDR, MODE1_AI};
+use self::common::{
+    assert_invalid_input_data, destroy, new, BitFlags, Register, DEV_ADDR, MODE1_AI, MODE2_DEFAULT,
+};
 
 macro_rules! can_convert_channel {
     ($t:ty, $($value:expr, $channel:ident),*) => {
@@ -557,3 +565,1017 @@ fn can_set_all_channels() {
     pwm.set_all_channels(&values).unwrap();
     destroy(pwm);
 }
+
+#[test]
+fn duty_off_only_writes_off_registers_after_power_up() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_OFF_L, 0xFF, 0x07]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_OFF_L, 0xFF, 0x03]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_duty_off_only(Channel::C5, 2047).unwrap();
+    pwm.set_channel_duty_off_only(Channel::C5, 1023).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn duty_off_only_resets_nonzero_on_counter() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_ON_L, 0x10, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_ON_L, 0, 0, 0xFF, 0x03]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_OFF_L, 0xFF, 0x07]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_on(Channel::C5, 16).unwrap();
+    pwm.set_channel_duty_off_only(Channel::C5, 1023).unwrap();
+    pwm.set_channel_duty_off_only(Channel::C5, 2047).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn duty_off_only_resets_full_on() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_ON_L, 0, 0b0001_0000]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_ON_L, 0, 0, 0xFF, 0x03]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_full_on(Channel::C5, 0).unwrap();
+    pwm.set_channel_duty_off_only(Channel::C5, 1023).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn duty_off_only_all_channels() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_OFF_L, 0xFF, 0x07]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 1, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_ON_L, 0, 0, 0, 1]),
+        I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_OFF_L, 0, 0]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_duty_off_only(Channel::All, 2047).unwrap();
+    pwm.set_channel_on(Channel::C0, 1).unwrap();
+    pwm.set_channel_duty_off_only(Channel::All, 256).unwrap();
+    pwm.set_channel_duty_off_only(Channel::All, 0).unwrap();
+    destroy(pwm);
+}
+
+invalid_test!(
+    cannot_set_channel_duty_off_only_invalid_value,
+    set_channel_duty_off_only,
+    Channel::C0,
+    4096
+);
+
+#[test]
+fn can_set_channel_duty_from_any_representation() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0xFF, 0x0F]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0x00, 0x08]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0x28, 0x08]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_duty(Channel::C2, 255_u8).unwrap();
+    pwm.set_channel_duty(Channel::C2, 2048_u16).unwrap();
+    pwm.set_channel_duty(Channel::C2, Percent::new(51).unwrap())
+        .unwrap();
+    destroy(pwm);
+}
+
+invalid_test!(
+    cannot_set_channel_duty_invalid_value,
+    set_channel_duty,
+    Channel::C0,
+    4096_u16
+);
+
+#[test]
+fn can_set_channel_duty_from_macro_literals() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0xFF, 0x0B]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0x33, 0x01]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0xFF, 0x03]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_duty(Channel::C2, duty!(75%)).unwrap();
+    pwm.set_channel_duty(Channel::C2, duty!(1500 us @ 50 Hz))
+        .unwrap();
+    pwm.set_channel_duty(Channel::C2, duty!(0x3FF)).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_channels_on_off_from_slices() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C14_ON_L, 1, 0, 2, 0, 3, 0, 4, 0]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channels_on_off_from_slices(Channel::C14, &[1, 3], &[2, 4])
+        .unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_channel_range_on_off() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::C4_ON_L, 1, 0, 2, 0, 0, 0, 0xFF, 0x0F],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_range_on_off(Channel::C4, &[(1, 2), (0, 4095)])
+        .unwrap();
+    assert_invalid_input_data(pwm.set_channel_range_on_off(Channel::C4, &[]));
+    assert_invalid_input_data(pwm.set_channel_range_on_off(Channel::C15, &[(0, 1), (0, 1)]));
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_channels_from_iter() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::C3_ON_L, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::C0_ON_L,
+                0,
+                0b0001_0000,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0b0001_0000,
+            ],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channels_on_off_from_iter(Channel::C3, (1..4).map(|off| (0, off)))
+        .unwrap();
+    pwm.set_channels_from_iter(
+        Channel::C0,
+        [
+            ChannelOnOffControl::full_on(),
+            ChannelOnOffControl::full_off(),
+        ],
+    )
+    .unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_write_values_built_with_constructors() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_ON_L, 100, 0, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_ON_L, 100, 0, 0xFF, 0x0F]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_ON_L, 0, 0, 0, 0b0001_0000]),
+    ];
+    let mut pwm = new(&trans);
+    let value = ChannelOnOffControl::duty(2048)
+        .and_then(|value| value.with_on(100))
+        .unwrap();
+    pwm.replace_channel(Channel::C5, value).unwrap();
+    let previous = pwm
+        .replace_channel(Channel::C5, value.with_off(4095).unwrap())
+        .unwrap();
+    assert_eq!(value, previous);
+    assert_eq!(None, value.with_off(4096));
+    pwm.replace_channel(Channel::C5, ChannelOnOffControl::full_off())
+        .unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn encoded_values_match_device_registers() {
+    let value = ChannelOnOffControl {
+        on: 0x123,
+        off: 0xABC,
+        ..Default::default()
+    };
+    let mut data = vec![Register::C7_ON_L];
+    data.extend_from_slice(&value.encode());
+    let mut trans = vec![
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, data),
+    ];
+    for (register, contents) in (Register::C7_ON_L..).zip([0x23, 0x01, 0xBC, 0x0A]) {
+        trans.push(I2cTrans::write_read(
+            DEV_ADDR,
+            vec![register],
+            vec![contents],
+        ));
+    }
+    let mut pwm = new(&trans);
+    pwm.replace_channel(Channel::C7, value).unwrap();
+    let mut registers = [0; 4];
+    for (register, contents) in (Register::C7_ON_L..).zip(&mut registers) {
+        *contents = pwm.read_register_raw(register).unwrap();
+    }
+    assert_eq!(value, ChannelOnOffControl::decode(registers));
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_set_channels_from_invalid_slices() {
+    let mut pwm = new(&[]);
+    assert_invalid_input_data(pwm.set_channels_on_off_from_slices(Channel::C0, &[1], &[1, 2]));
+    assert_invalid_input_data(pwm.set_channels_on_off_from_slices(Channel::C0, &[], &[]));
+    assert_invalid_input_data(pwm.set_channels_on_off_from_slices(Channel::All, &[1], &[1]));
+    assert_invalid_input_data(pwm.set_channels_on_off_from_slices(Channel::C15, &[1, 2], &[1, 2]));
+    assert_invalid_input_data(pwm.set_channels_on_off_from_slices(Channel::C0, &[4096], &[1]));
+    assert_invalid_input_data(
+        pwm.set_channels_on_off_from_iter(Channel::C0, (0..17).map(|i| (i, i))),
+    );
+    destroy(pwm);
+}
+
+fn all_full_off_write() -> I2cTrans {
+    I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_ON_L, 0, 0, 0, 0b0001_0000])
+}
+
+#[test]
+fn commit_on_ack_restores_on_stop() {
+    let trans = [
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE2, MODE2_DEFAULT | BitFlags::OCH],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        all_full_off_write(),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE2, MODE2_DEFAULT]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.commit_on_ack(&Frame::all_off()).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn commit_on_ack_restores_on_stop_if_frame_write_fails() {
+    let nack = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data);
+    let trans = [
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE2, MODE2_DEFAULT | BitFlags::OCH],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        all_full_off_write().with_error(nack),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE2, MODE2_DEFAULT]),
+    ];
+    let mut pwm = new(&trans);
+    match pwm.commit_on_ack(&Frame::all_off()) {
+        Err(Error::I2C(error)) => assert_eq!(nack, error),
+        _ => panic!("Expected I2C error"),
+    }
+    assert_eq!(Mode2Flags::default(), pwm.device_config().mode2);
+    destroy(pwm);
+}
+
+#[test]
+fn commit_on_ack_keeps_on_ack() {
+    let trans = [
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE2, MODE2_DEFAULT | BitFlags::OCH],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        all_full_off_write(),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_output_change_behavior(OutputStateChange::OnAck)
+        .unwrap();
+    pwm.commit_on_ack(&Frame::all_off()).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_all_full_off() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        all_full_off_write(),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_all_full_off().unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_toggle_channel() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_ON_L, 1, 0, 2, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 2, 0b0001_0000]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 2, 0]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_on_off(Channel::C2, 1, 2).unwrap();
+    assert!(pwm.toggle_channel(Channel::C2).unwrap());
+    assert!(!pwm.toggle_channel(Channel::C2).unwrap());
+    assert_invalid_input_data(pwm.toggle_channel(Channel::All));
+    destroy(pwm);
+}
+
+#[test]
+fn toggle_after_full_off_restores_duty() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_ON_L, 0, 0, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0, 0b0001_1000]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0, 8]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_on_off(Channel::C2, 0, 2048).unwrap();
+    pwm.set_channel_full_off(Channel::C2).unwrap();
+    assert!(!pwm.toggle_channel(Channel::C2).unwrap());
+    assert_eq!(
+        ChannelOnOffControl::duty(2048),
+        pwm.snapshot().outputs.get(Channel::C2)
+    );
+    destroy(pwm);
+}
+
+#[test]
+fn toggle_after_driver_state_reset_uses_power_up_values() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_ON_L, 1, 0, 2, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0, 0]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_on_off(Channel::C2, 1, 2).unwrap();
+    pwm.reset_internal_driver_state();
+    assert_eq!(Frame::all_off(), pwm.snapshot().outputs);
+    assert!(!pwm.toggle_channel(Channel::C2).unwrap());
+    destroy(pwm);
+}
+
+#[test]
+fn can_read_all_channels() {
+    let mut data = vec![0; 64];
+    data[0..4].copy_from_slice(&[1, 0, 0xFF, 0x0F]);
+    data[60..64].copy_from_slice(&[0, 0, 0, 0b0001_0000]);
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::C0_ON_L], data),
+    ];
+    let mut pwm = new(&trans);
+    let values = pwm.read_all_channels().unwrap();
+    assert_eq!(ChannelOnOffControl::try_from((1, 4095)), Ok(values[0]));
+    assert_eq!(ChannelOnOffControl::default(), values[1]);
+    assert_eq!(ChannelOnOffControl::full_off(), values[15]);
+    destroy(pwm);
+}
+
+#[test]
+fn toggle_after_output_sync_uses_read_values() {
+    let mut data = vec![0; 64];
+    data[8..12].copy_from_slice(&[1, 0, 0x34, 0b0001_0010]);
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::C0_ON_L], data),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0x34, 2]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.sync_outputs_from_device().unwrap();
+    assert!(!pwm.toggle_channel(Channel::C2).unwrap());
+    assert_eq!(
+        ChannelOnOffControl::try_from((1, 0x234)).ok(),
+        pwm.snapshot().outputs.get(Channel::C2)
+    );
+    destroy(pwm);
+}
+
+#[test]
+fn can_write_frame() {
+    let mut data = vec![Register::C0_ON_L];
+    data.extend_from_slice(&[0, 0, 0xFF, 0x0F]);
+    for _ in 1..16 {
+        data.extend_from_slice(&[0, 0, 0, 0b0001_0000]);
+    }
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, data),
+    ];
+    let mut pwm = new(&trans);
+    let mut frame = Frame::all_off();
+    frame.set_duty(Channel::C0, Duty::MAX);
+    pwm.write_frame(&frame).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_write_frame_diff() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::C1_ON_L, 0, 0, 0xFF, 0x0F, 0, 0, 0xFF, 0x0F],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Register::C15_ON_L, 0, 0b0001_0000, 0, 0]),
+    ];
+    let mut pwm = new(&trans);
+    let old = Frame::all_off();
+    let mut new_frame = old;
+    new_frame.set_duty(Channel::C1, Duty::MAX);
+    new_frame.set_duty(Channel::C2, Duty::MAX);
+    new_frame.set_full_on(Channel::C15);
+    pwm.write_frame_diff(&old, &new_frame).unwrap();
+    pwm.write_frame_diff(&new_frame, &new_frame).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn uniform_values_are_written_to_all_channel_registers() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_ON_L, 1, 0, 2, 0]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_all_on_off(&[1; 16], &[2; 16]).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn uniform_subset_is_written_to_channel_registers() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::C0_ON_L,
+                1,
+                0,
+                2,
+                0,
+                1,
+                0,
+                2,
+                0,
+                1,
+                0,
+                2,
+                0,
+                1,
+                0,
+                2,
+                0,
+            ],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_first_4_channels_on_off(&[1; 4], &[2; 4]).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_channel_brightness_with_curve() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C3_OFF_L, 0x08, 0x08]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C3_OFF_L, 0xF9, 0x02]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_brightness(Channel::C3, 128).unwrap();
+    pwm.set_brightness_curve(BrightnessCurve::Cie1931);
+    pwm.set_channel_brightness(Channel::C3, 128).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_all_from_u8() {
+    let mut data = vec![Register::C0_ON_L];
+    data.extend_from_slice(&[0, 0, 0xFF, 0x0F, 0, 0, 0xF9, 0x02]);
+    for _ in 2..16 {
+        data.extend_from_slice(&[0, 0, 0, 0x10]);
+    }
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, data),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_brightness_curve(BrightnessCurve::Cie1931);
+    let mut levels = [0; 16];
+    levels[0] = 255;
+    levels[1] = 128;
+    pwm.set_all_from_u8(&levels).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_update_channel_with_closure() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0, 0xE8, 3]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0, 0x4C, 4]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_on_off(Channel::C1, 0, 1000).unwrap();
+    pwm.update_channel(Channel::C1, |value| value.off += 100)
+        .unwrap();
+    pwm.update_channel(Channel::C1, |value| value.off = 1100)
+        .unwrap();
+    assert_invalid_input_data(pwm.update_channel(Channel::All, |_| ()));
+    assert_invalid_input_data(pwm.update_channel(Channel::C1, |value| value.off = 4096));
+    destroy(pwm);
+}
+
+#[test]
+fn can_split_writes_at_max_len() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(
+            DEV_ADDR,
+            [vec![Register::C0_ON_L], [1, 0, 2, 0].repeat(4)].concat(),
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            [vec![Register::C4_ON_L], [1, 0, 2, 0].repeat(4)].concat(),
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Register::C8_ON_L, 1, 0, 2, 0]),
+    ];
+    let mut pwm = new(&trans);
+    assert_invalid_input_data(pwm.set_max_write_len(4));
+    pwm.set_max_write_len(20).unwrap();
+    pwm.set_channels_on_off_from_slices(Channel::C0, &[1; 9], &[2; 9])
+        .unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_estimate_current() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0x10, 0, 0]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE2, MODE2_DEFAULT | BitFlags::INVRT],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_current_ma(Channel::All, 20);
+    pwm.set_channel_current_ma(Channel::C1, 100);
+    assert_eq!(0, pwm.estimated_current_ma());
+    pwm.set_channel_on_off(Channel::C0, 0, 2048).unwrap();
+    pwm.update_channel(Channel::C1, |v| *v = ChannelOnOffControl::full_on())
+        .unwrap();
+    assert_eq!(110, pwm.estimated_current_ma());
+    pwm.set_output_logic_state(OutputLogicState::Inverted)
+        .unwrap();
+    assert_eq!(14 * 20 + 10, pwm.estimated_current_ma());
+    destroy(pwm);
+}
+
+#[test]
+fn can_adjust_duty_with_saturation() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 100, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0xFF, 0x0F]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.adjust_duty(Channel::C2, 100).unwrap();
+    pwm.adjust_duty(Channel::C2, -200).unwrap();
+    pwm.adjust_duty(Channel::C2, -1).unwrap();
+    pwm.adjust_duty(Channel::C2, i16::MAX).unwrap();
+    pwm.adjust_duty(Channel::C2, 1).unwrap();
+    assert_invalid_input_data(pwm.adjust_duty(Channel::All, 1));
+    destroy(pwm);
+}
+
+#[test]
+fn adjusted_zero_duty_matches_set_duty() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 100, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C3_OFF_L, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 100, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 100, 0b0001_0000]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.adjust_duty(Channel::C2, 100).unwrap();
+    pwm.adjust_duty(Channel::C2, -100).unwrap();
+    pwm.set_channel_duty(Channel::C3, 0_u16).unwrap();
+    let outputs = pwm.snapshot().outputs;
+    assert_eq!(outputs.get(Channel::C3), outputs.get(Channel::C2));
+    pwm.set_full_on_off_promotion(true);
+    pwm.adjust_duty(Channel::C2, 100).unwrap();
+    pwm.adjust_duty(Channel::C2, -100).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn duty_is_clamped_and_small_changes_ignored() {
+    let mut trans = vec![
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_OFF_L, 0xA0, 0x0F]),
+        I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_OFF_L, 100, 0]),
+    ];
+    for i in 0..16 {
+        let off: u16 = if i == 1 { 3000 } else { 4000 };
+        trans.push(I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::C0_OFF_L + i * 4, off as u8, (off >> 8) as u8],
+        ));
+    }
+    let mut pwm = new(&trans);
+    let limits = ChannelLimits {
+        min_count: 100,
+        max_count: 4000,
+        deadband: 50,
+    };
+    pwm.set_channel_limits(Channel::All, limits).unwrap();
+    pwm.set_channel_limits(
+        Channel::C1,
+        ChannelLimits {
+            max_count: 3000,
+            ..limits
+        },
+    )
+    .unwrap();
+    assert_eq!(Some(limits), pwm.channel_limits(Channel::C0));
+    pwm.set_channel_duty(Channel::C0, 4095_u16).unwrap();
+    pwm.set_channel_duty(Channel::C0, 3951_u16).unwrap();
+    pwm.set_channel_duty(Channel::All, 0_u16).unwrap();
+    pwm.set_channel_duty(Channel::All, 4095_u16).unwrap();
+    assert_invalid_input_data(pwm.set_channel_limits(
+        Channel::C0,
+        ChannelLimits {
+            min_count: 200,
+            max_count: 100,
+            deadband: 0,
+        },
+    ));
+    destroy(pwm);
+}
+
+#[test]
+fn can_promote_extreme_duty_to_full_on_off() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_OFF_L, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_OFF_L, 0, 0x10]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_ON_L, 0, 0x10, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_ON_L, 0, 0, 0xFE, 0x0F]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_duty(Channel::C5, 0_u16).unwrap();
+    pwm.set_full_on_off_promotion(true);
+    pwm.set_channel_duty(Channel::C5, 0_u16).unwrap();
+    pwm.set_channel_duty(Channel::C5, 255_u8).unwrap();
+    pwm.set_channel_duty(Channel::C5, 4094_u16).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_write_single_registers() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 2]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L + 1, 1]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_OFF_L, 4]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_OFF_L + 1, 3]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_single_register_writes(true);
+    pwm.set_channel_on_off(Channel::C0, 0x102, 0x304).unwrap();
+    assert!(!pwm.is_auto_increment_enabled());
+    destroy(pwm);
+}
+
+#[test]
+fn can_validate_frame() {
+    let mut pwm = new(&[]);
+    pwm.set_channel_mode(Channel::C2, ChannelMode::Digital);
+    pwm.set_channel_limits(
+        Channel::C3,
+        ChannelLimits {
+            min_count: 100,
+            max_count: 200,
+            deadband: 0,
+        },
+    )
+    .unwrap();
+    let mut frame = Frame::all_off();
+    assert_eq!(Ok(()), pwm.validate_frame(&frame));
+    let duty = |off| ChannelOnOffControl::duty(off).unwrap();
+    frame.set(
+        Channel::C0,
+        ChannelOnOffControl {
+            on: 4096,
+            ..duty(10)
+        },
+    );
+    frame.set(
+        Channel::C1,
+        ChannelOnOffControl {
+            full_on: true,
+            full_off: true,
+            off: 5000,
+            on: 0,
+        },
+    );
+    frame.set(Channel::C2, duty(50));
+    frame.set(Channel::C3, duty(201));
+    frame.set(Channel::C4, duty(201));
+    let violations = pwm.validate_frame(&frame).unwrap_err();
+    let none = ChannelViolations::default();
+    assert_eq!(
+        vec![
+            (
+                Channel::C0,
+                ChannelViolations {
+                    on_out_of_range: true,
+                    ..none
+                }
+            ),
+            (
+                Channel::C1,
+                ChannelViolations {
+                    off_out_of_range: true,
+                    full_on_and_full_off: true,
+                    ..none
+                }
+            ),
+            (
+                Channel::C2,
+                ChannelViolations {
+                    mode_mismatch: true,
+                    ..none
+                }
+            ),
+            (
+                Channel::C3,
+                ChannelViolations {
+                    above_max_count: true,
+                    ..none
+                }
+            ),
+        ],
+        violations.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(Some(none), violations.get(Channel::C4));
+    destroy(pwm);
+}
+
+#[test]
+fn can_label_channels() {
+    let mut pwm = new(&[]);
+    pwm.set_channel_label(Channel::All, Some("spare"));
+    pwm.set_channel_label(Channel::C3, Some("left aileron"));
+    pwm.set_channel_label(Channel::C4, None);
+    assert_eq!(Some("left aileron"), pwm.channel_label(Channel::C3));
+    assert_eq!(Some("spare"), pwm.channel_label(Channel::C0));
+    assert_eq!(None, pwm.channel_label(Channel::C4));
+    assert_eq!(None, pwm.channel_label(Channel::All));
+    assert_eq!(
+        "channel 3 (left aileron)",
+        pwm.channel_name(Channel::C3).to_string()
+    );
+    assert_eq!("channel 4", pwm.channel_name(Channel::C4).to_string());
+    pwm.set_channel_mode(Channel::C3, ChannelMode::Servo);
+    let error = pwm.set_channel_full_on(Channel::C3, 0).unwrap_err();
+    assert_eq!(
+        "Operation does not match the mode of channel 3 (left aileron)",
+        pwm.describe_error(&error).to_string()
+    );
+    destroy(pwm);
+}
+
+#[test]
+fn setters_can_return_previous_values() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_ON_L, 0, 0, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0, 4]),
+    ];
+    let mut pwm = new(&trans);
+    let value = ChannelOnOffControl::duty(2048).unwrap();
+    assert_eq!(
+        ChannelOnOffControl::full_off(),
+        pwm.replace_channel(Channel::C2, value).unwrap()
+    );
+    assert_eq!(
+        2048,
+        pwm.replace_channel_duty(Channel::C2, 1024_u16).unwrap()
+    );
+    assert_invalid_input_data(pwm.replace_channel(Channel::All, value));
+    assert_invalid_input_data(pwm.replace_channel_duty(Channel::All, 0_u16));
+    destroy(pwm);
+}
+
+#[test]
+fn can_track_stale_channels() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_OFF_L, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0, 8]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_off(Channel::C1, 2048).unwrap();
+    assert_eq!(None, pwm.channel_age_ms(Channel::C1));
+    assert_eq!(0, pwm.stale_channels(0).count());
+    pwm.tick(1000).unwrap();
+    pwm.tick(1100).unwrap();
+    pwm.set_channel_off(Channel::C2, 2048).unwrap();
+    pwm.tick(1150).unwrap();
+    assert_eq!(Some(150), pwm.channel_age_ms(Channel::C1));
+    assert_eq!(Some(50), pwm.channel_age_ms(Channel::C2));
+    assert_eq!(None, pwm.channel_age_ms(Channel::All));
+    let stale: Vec<_> = pwm.stale_channels(100).collect();
+    assert_eq!(15, stale.len());
+    assert!(!stale.contains(&Channel::C2));
+    destroy(pwm);
+}
+
+#[test]
+fn can_clamp_out_of_range_values() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0xFF, 0x0F, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_OFF_L, 0xFF, 0x0F]),
+    ];
+    let mut pwm = new(&trans);
+    assert_invalid_input_data(pwm.set_channel_on(Channel::C0, 4096));
+    pwm.set_clamp_out_of_range(true);
+    assert!(!pwm.take_clamped());
+    pwm.set_channel_on_off(Channel::C0, 5000, 0).unwrap();
+    assert!(pwm.take_clamped());
+    assert!(!pwm.take_clamped());
+    pwm.set_channel_duty(Channel::C1, 9999_u16).unwrap();
+    assert!(pwm.take_clamped());
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_duty_at_phase() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C3_ON_L, 0xAC, 0x0D, 0x94, 0x01]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_duty_at_phase(Channel::C3, 1000_u16, 3500)
+        .unwrap();
+    assert_invalid_input_data(pwm.set_channel_duty_at_phase(Channel::C3, 1000_u16, 4096));
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_phase_width() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C3_ON_L, 0xAC, 0x0D, 0x94, 0x01]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C3_ON_L, 0, 0x10, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C3_ON_L, 0, 0, 0, 0x10]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_phase_width(Channel::C3, 3500, 1000)
+        .unwrap();
+    pwm.set_channel_phase_width(Channel::C3, 100, 4096).unwrap();
+    pwm.set_channel_phase_width(Channel::C3, 100, 0).unwrap();
+    assert_invalid_input_data(pwm.set_channel_phase_width(Channel::C3, 4096, 10));
+    assert_invalid_input_data(pwm.set_channel_phase_width(Channel::C3, 0, 4097));
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_channel_set() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::C1_ON_L,
+                0,
+                0,
+                0,
+                0x08,
+                0,
+                0,
+                0,
+                0x10,
+                0,
+                0,
+                0,
+                0x08,
+            ],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::C1_ON_L,
+                0,
+                0,
+                0,
+                0x10,
+                0,
+                0,
+                0,
+                0x10,
+                0,
+                0,
+                0,
+                0x10,
+            ],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    let set = ChannelSet::EMPTY.with(Channel::C1).with(Channel::C3);
+    pwm.set_channels_duty(set, 2048_u16).unwrap();
+    pwm.set_channels_full_off(set).unwrap();
+    pwm.set_channels_full_off(ChannelSet::EMPTY).unwrap();
+    destroy(pwm);
+}
+
+static QUIRK_DELAY_US: AtomicU32 = AtomicU32::new(0);
+
+fn quirk_delay(us: u32) {
+    QUIRK_DELAY_US.fetch_add(us, Ordering::SeqCst);
+}
+
+#[test]
+fn can_apply_clone_quirks() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_OFF_L, 0, 1]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_OFF_L, 0, 2]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_OFF_L, 0, 3]),
+    ];
+    let mut pwm = new(&trans);
+    let quirks = Quirks {
+        mode_write_delay_us: 500,
+        auto_increment_gap_us: 100,
+        reassert_auto_increment_every: 2,
+    };
+    pwm.set_quirks(quirks, Some(quirk_delay));
+    assert_eq!(quirks, pwm.quirks());
+    pwm.set_channel_duty(Channel::C0, 0x100_u16).unwrap();
+    assert_eq!(500, QUIRK_DELAY_US.load(Ordering::SeqCst));
+    pwm.set_channel_duty(Channel::C1, 0x200_u16).unwrap();
+    assert_eq!(1000, QUIRK_DELAY_US.load(Ordering::SeqCst));
+    pwm.set_channel_duty(Channel::C2, 0x300_u16).unwrap();
+    assert_eq!(1100, QUIRK_DELAY_US.load(Ordering::SeqCst));
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_analog_output() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        // 1650 * 4095 / 3300 = 2047.5
+        I2cTrans::write(DEV_ADDR, vec![Register::C4_OFF_L, 0, 8]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_analog_output(Channel::C4, 1650, 3300).unwrap();
+    assert_invalid_input_data(pwm.set_analog_output(Channel::C4, 3301, 3300));
+    assert_invalid_input_data(pwm.set_analog_output(Channel::C4, 0, 0));
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_channel_duty_percent() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        // 50 * 4095 / 100 = 2047.5
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_OFF_L, 0, 8]),
+        // 1250 * 4095 / 10000 = 511.875
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_OFF_L, 0, 2]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_duty_percent(Channel::C0, 50).unwrap();
+    pwm.set_channel_duty_percent_hundredths(Channel::C1, 1250)
+        .unwrap();
+    assert_invalid_input_data(pwm.set_channel_duty_percent(Channel::C0, 101));
+    assert_invalid_input_data(pwm.set_channel_duty_percent_hundredths(Channel::C0, 10_001));
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_channel_duty_u16() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_OFF_L, 0xFF, 0x0F]),
+        // 0x8000 * 4095 / 65535 = 2047.53
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_OFF_L, 0, 8]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_duty_u16(Channel::C0, u16::MAX).unwrap();
+    pwm.set_channel_duty_u16(Channel::C0, 0x8000).unwrap();
+    destroy(pwm);
+}
@@ -0,0 +1,129 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{Channel, ChannelOnOffControl, Frame, Priority};
+
+mod common;
+use crate::common::{assert_invalid_input_data, destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+fn frame() -> Frame {
+    let mut channels = [ChannelOnOffControl::default(); 16];
+    for (i, c) in channels.iter_mut().enumerate() {
+        c.off = i as u16;
+    }
+    Frame::new(channels)
+}
+
+fn chunk_write(first: u8, count: u8) -> I2cTrans {
+    let mut data = vec![Register::C0_ON_L + first * 4];
+    for i in first..first + count {
+        data.extend_from_slice(&[0, 0, i, 0]);
+    }
+    I2cTrans::write(DEV_ADDR, data)
+}
+
+#[test]
+fn start_does_not_write() {
+    let mut pwm = new(&[]);
+    pwm.start_chunked_commit(frame(), 4).unwrap();
+    assert!(pwm.is_chunked_commit_pending());
+    destroy(pwm);
+}
+
+#[test]
+fn writes_one_chunk_per_call() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        chunk_write(0, 6),
+        chunk_write(6, 6),
+        chunk_write(12, 4),
+    ];
+    let mut pwm = new(&trans);
+    pwm.start_chunked_commit(frame(), 6).unwrap();
+    assert!(!pwm.commit_next_chunk().unwrap());
+    assert!(!pwm.commit_next_chunk().unwrap());
+    assert!(pwm.commit_next_chunk().unwrap());
+    assert!(!pwm.is_chunked_commit_pending());
+    assert!(pwm.commit_next_chunk().unwrap());
+    destroy(pwm);
+}
+
+#[test]
+fn tick_writes_one_chunk() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        chunk_write(0, 8),
+        chunk_write(8, 8),
+    ];
+    let mut pwm = new(&trans);
+    pwm.start_chunked_commit(frame(), 8).unwrap();
+    pwm.tick(0).unwrap();
+    pwm.tick(1).unwrap();
+    pwm.tick(2).unwrap();
+    assert!(!pwm.is_chunked_commit_pending());
+    destroy(pwm);
+}
+
+#[test]
+fn can_cancel() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        chunk_write(0, 8),
+    ];
+    let mut pwm = new(&trans);
+    pwm.start_chunked_commit(frame(), 8).unwrap();
+    pwm.commit_next_chunk().unwrap();
+    pwm.cancel_chunked_commit();
+    assert!(!pwm.is_chunked_commit_pending());
+    pwm.tick(0).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_start_with_invalid_chunk_size() {
+    let mut pwm = new(&[]);
+    assert_invalid_input_data(pwm.start_chunked_commit(frame(), 0));
+    assert_invalid_input_data(pwm.start_chunked_commit(frame(), 17));
+    assert!(!pwm.is_chunked_commit_pending());
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_start_with_invalid_value() {
+    let mut pwm = new(&[]);
+    let mut f = frame();
+    f.set(
+        pwm_pca9685::Channel::C3,
+        ChannelOnOffControl {
+            off: 4096,
+            ..Default::default()
+        },
+    );
+    assert_invalid_input_data(pwm.start_chunked_commit(f, 4));
+    destroy(pwm);
+}
+
+#[test]
+fn writes_high_priority_channels_first() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        chunk_write(5, 1),
+        chunk_write(9, 1),
+        chunk_write(0, 2),
+        chunk_write(2, 2),
+        chunk_write(6, 2),
+        chunk_write(8, 1),
+        chunk_write(10, 3),
+        chunk_write(13, 3),
+        chunk_write(4, 1),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_priority(Channel::C5, Priority::High);
+    pwm.set_channel_priority(Channel::C9, Priority::High);
+    pwm.set_channel_priority(Channel::C4, Priority::Low);
+    assert_eq!(Some(Priority::Low), pwm.channel_priority(Channel::C4));
+    pwm.start_chunked_commit(frame(), 4).unwrap();
+    assert!(!pwm.commit_next_chunk().unwrap());
+    assert!(!pwm.commit_next_chunk().unwrap());
+    assert!(!pwm.commit_next_chunk().unwrap());
+    assert!(pwm.commit_next_chunk().unwrap());
+    destroy(pwm);
+}
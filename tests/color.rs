@@ -0,0 +1,53 @@
+#![cfg(feature = "rgb")]
+
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{boards::RgbPins, Channel, Frame, Segment};
+use rgb::{RGB16, RGB8};
+
+mod common;
+use crate::common::{destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+fn pins(first: Channel) -> RgbPins {
+    let first = first as u8;
+    let channel = |offset| Channel::try_from(first + offset).unwrap();
+    RgbPins {
+        red: channel(0),
+        green: channel(1),
+        blue: channel(2),
+    }
+}
+
+#[test]
+fn can_write_rgb8_color() {
+    // 128 / 255 * 4095 = 2055.5 counts
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C9_ON_L, 0, 0, 0xFF, 0x0F]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C11_ON_L, 0, 0, 0x08, 0x08]),
+    ];
+    let mut pwm = new(&trans);
+    let segment = Segment::from_rgb_pins(&[pins(Channel::C9)]).unwrap();
+    let mut frame = Frame::all_off();
+    assert!(segment.set_pixel_color(&mut frame, 0, RGB8::new(255, 0, 128)));
+    pwm.write_frame_diff(&Frame::all_off(), &frame).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_write_row_of_rgb16_colors() {
+    // 16-bit components are truncated to 12 bits: 0x1234 -> 0x123
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0xFF, 0x0F]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C4_ON_L, 0, 0, 0x23, 0x01]),
+    ];
+    let mut pwm = new(&trans);
+    let segment = Segment::from_rgb_pins(&[pins(Channel::C0), pins(Channel::C3)]).unwrap();
+    let mut frame = Frame::all_off();
+    // Overwritten by the green component of the first color.
+    frame.set_full_on(Channel::C1);
+    let colors = [RGB16::new(0xFFFF, 0, 0), RGB16::new(0, 0x1234, 0)];
+    segment.set_pixel_colors(&mut frame, &colors);
+    pwm.write_frame_diff(&Frame::all_off(), &frame).unwrap();
+    destroy(pwm);
+}
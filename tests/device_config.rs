@@ -0,0 +1,220 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{
+    Channel, ChannelOnOffControl, ConfigDiff, DeviceConfig, DeviceSnapshot, Error, Mode1Flags,
+    Mode2Flags, PROFILE_SLOTS,
+};
+
+mod common;
+use crate::common::{
+    assert_invalid_input_data, destroy, new, BitFlags, Register, DEV_ADDR, MODE1_DEFAULT,
+    MODE2_DEFAULT,
+};
+
+#[test]
+fn power_up_config_needs_no_writes() {
+    let mut pwm = new(&[]);
+    assert_eq!(DeviceConfig::default(), pwm.device_config());
+    pwm.apply_config(DeviceConfig::default()).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_sync_from_device() {
+    let mode1 = BitFlags::RESTART | BitFlags::AUTO_INC | BitFlags::ALL_CALL_ADDR;
+    let trans = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::MODE1], vec![mode1]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::MODE2],
+            vec![MODE2_DEFAULT | BitFlags::INVRT],
+        ),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::PRE_SCALE], vec![121]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.sync_from_device().unwrap();
+    let config = pwm.device_config();
+    assert_eq!(Mode1Flags::AI | Mode1Flags::ALLCALL, config.mode1);
+    assert_eq!(Mode2Flags::default() | Mode2Flags::INVRT, config.mode2);
+    assert_eq!(121, config.prescale);
+    assert!(pwm.is_auto_increment_enabled());
+    destroy(pwm);
+}
+
+#[test]
+fn can_verify_config() {
+    let trans = [
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::MODE1],
+            vec![MODE1_DEFAULT | BitFlags::RESTART],
+        ),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::MODE2], vec![MODE2_DEFAULT]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::MODE1], vec![BitFlags::AUTO_INC]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::MODE2],
+            vec![MODE2_DEFAULT | BitFlags::INVRT],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    pwm.verify_config().unwrap();
+    let expected = ConfigDiff {
+        mode1: Mode1Flags::AI | Mode1Flags::SLEEP | Mode1Flags::ALLCALL,
+        mode2: Mode2Flags::INVRT,
+        device_mode1: Mode1Flags::AI,
+        device_mode2: Mode2Flags::default() | Mode2Flags::INVRT,
+    };
+    assert_eq!(Err(Error::ConfigMismatch(expected)), pwm.verify_config());
+    destroy(pwm);
+}
+
+#[test]
+fn writes_changed_registers_only() {
+    let trans = [
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE2, MODE2_DEFAULT | BitFlags::INVRT],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 100]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, BitFlags::ALL_CALL_ADDR]),
+    ];
+    let mut pwm = new(&trans);
+    let target = DeviceConfig {
+        mode1: Mode1Flags::ALLCALL,
+        mode2: Mode2Flags::default() | Mode2Flags::INVRT,
+        prescale: 100,
+    };
+    pwm.apply_config(target).unwrap();
+    assert_eq!(target, pwm.device_config());
+    pwm.apply_config(target).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn stops_oscillator_to_select_external_clock() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, BitFlags::ALL_CALL_ADDR]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_DEFAULT]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE1, MODE1_DEFAULT | BitFlags::EXT_CLK],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE1, BitFlags::ALL_CALL_ADDR | BitFlags::EXT_CLK],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    pwm.enable().unwrap();
+    let target = DeviceConfig {
+        mode1: Mode1Flags::ALLCALL | Mode1Flags::EXTCLK,
+        ..DeviceConfig::default()
+    };
+    pwm.apply_config(target).unwrap();
+    let internal = DeviceConfig::default();
+    assert_invalid_input_data(pwm.apply_config(internal));
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_apply_invalid_prescale() {
+    let mut pwm = new(&[]);
+    let target = DeviceConfig {
+        prescale: 2,
+        ..DeviceConfig::default()
+    };
+    assert_invalid_input_data(pwm.apply_config(target));
+    destroy(pwm);
+}
+
+#[test]
+fn can_restore_snapshot() {
+    let mut channels = vec![Register::C0_ON_L];
+    channels.extend([0, 0, 0x34, 0x02]);
+    channels.extend([0, 0, 0, 0b0001_0000].repeat(15));
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 100]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE1, MODE1_DEFAULT | BitFlags::AUTO_INC],
+        ),
+        I2cTrans::write(DEV_ADDR, channels),
+    ];
+    let mut pwm = new(&trans);
+    let mut snapshot = pwm.snapshot();
+    snapshot.config.prescale = 100;
+    snapshot.config.mode1.insert(Mode1Flags::AI);
+    snapshot.outputs.set(
+        Channel::C0,
+        ChannelOnOffControl {
+            off: 0x234,
+            ..Default::default()
+        },
+    );
+    let bytes = snapshot.to_bytes();
+    let restored = DeviceSnapshot::from_bytes(&bytes).unwrap();
+    pwm.restore(&restored).unwrap();
+    assert_eq!(snapshot, pwm.snapshot());
+    destroy(pwm);
+}
+
+fn off(off: u16) -> ChannelOnOffControl {
+    ChannelOnOffControl {
+        off,
+        ..Default::default()
+    }
+}
+
+fn c2_to_c5(c2: u16, c5: u16) -> Vec<u8> {
+    let mut data = vec![Register::C2_ON_L, 0, 0, c2 as u8, (c2 >> 8) as u8];
+    data.extend([0, 0, 0, 0b0001_0000].repeat(2));
+    data.extend([0, 0, c5 as u8, (c5 >> 8) as u8]);
+    data
+}
+
+#[test]
+fn can_switch_and_crossfade_profiles() {
+    let trans = [
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE1, MODE1_DEFAULT | BitFlags::AUTO_INC],
+        ),
+        I2cTrans::write(DEV_ADDR, c2_to_c5(1000, 3000)),
+        I2cTrans::write(DEV_ADDR, c2_to_c5(2000, 1000)),
+        I2cTrans::write(DEV_ADDR, c2_to_c5(1500, 2000)),
+        I2cTrans::write(DEV_ADDR, c2_to_c5(1000, 3000)),
+    ];
+    let mut pwm = new(&trans);
+    let mut day = pwm.snapshot();
+    day.config.mode1.insert(Mode1Flags::AI);
+    day.outputs.set(Channel::C2, off(1000));
+    day.outputs.set(Channel::C5, off(3000));
+    let mut night = day;
+    night.outputs.set(Channel::C2, off(2000));
+    night.outputs.set(Channel::C5, off(1000));
+    pwm.store_profile(0, day).unwrap();
+    pwm.store_profile(1, night).unwrap();
+    assert_eq!(Some(night), pwm.profile(1));
+    assert_eq!(None, pwm.profile(2));
+    pwm.switch_profile(0).unwrap();
+    pwm.switch_profile(0).unwrap();
+    pwm.switch_profile(1).unwrap();
+    pwm.crossfade_to_profile(0, 1000, 100).unwrap();
+    assert!(pwm.is_profile_crossfade_active());
+    pwm.tick(600).unwrap();
+    pwm.tick(1100).unwrap();
+    assert!(!pwm.is_profile_crossfade_active());
+    pwm.tick(2000).unwrap();
+    assert_eq!(day, pwm.snapshot());
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_use_invalid_profile_slot() {
+    let mut pwm = new(&[]);
+    let profile = pwm.snapshot();
+    assert_invalid_input_data(pwm.store_profile(PROFILE_SLOTS, profile));
+    assert_invalid_input_data(pwm.switch_profile(0));
+    assert_invalid_input_data(pwm.crossfade_to_profile(0, 100, 0));
+    destroy(pwm);
+}
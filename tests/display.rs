@@ -0,0 +1,98 @@
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{
+    Channel, ChannelMode, DisabledOutputValue, OutputDriver, OutputLogicState, OutputStateChange,
+    ProgrammableAddress,
+};
+
+mod common;
+use crate::common::{destroy, new, BitFlags, Register, DEV_ADDR, MODE1_DEFAULT, MODE2_DEFAULT};
+
+#[test]
+fn can_display_i2c_error() {
+    let nack = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+    let trans = [I2cTrans::write(
+        DEV_ADDR,
+        vec![Register::MODE1, MODE1_DEFAULT & !BitFlags::SLEEP],
+    )
+    .with_error(nack)];
+    let mut pwm = new(&trans);
+    let error = pwm.enable().unwrap_err();
+    assert_eq!(format!("I²C bus error: {}", nack), error.to_string());
+    destroy(pwm);
+}
+
+#[test]
+fn can_display_channel_mode_mismatch() {
+    let mut pwm = new(&[]);
+    pwm.set_channel_mode(Channel::C1, ChannelMode::Digital);
+    let error = pwm.set_channel_on_off(Channel::C1, 0, 2047).unwrap_err();
+    assert_eq!(
+        "Operation does not match the mode of channel 1",
+        error.to_string()
+    );
+    destroy(pwm);
+}
+
+#[test]
+fn can_display_written_output_configuration() {
+    let trans = [
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE2, MODE2_DEFAULT | BitFlags::INVRT],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::MODE2,
+                MODE2_DEFAULT | BitFlags::INVRT | BitFlags::OCH,
+            ],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE2, BitFlags::INVRT | BitFlags::OCH],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::MODE2,
+                BitFlags::INVRT | BitFlags::OCH | BitFlags::OUTNE1,
+            ],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    let logic = OutputLogicState::Inverted;
+    let change = OutputStateChange::OnAck;
+    let driver = OutputDriver::OpenDrain;
+    let disabled = DisabledOutputValue::HighImpedance;
+    pwm.set_output_logic_state(logic).unwrap();
+    pwm.set_output_change_behavior(change).unwrap();
+    pwm.set_output_driver(driver).unwrap();
+    pwm.set_disabled_output_value(disabled).unwrap();
+    assert_eq!(
+        "inverted, on ACK, open-drain, high-impedance",
+        format!("{}, {}, {}, {}", logic, change, driver, disabled)
+    );
+    destroy(pwm);
+}
+
+#[test]
+fn can_display_programmable_address() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::SUBADDR2, 0x72]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE1, MODE1_DEFAULT | BitFlags::SUBADDR2],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    let address = ProgrammableAddress::Subaddress2;
+    pwm.set_programmable_address(address, 0x72).unwrap();
+    pwm.enable_programmable_address(address).unwrap();
+    assert_eq!("subaddress 2", address.to_string());
+    assert_eq!(
+        "LED all call address",
+        ProgrammableAddress::AllCall.to_string()
+    );
+    destroy(pwm);
+}
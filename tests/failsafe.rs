@@ -0,0 +1,68 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::Frame;
+
+mod common;
+use crate::common::{destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+fn all_off_frame_write() -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_ON_L, 0, 0, 0, 0b0001_0000]),
+    ]
+}
+
+#[test]
+fn tick_does_nothing_if_failsafe_disabled() {
+    let mut pwm = new(&[]);
+    pwm.tick(100_000).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn tick_does_nothing_before_timeout() {
+    let mut pwm = new(&[]);
+    pwm.enable_failsafe(Frame::all_off(), 100, 1000);
+    pwm.tick(1099).unwrap();
+    assert!(!pwm.is_failsafe_triggered());
+    destroy(pwm);
+}
+
+#[test]
+fn writes_safe_frame_once_on_timeout() {
+    let mut pwm = new(&all_off_frame_write());
+    pwm.enable_failsafe(Frame::all_off(), 100, 1000);
+    pwm.tick(1100).unwrap();
+    assert!(pwm.is_failsafe_triggered());
+    pwm.tick(1200).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn feeding_restarts_timeout() {
+    let mut pwm = new(&[]);
+    pwm.enable_failsafe(Frame::all_off(), 100, 1000);
+    pwm.feed_failsafe(1090);
+    pwm.tick(1150).unwrap();
+    assert!(!pwm.is_failsafe_triggered());
+    destroy(pwm);
+}
+
+#[test]
+fn timeout_handles_clock_wrap_around() {
+    let mut pwm = new(&all_off_frame_write());
+    pwm.enable_failsafe(Frame::all_off(), 100, u32::MAX - 10);
+    pwm.tick(90).unwrap();
+    assert!(pwm.is_failsafe_triggered());
+    destroy(pwm);
+}
+
+#[test]
+fn feeding_rearms_after_trigger() {
+    let mut pwm = new(&all_off_frame_write());
+    pwm.enable_failsafe(Frame::all_off(), 100, 0);
+    pwm.tick(100).unwrap();
+    pwm.feed_failsafe(150);
+    assert!(!pwm.is_failsafe_triggered());
+    pwm.tick(200).unwrap();
+    destroy(pwm);
+}
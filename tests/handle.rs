@@ -0,0 +1,42 @@
+use embedded_hal::pwm::SetDutyCycle;
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{Channel, ChannelOnOffControl};
+
+mod common;
+use crate::common::{assert_invalid_input_data, destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+#[test]
+fn full_on_after_full_off_clears_full_off_bit() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C4_OFF_L, 0, 0b0001_0000]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C4_ON_L, 0, 0b0001_0000, 0, 0]),
+    ];
+    let mut pwm = new(&trans);
+    let mut channel = pwm.channel(Channel::C4);
+    channel.set_duty_cycle(0).unwrap();
+    channel.set_duty_cycle(4096).unwrap();
+    assert_eq!(
+        Some(ChannelOnOffControl::full_on()),
+        pwm.snapshot().outputs.get(Channel::C4)
+    );
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_duty_cycle_through_handle() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C4_OFF_L, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C4_ON_L, 0, 0b0001_0000, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C4_OFF_L, 0, 0b0001_0000]),
+    ];
+    let mut pwm = new(&trans);
+    let mut channel = pwm.channel(Channel::C4);
+    assert_eq!(4096, channel.max_duty_cycle());
+    channel.set_duty_cycle_percent(50).unwrap();
+    channel.set_duty_cycle_fully_on().unwrap();
+    channel.set_duty_cycle_fully_off().unwrap();
+    assert_invalid_input_data(channel.set_duty_cycle(4097));
+    destroy(pwm);
+}
@@ -1,9 +1,12 @@
-use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
-use pwm_pca9685::{DisabledOutputValue, OutputDriver, OutputLogicState, OutputStateChange};
+use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+use pwm_pca9685::{
+    Address, Channel, DisabledOutputValue, Error, Mode1Flags, OutputDriver, OutputLogicState,
+    OutputStateChange, Pca9685, Rounding, Topology, Q16,
+};
 
 mod common;
 use crate::common::{
-    assert_invalid_input_data, destroy, new, BitFlags, Register, DEV_ADDR, MODE1_DEFAULT,
+    assert_invalid_input_data, destroy, new, BitFlags, Register, DEV_ADDR, MODE1_AI, MODE1_DEFAULT,
     MODE2_DEFAULT,
 };
 
@@ -15,6 +18,18 @@ fn can_create_and_destroy() {
 
 call_method_test!(can_enable, enable, MODE1, MODE1_DEFAULT & !BitFlags::SLEEP);
 call_method_test!(can_disable, disable, MODE1, MODE1_DEFAULT);
+call_method_test!(
+    can_enable_auto_increment,
+    enable_auto_increment,
+    MODE1,
+    MODE1_DEFAULT | BitFlags::AUTO_INC
+);
+call_method_test!(
+    can_disable_auto_increment,
+    disable_auto_increment,
+    MODE1,
+    MODE1_DEFAULT
+);
 call_method_test!(
     can_set_direct_ols,
     set_output_logic_state,
@@ -54,6 +69,138 @@ fn can_set_prescale() {
     destroy(pwm);
 }
 
+#[test]
+fn can_get_and_read_prescale() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 121]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::PRE_SCALE], vec![100]),
+    ];
+    let mut pwm = new(&trans);
+    assert_eq!(30, pwm.get_prescale());
+    pwm.set_prescale(121).unwrap();
+    assert_eq!(121, pwm.get_prescale());
+    assert_eq!(100, pwm.read_prescale().unwrap());
+    assert_eq!(121, pwm.get_prescale());
+    destroy(pwm);
+}
+
+#[test]
+fn can_create_with_frequency() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 121]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI & !BitFlags::SLEEP]),
+    ];
+    let pwm = Pca9685::new_with_frequency(I2cMock::new(&trans), Address::default(), 50).unwrap();
+    assert!(pwm.is_auto_increment_enabled());
+    assert_eq!(121, pwm.get_prescale());
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_create_with_frequency_out_of_range() {
+    let mut i2c = I2cMock::new(&[]);
+    let result = Pca9685::new_with_frequency(i2c.clone(), Address::default(), 2000);
+    assert!(matches!(result, Err(Error::InvalidInputData)));
+    i2c.done();
+}
+
+#[test]
+fn can_convert_ticks_and_us() {
+    let trans = [I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 100])];
+    let mut pwm = new(&trans);
+    pwm.set_prescale(100).unwrap();
+    // 814 µs * 25 MHz / 101 = 201.49
+    assert_eq!(Some(201), pwm.us_to_ticks(814));
+    assert_eq!(Some(812), pwm.ticks_to_us(201));
+    assert_eq!(None, pwm.us_to_ticks(20_000));
+    pwm.set_rounding(Rounding::Exact);
+    assert_eq!(None, pwm.ticks_to_us(201));
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_period() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 121]),
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 121]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_period_us(20_000).unwrap();
+    pwm.set_period_ms(20).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_query_sleep_state() {
+    let trans = [
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE1, MODE1_DEFAULT & !BitFlags::SLEEP],
+        ),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::MODE1], vec![MODE1_DEFAULT]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::MODE1], vec![MODE1_DEFAULT]),
+    ];
+    let mut pwm = new(&trans);
+    assert!(pwm.is_sleeping());
+    pwm.enable().unwrap();
+    assert!(pwm.is_enabled());
+    assert!(!pwm.is_sleeping());
+    assert!(pwm.is_sleeping_read().unwrap());
+    assert!(!pwm.is_enabled_read().unwrap());
+    destroy(pwm);
+}
+
+#[test]
+fn can_access_raw_registers() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::SUBADDR1, 0xE4]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::SUBADDR1], vec![0xE4]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.write_register_raw(Register::SUBADDR1, 0xE4).unwrap();
+    assert_eq!(0xE4, pwm.read_register_raw(Register::SUBADDR1).unwrap());
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_frequency() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 121]),
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 3]),
+    ];
+    let mut pwm = new(&trans);
+    assert_eq!(Some(197), pwm.frequency());
+    pwm.set_frequency_hz(50).unwrap();
+    assert_eq!(Some(50), pwm.frequency());
+    assert_eq!(Some(Q16::from_bits(3_278_689)), pwm.frequency_q16());
+    pwm.set_frequency_hz(1526).unwrap();
+    assert_eq!(Some(1526), pwm.frequency());
+    destroy(pwm);
+}
+
+#[test]
+fn can_correct_oscillator_frequency() {
+    // 26 MHz: round(26 MHz / (4096 * 50 Hz)) - 1 = 126
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 126]),
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 126]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_oscillator_correction_ppm(40_000).unwrap();
+    assert_eq!(26_000_000, pwm.oscillator_hz());
+    pwm.set_frequency_hz(50).unwrap();
+    pwm.set_period_ms(20).unwrap();
+    assert_eq!(Some(50), pwm.frequency());
+    assert_invalid_input_data(pwm.set_oscillator_correction_ppm(200_001));
+    destroy(pwm);
+}
+
+invalid_test!(cannot_set_frequency_too_low, set_frequency_hz, 23);
+invalid_test!(cannot_set_frequency_too_high, set_frequency_hz, 1527);
+invalid_test!(cannot_set_period_too_short, set_period_us, 500);
+invalid_test!(cannot_set_period_too_long, set_period_ms, 50);
+
 #[test]
 fn set_prescale_stops_and_restarts_oscillator() {
     let trans = [
@@ -129,3 +276,94 @@ call_method_test!(
     MODE2_DEFAULT | BitFlags::OUTNE1,
     DisabledOutputValue::HighImpedance
 );
+
+call_method_test!(
+    can_set_topology_n_mos,
+    set_output_topology,
+    MODE2,
+    BitFlags::OUT_DRV,
+    Topology::ExternalNMosDriver
+);
+
+call_method_test!(
+    can_set_topology_p_mos,
+    set_output_topology,
+    MODE2,
+    BitFlags::INVRT | BitFlags::OUT_DRV | BitFlags::OUTNE0,
+    Topology::ExternalPMosDriver
+);
+
+call_method_test!(
+    can_set_topology_led_to_vcc,
+    set_output_topology,
+    MODE2,
+    BitFlags::INVRT | BitFlags::OUTNE1,
+    Topology::LedToVccOpenDrain
+);
+
+#[test]
+fn mode_flags_track_written_configuration() {
+    let trans = [
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE1, MODE1_DEFAULT & !BitFlags::SLEEP],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE2, MODE2_DEFAULT & !BitFlags::OUT_DRV],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    assert_eq!(Mode1Flags::default(), pwm.mode1_flags());
+    pwm.enable().unwrap();
+    pwm.set_output_driver(OutputDriver::OpenDrain).unwrap();
+    assert!(!pwm.mode1_flags().contains(Mode1Flags::SLEEP));
+    assert_eq!(OutputDriver::OpenDrain, pwm.mode2_flags().output_driver());
+    destroy(pwm);
+}
+
+#[test]
+fn multi_master_mode_keeps_foreign_bits() {
+    let foreign = MODE1_DEFAULT | BitFlags::SUBADDR1;
+    let enabled = foreign & !BitFlags::SLEEP;
+    let trans = [
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::MODE1],
+            vec![foreign | BitFlags::RESTART],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, enabled]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::MODE1], vec![enabled]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE1, enabled | BitFlags::AUTO_INC],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0, 8]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::MODE2], vec![MODE2_DEFAULT]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE2, MODE2_DEFAULT | BitFlags::INVRT],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_multi_master(true);
+    pwm.enable().unwrap();
+    pwm.set_channel_on_off(Channel::C0, 0, 2048).unwrap();
+    pwm.set_output_logic_state(OutputLogicState::Inverted)
+        .unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn auto_increment_is_enabled_once() {
+    let trans = [I2cTrans::write(
+        DEV_ADDR,
+        vec![Register::MODE1, MODE1_DEFAULT | BitFlags::AUTO_INC],
+    )];
+    let mut pwm = new(&trans);
+    assert!(!pwm.is_auto_increment_enabled());
+    pwm.enable_auto_increment().unwrap();
+    assert!(pwm.is_auto_increment_enabled());
+    pwm.enable_auto_increment().unwrap();
+    destroy(pwm);
+}
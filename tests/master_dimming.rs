@@ -0,0 +1,41 @@
+use embedded_hal_mock::eh1::{i2c::Transaction as I2cTrans, pwm};
+use pwm_pca9685::{OutputDriver, Percent};
+
+mod common;
+use crate::common::{destroy, new, BitFlags, Register, DEV_ADDR, MODE2_DEFAULT};
+
+#[test]
+fn disabled_outputs_are_zero_for_totem_pole() {
+    let trans = [I2cTrans::write(
+        DEV_ADDR,
+        vec![Register::MODE2, MODE2_DEFAULT],
+    )];
+    let oe_trans = [
+        pwm::Transaction::max_duty_cycle(1000),
+        pwm::Transaction::set_duty_cycle(250),
+    ];
+    let mut pwm = new(&trans);
+    let mut dimming = pwm
+        .enable_master_dimming(pwm::Mock::new(&oe_trans))
+        .unwrap();
+    assert_eq!(Percent::new(100), Some(dimming.master_dimming()));
+    dimming
+        .set_master_dimming(Percent::new(75).unwrap())
+        .unwrap();
+    assert_eq!(Percent::new(75), Some(dimming.master_dimming()));
+    dimming.release().done();
+    destroy(pwm);
+}
+
+#[test]
+fn disabled_outputs_are_high_impedance_for_open_drain() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE2, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE2, BitFlags::OUTNE1]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_output_driver(OutputDriver::OpenDrain).unwrap();
+    let dimming = pwm.enable_master_dimming(pwm::Mock::new(&[])).unwrap();
+    dimming.release().done();
+    destroy(pwm);
+}
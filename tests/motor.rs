@@ -0,0 +1,77 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{
+    boards::{adafruit_motor_hat, DcMotorPins},
+    Channel, DcMotor, MotorCommand,
+};
+
+mod common;
+use crate::common::{destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+const HIGH: [u8; 4] = [0, 0b0001_0000, 0, 0];
+const LOW: [u8; 4] = [0, 0, 0, 0b0001_0000];
+
+fn write(reg: u8, value: [u8; 4]) -> I2cTrans {
+    let mut data = vec![reg];
+    data.extend_from_slice(&value);
+    I2cTrans::write(DEV_ADDR, data)
+}
+
+#[test]
+fn can_get_motor_hat_pins() {
+    let mut pwm = new(&[]);
+    let motor = DcMotor::motor1_on_motor_hat(&mut pwm);
+    assert_eq!(adafruit_motor_hat::MOTOR1, motor.pins());
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_speed() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C8_ON_L, 0, 0, 0xFF, 0x07]),
+    ];
+    let mut pwm = new(&trans);
+    let mut motor = DcMotor::motor1_on_motor_hat(&mut pwm);
+    motor.set_speed(2047).unwrap();
+    destroy(pwm);
+}
+
+macro_rules! run_test {
+    ($name:ident, $command:ident, $in2:expr, $in1:expr) => {
+        #[test]
+        fn $name() {
+            let trans = [
+                I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+                write(Register::C12_ON_L, $in2),
+                write(Register::C11_ON_L, $in1),
+            ];
+            let mut pwm = new(&trans);
+            let mut motor = DcMotor::motor2_on_motor_hat(&mut pwm);
+            motor.run(MotorCommand::$command).unwrap();
+            destroy(pwm);
+        }
+    };
+}
+
+run_test!(can_run_forward, Forward, LOW, HIGH);
+run_test!(can_run_backward, Backward, HIGH, LOW);
+run_test!(can_brake, Brake, HIGH, HIGH);
+run_test!(can_release, Release, LOW, LOW);
+
+#[test]
+fn can_use_custom_pins() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        write(Register::C1_ON_L, HIGH),
+        write(Register::C0_ON_L, LOW),
+    ];
+    let mut pwm = new(&trans);
+    let pins = DcMotorPins {
+        pwm: Channel::C2,
+        in1: Channel::C0,
+        in2: Channel::C1,
+    };
+    let mut motor = DcMotor::new(&mut pwm, pins);
+    motor.run(MotorCommand::Backward).unwrap();
+    destroy(pwm);
+}
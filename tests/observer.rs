@@ -0,0 +1,33 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{Channel, ChannelOnOffControl};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+mod common;
+use crate::common::{destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+static CHANGES: AtomicU32 = AtomicU32::new(0);
+static LAST_OFF: AtomicU32 = AtomicU32::new(0);
+
+fn observe(channel: Channel, old: ChannelOnOffControl, new: ChannelOnOffControl) {
+    assert_eq!(Channel::C2, channel);
+    assert!(old.full_off);
+    CHANGES.fetch_add(1, Ordering::SeqCst);
+    LAST_OFF.store(u32::from(new.off), Ordering::SeqCst);
+}
+
+#[test]
+fn reports_changed_channels_only() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_ON_L, 0, 0, 0x34, 0x02]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_ON_L, 0, 0, 0x34, 0x02]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_output_observer(Some(observe));
+    pwm.set_channel_on_off(Channel::C2, 0, 0x234).unwrap();
+    pwm.set_channel_on_off(Channel::C2, 0, 0x234).unwrap();
+    assert_eq!(1, CHANGES.load(Ordering::SeqCst));
+    assert_eq!(0x234, LAST_OFF.load(Ordering::SeqCst));
+    pwm.set_output_observer(None);
+    destroy(pwm);
+}
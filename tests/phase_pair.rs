@@ -0,0 +1,38 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{Channel, PhasePair};
+
+mod common;
+use crate::common::{assert_invalid_input_data, destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+#[test]
+fn writes_adjacent_channels_at_once() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_ON_L, 0, 4, 0, 12, 0, 0, 0, 8]),
+    ];
+    let mut pwm = new(&trans);
+    let pair = PhasePair::quadrature(Channel::C3, Channel::C2).unwrap();
+    pwm.set_phase_pair_duty(&pair, 2048).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn writes_separate_channels() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0, 0b0001_0000]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C5_ON_L, 0, 0, 0, 0b0001_0000]),
+    ];
+    let mut pwm = new(&trans);
+    let pair = PhasePair::quadrature(Channel::C0, Channel::C5).unwrap();
+    pwm.set_phase_pair_duty(&pair, 0).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_set_invalid_duty() {
+    let mut pwm = new(&[]);
+    let pair = PhasePair::quadrature(Channel::C0, Channel::C1).unwrap();
+    assert_invalid_input_data(pwm.set_phase_pair_duty(&pair, 4096));
+    destroy(pwm);
+}
@@ -0,0 +1,107 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{Channel, ChannelOnOffControl, Easing, Frame, FrameRamp, Q16};
+
+mod common;
+use crate::common::{destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+/// Write of the same `OFF` counter to all channels with the `ON` counter at 0.
+fn all_off_counter_write(off: u16) -> I2cTrans {
+    let [off_l, off_h] = off.to_le_bytes();
+    I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_ON_L, 0, 0, off_l, off_h])
+}
+
+/// Write of all channels full-off except `channel`, set to `value`.
+fn frame_write(channel: usize, value: [u8; 4]) -> I2cTrans {
+    let mut data = vec![Register::C0_ON_L];
+    for i in 0..16 {
+        if i == channel {
+            data.extend_from_slice(&value);
+        } else {
+            data.extend_from_slice(&[0, 0, 0, 0b0001_0000]);
+        }
+    }
+    I2cTrans::write(DEV_ADDR, data)
+}
+
+#[test]
+fn can_play_frame_ramp() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        all_off_counter_write(1024),
+        all_off_counter_write(2048),
+    ];
+    let mut pwm = new(&trans);
+    let to = Frame::new([ChannelOnOffControl::duty(2048).unwrap(); 16]);
+    for frame in FrameRamp::new(Frame::all_off(), to, 2) {
+        pwm.write_frame(&frame).unwrap();
+    }
+    destroy(pwm);
+}
+
+#[test]
+fn can_write_ramp_frame_at_elapsed_time() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        frame_write(3, [0, 0, 0, 4]),
+        frame_write(3, [0, 0b0001_0000, 0, 0]),
+    ];
+    let mut pwm = new(&trans);
+    let mut to = Frame::all_off();
+    to.set_full_on(Channel::C3);
+    let ramp = FrameRamp::new(Frame::all_off(), to, 100);
+    pwm.write_frame(&ramp.at(250, 1000)).unwrap();
+    pwm.write_frame(&ramp.at(1500, 1000)).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_play_eased_frame_ramp() {
+    // Quadratic easing reaches a quarter of the pulse width halfway.
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        all_off_counter_write(512),
+        all_off_counter_write(2048),
+    ];
+    let mut pwm = new(&trans);
+    let to = Frame::new([ChannelOnOffControl::duty(2048).unwrap(); 16]);
+    let ramp = FrameRamp::new(Frame::all_off(), to, 2).with_easing(Easing::QuadIn);
+    for frame in ramp {
+        pwm.write_frame(&frame).unwrap();
+    }
+    assert_eq!(250, Easing::QuadIn.apply(500, 1000));
+    destroy(pwm);
+}
+
+#[test]
+fn eased_ramp_ends_at_target() {
+    // Decelerating quadratic easing reaches three quarters of the pulse
+    // width halfway.
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        all_off_counter_write(1536),
+        all_off_counter_write(2048),
+    ];
+    let mut pwm = new(&trans);
+    let to = Frame::new([ChannelOnOffControl::duty(2048).unwrap(); 16]);
+    let ramp = FrameRamp::new(Frame::all_off(), to, 4).with_easing(Easing::QuadOut);
+    pwm.write_frame(&ramp.at(2, 4)).unwrap();
+    pwm.write_frame(&ramp.at(4, 4)).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_write_lerped_frames() {
+    // Interpolation factors beyond 1 are clamped to the target frame.
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        all_off_counter_write(512),
+        all_off_counter_write(2048),
+    ];
+    let mut pwm = new(&trans);
+    let to = Frame::new([ChannelOnOffControl::duty(2048).unwrap(); 16]);
+    for t in [Q16::from_ratio(1, 4), Q16::from_ratio(2, 1)] {
+        pwm.write_frame(&Frame::all_off().lerp(&to, t.unwrap()))
+            .unwrap();
+    }
+    destroy(pwm);
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "std")]
+
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::Channel;
+
+mod common;
+use crate::common::{destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+#[test]
+fn can_report_state() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 1, 0xFF, 5]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_on_off(Channel::C1, 256, 1535).unwrap();
+    pwm.set_channel_label(Channel::C0, Some("status LED"));
+    let report = pwm.report();
+    let lines: Vec<_> = report.lines().collect();
+    assert_eq!(
+        [
+            "PCA9685 at address 0x40",
+            "MODE1: 0x31 [AI SLEEP ALLCALL]",
+            "MODE2: 0x04 [OUTDRV] Direct, OnStop, TotemPole, disabled outputs Zero",
+            "Prescale: 30 (197 Hz)",
+            "channel 0 (status LED): on 0 off 0 full-off",
+            "channel 1: on 256 off 1535",
+            "channel 2: on 0 off 0 full-off",
+        ],
+        lines[..7]
+    );
+    assert_eq!(20, lines.len());
+    destroy(pwm);
+}
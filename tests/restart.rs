@@ -1,8 +1,33 @@
 use embedded_hal_mock::eh1::{delay::NoopDelay as DelayMock, i2c::Transaction as I2cTrans};
-use pwm_pca9685::ProgrammableAddress as ProgAddr;
+use pwm_pca9685::{Channel, Error, Mode1Flags, ProgrammableAddress as ProgAddr, Stagger};
 
 mod common;
-use crate::common::{destroy, new, BitFlags, Register, DEV_ADDR, MODE1_DEFAULT};
+use crate::common::{
+    assert_invalid_input_data, destroy, new, BitFlags, Register, DEV_ADDR, MODE1_AI, MODE1_DEFAULT,
+};
+
+#[test]
+fn can_set_prescale_with_delay() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 121]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE1, MODE1_DEFAULT & !BitFlags::SLEEP],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_DEFAULT]),
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 3]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE1, MODE1_DEFAULT & !BitFlags::SLEEP],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_prescale_with_delay(121, &mut DelayMock).unwrap();
+    pwm.enable().unwrap();
+    pwm.set_prescale_with_delay(3, &mut DelayMock).unwrap();
+    assert_invalid_input_data(pwm.set_prescale_with_delay(2, &mut DelayMock));
+    destroy(pwm);
+}
 
 #[test]
 fn restart_is_only_set_once() {
@@ -56,6 +81,22 @@ fn restart_does_nothing_if_not_enabled() {
     destroy(pwm);
 }
 
+#[test]
+fn can_check_if_restart_is_pending() {
+    let trans = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::MODE1], vec![MODE1_DEFAULT]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::MODE1],
+            vec![MODE1_DEFAULT | BitFlags::RESTART],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    assert!(!pwm.is_restart_pending().unwrap());
+    assert!(pwm.is_restart_pending().unwrap());
+    destroy(pwm);
+}
+
 #[test]
 fn restart_nonblocking_does_nothing_if_not_enabled() {
     let trans = [I2cTrans::write_read(
@@ -140,3 +181,182 @@ fn can_disable_then_restart_nonblocking() {
     pwm.restart_nonblocking().unwrap();
     destroy(pwm);
 }
+
+#[test]
+fn restart_poll_does_nothing_if_not_enabled() {
+    let trans = [I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::MODE1],
+        vec![MODE1_DEFAULT],
+    )];
+    let mut pwm = new(&trans);
+    pwm.restart_poll(0).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_disable_then_restart_poll() {
+    let trans = [
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::MODE1,
+                MODE1_DEFAULT | BitFlags::SLEEP | BitFlags::RESTART,
+            ],
+        ),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::MODE1],
+            vec![MODE1_DEFAULT | BitFlags::SLEEP | BitFlags::RESTART],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE1, MODE1_DEFAULT & !BitFlags::SLEEP],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::MODE1,
+                MODE1_DEFAULT & !BitFlags::SLEEP | BitFlags::RESTART,
+            ],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    pwm.enable_restart_and_disable().unwrap();
+    assert_error!(pwm.restart_poll(u32::MAX - 100), nb::Error::WouldBlock);
+    assert_error!(pwm.restart_poll(300), nb::Error::WouldBlock);
+    pwm.restart_poll(399).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn enable_sequenced_brings_up_groups() {
+    let mut first_group = vec![Register::C0_ON_L, 0, 0, 0, 8];
+    first_group.extend([0, 0, 0, 0b0001_0000].repeat(7));
+    let mut second_group = vec![Register::C8_ON_L];
+    second_group.extend([0, 0, 0, 0b0001_0000].repeat(8));
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_ON_L, 0, 0, 0, 0b0001_0000]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI & !BitFlags::SLEEP]),
+        I2cTrans::write(DEV_ADDR, first_group),
+        I2cTrans::write(DEV_ADDR, second_group),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_on_off(Channel::C0, 0, 2048).unwrap();
+    let stagger = Stagger {
+        group_size: 8,
+        delay_us: 1000,
+    };
+    pwm.enable_sequenced(&mut DelayMock::new(), stagger)
+        .unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_enable_sequenced_with_empty_groups() {
+    let mut pwm = new(&[]);
+    let stagger = Stagger {
+        group_size: 0,
+        delay_us: 1000,
+    };
+    assert_invalid_input_data(pwm.enable_sequenced(&mut DelayMock::new(), stagger));
+    destroy(pwm);
+}
+
+#[test]
+fn can_sweep_frequency() {
+    let awake = MODE1_DEFAULT & !BitFlags::SLEEP;
+    let mut trans = vec![I2cTrans::write(DEV_ADDR, vec![Register::MODE1, awake])];
+    for prescale in [100, 101, 30] {
+        trans.extend([
+            I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_DEFAULT]),
+            I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, prescale]),
+            I2cTrans::write(DEV_ADDR, vec![Register::MODE1, awake]),
+            I2cTrans::write(DEV_ADDR, vec![Register::MODE1, awake | BitFlags::RESTART]),
+        ]);
+    }
+    let mut pwm = new(&trans);
+    pwm.enable().unwrap();
+    pwm.sweep_frequency(100..=101, 10, &mut DelayMock::new())
+        .unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_sweep_frequency_asleep_or_invalid() {
+    let mut pwm = new(&[]);
+    assert_invalid_input_data(pwm.sweep_frequency(2..=10, 10, &mut DelayMock::new()));
+    assert_eq!(
+        Err(Error::Asleep),
+        pwm.sweep_frequency(100..=101, 10, &mut DelayMock::new())
+    );
+    destroy(pwm);
+}
+
+#[test]
+fn can_suspend_and_resume() {
+    let awake = MODE1_DEFAULT & !BitFlags::SLEEP;
+    let mut frame = vec![Register::C0_ON_L];
+    for i in 0..16 {
+        let on_h = if i == 1 { 0x10 } else { 0 };
+        frame.extend([0, on_h, 0, 0x10]);
+    }
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, awake]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, awake | BitFlags::AUTO_INC]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0x10]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MODE1, MODE1_DEFAULT | BitFlags::AUTO_INC],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, awake | BitFlags::AUTO_INC]),
+        I2cTrans::write(DEV_ADDR, frame),
+    ];
+    let mut pwm = new(&trans);
+    pwm.enable().unwrap();
+    pwm.set_channel_full_on(Channel::C1, 0).unwrap();
+    pwm.suspend().unwrap();
+    pwm.resume(&mut DelayMock::new()).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn restart_and_disable_keeps_foreign_bits_in_multi_master_mode() {
+    let foreign = MODE1_DEFAULT | BitFlags::SUBADDR1;
+    let trans = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::MODE1], vec![foreign]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, foreign | BitFlags::RESTART]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, foreign & !BitFlags::SLEEP]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_multi_master(true);
+    pwm.enable_restart_and_disable().unwrap();
+    assert_eq!(
+        Mode1Flags::SLEEP | Mode1Flags::ALLCALL | Mode1Flags::SUB1,
+        pwm.device_config().mode1
+    );
+    pwm.set_multi_master(false);
+    pwm.enable().unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn restart_bit_read_in_multi_master_mode_is_not_cached() {
+    let trans = [
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::MODE1],
+            vec![MODE1_AI | BitFlags::RESTART],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI & !BitFlags::SLEEP]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_multi_master(true);
+    pwm.enable_auto_increment().unwrap();
+    assert!(!pwm.device_config().mode1.contains(Mode1Flags::RESTART));
+    pwm.set_multi_master(false);
+    pwm.enable().unwrap();
+    destroy(pwm);
+}
@@ -0,0 +1,60 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{boards::RgbPins, Channel, Duty, Frame, Segment};
+
+mod common;
+use crate::common::{destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+fn duty(count: u16) -> Duty {
+    Duty::new(count).unwrap()
+}
+
+#[test]
+fn can_write_gradient() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::C0_ON_L,
+                0,
+                0,
+                0xA0,
+                0x0F,
+                0,
+                0,
+                0xC4,
+                0x09,
+                0,
+                0,
+                0xE8,
+                0x03,
+            ],
+        ),
+    ];
+    let mut pwm = new(&trans);
+    let segment = Segment::new(&[[Channel::C0], [Channel::C1], [Channel::C2]]).unwrap();
+    let mut frame = Frame::all_off();
+    segment.gradient(&mut frame, [duty(4000)], [duty(1000)]);
+    pwm.write_frame_diff(&Frame::all_off(), &frame).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_write_rgb_pixel() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C9_ON_L, 0, 0, 0xFF, 0x0F]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C11_ON_L, 0, 0, 0, 8]),
+    ];
+    let mut pwm = new(&trans);
+    let pins = RgbPins {
+        red: Channel::C9,
+        green: Channel::C10,
+        blue: Channel::C11,
+    };
+    let segment = Segment::from_rgb_pins(&[pins]).unwrap();
+    let mut frame = Frame::all_off();
+    segment.fill(&mut frame, [duty(4095), duty(0), duty(2048)]);
+    pwm.write_frame_diff(&Frame::all_off(), &frame).unwrap();
+    destroy(pwm);
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "std")]
+
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{Channel, ChannelOnOffControl, Easing, Frame, Pca9685Service, ServiceCommand};
+use std::time::Duration;
+
+mod common;
+use crate::common::{destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+#[test]
+fn can_write_frames_and_fade_in_background() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_ON_L, 0, 0, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_ON_L, 0, 0, 0, 0x10]),
+    ];
+    let service = Pca9685Service::spawn(new(&trans), Duration::from_secs(3600));
+    let mut frame = Frame::all_off();
+    frame.set(
+        Channel::All,
+        ChannelOnOffControl {
+            off: 2048,
+            ..Default::default()
+        },
+    );
+    service.send(ServiceCommand::Frame(frame)).unwrap();
+    let sender = service.sender();
+    std::thread::spawn(move || {
+        sender.send(ServiceCommand::Fade {
+            to: Frame::all_off(),
+            duration_ms: 0,
+            easing: Easing::Linear,
+        })
+    })
+    .join()
+    .unwrap()
+    .unwrap();
+    let pwm = service.stop().unwrap();
+    destroy(pwm);
+}
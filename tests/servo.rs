@@ -0,0 +1,323 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{
+    CalibrationSession, Channel, ChannelLimits, ChannelMode, ChannelOnOffControl, Error, Rounding,
+    ServoConfig,
+};
+
+mod common;
+use crate::common::{assert_invalid_input_data, destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+#[test]
+fn can_set_servo_pulse() {
+    // 1500 µs with prescale 100 = 371.29 counts
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 100]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_ON_L, 0, 0, 0x73, 1]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_prescale(100).unwrap();
+    pwm.set_servo_pulse_us(Channel::C2, 1500).unwrap();
+    destroy(pwm);
+}
+
+#[cfg(feature = "fugit")]
+#[test]
+fn can_set_servo_pulse_duration() {
+    use fugit::{ExtU32, RateExtU32};
+    // 1500 µs with prescale 121 = 307.2 counts
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 121]),
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 121]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C2_ON_L, 0, 0, 0x33, 1]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_frequency(50.Hz::<1, 1>()).unwrap();
+    pwm.set_period(20.millis::<1, 1_000_000>()).unwrap();
+    pwm.set_servo_pulse(Channel::C2, 1500.micros::<1, 1_000_000>())
+        .unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_servo_angle() {
+    // 2000 µs with prescale 30 (reset value) = 1612.9 counts
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0x4D, 6]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_servo_angle(Channel::C0, &ServoConfig::default(), 180)
+        .unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn servo_angle_is_rounded_from_exact_pulse() {
+    // 1° of 270° = 1003.7 µs = 809.44 counts
+    let config = ServoConfig {
+        max_angle_deg: 270,
+        ..Default::default()
+    };
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0x29, 3]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0x2A, 3]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_servo_angle(Channel::C0, &config, 1).unwrap();
+    pwm.set_rounding(Rounding::Up);
+    pwm.set_servo_angle(Channel::C0, &config, 1).unwrap();
+    destroy(pwm);
+}
+
+invalid_test!(
+    cannot_set_servo_pulse_longer_than_cycle,
+    set_servo_pulse_us,
+    Channel::C0,
+    5100
+);
+
+invalid_test!(
+    cannot_set_servo_angle_out_of_range,
+    set_servo_angle,
+    Channel::C0,
+    &ServoConfig::default(),
+    181
+);
+
+#[test]
+fn default_channel_mode_is_any() {
+    let pwm = new(&[]);
+    assert_eq!(Some(ChannelMode::Any), pwm.channel_mode(Channel::C0));
+    assert_eq!(None, pwm.channel_mode(Channel::All));
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_set_servo_on_digital_channel() {
+    let mut pwm = new(&[]);
+    pwm.set_channel_mode(Channel::C1, ChannelMode::Digital);
+    assert_eq!(
+        Err(Error::ChannelModeMismatch(Channel::C1)),
+        pwm.set_servo_angle(Channel::C1, &ServoConfig::default(), 90)
+    );
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_set_counters_on_digital_channel() {
+    let mut pwm = new(&[]);
+    pwm.set_channel_mode(Channel::C1, ChannelMode::Digital);
+    assert_eq!(
+        Err(Error::ChannelModeMismatch(Channel::C1)),
+        pwm.set_channel_on_off(Channel::C1, 0, 2047)
+    );
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_set_servo_on_pwm_channel() {
+    let mut pwm = new(&[]);
+    pwm.set_channel_mode(Channel::C1, ChannelMode::Pwm);
+    assert_eq!(
+        Err(Error::ChannelModeMismatch(Channel::C1)),
+        pwm.set_servo_pulse_us(Channel::C1, 1500)
+    );
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_set_full_on_on_servo_channel() {
+    let mut pwm = new(&[]);
+    pwm.set_channel_mode(Channel::C1, ChannelMode::Servo);
+    assert_eq!(
+        Err(Error::ChannelModeMismatch(Channel::C1)),
+        pwm.set_channel_full_on(Channel::C1, 0)
+    );
+    destroy(pwm);
+}
+
+#[test]
+fn can_set_full_off_on_servo_channel() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_OFF_L, 0, 0b0001_0000]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_mode(Channel::C1, ChannelMode::Servo);
+    pwm.set_channel_full_off(Channel::C1).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn all_channels_operation_checks_every_channel() {
+    let mut pwm = new(&[]);
+    pwm.set_channel_mode(Channel::C7, ChannelMode::Digital);
+    assert_eq!(
+        Err(Error::ChannelModeMismatch(Channel::C7)),
+        pwm.set_channel_on(Channel::All, 100)
+    );
+    destroy(pwm);
+}
+
+#[test]
+fn batch_operation_checks_channel_modes() {
+    let mut pwm = new(&[]);
+    pwm.set_channel_mode(Channel::C3, ChannelMode::Digital);
+    let mut values = [ChannelOnOffControl {
+        full_off: true,
+        ..Default::default()
+    }; 16];
+    values[3].full_off = false;
+    values[3].off = 100;
+    assert_eq!(
+        Err(Error::ChannelModeMismatch(Channel::C3)),
+        pwm.set_all_channels(&values)
+    );
+    assert_eq!(
+        Err(Error::ChannelModeMismatch(Channel::C3)),
+        pwm.set_first_4_channels_on_off(&[0; 4], &[0; 4])
+    );
+    destroy(pwm);
+}
+
+#[test]
+fn reset_internal_state_restores_default_prescale() {
+    // 1000 µs with prescale 30 (reset value) = 806.45 counts
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::PRE_SCALE, 100]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0x26, 3]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_prescale(100).unwrap();
+    pwm.reset_internal_driver_state();
+    pwm.set_servo_pulse_us(Channel::C0, 1000).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn servo_pulse_uses_selected_rounding() {
+    // 1000 µs with prescale 30 (reset value) = 806.45 counts
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0x27, 3]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_rounding(Rounding::Up);
+    pwm.set_servo_pulse_us(Channel::C0, 1000).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn servo_pulse_exact_rounding_rejects_inexact() {
+    let mut pwm = new(&[]);
+    pwm.set_rounding(Rounding::Exact);
+    assert_invalid_input_data(pwm.set_servo_pulse_us(Channel::C0, 1000));
+    destroy(pwm);
+}
+
+#[test]
+fn servo_angle_is_rounded_once() {
+    // 1500 µs with prescale 30 (reset value) = 1209.68 counts
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0xB9, 4]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_rounding(Rounding::Down);
+    pwm.set_servo_angle(Channel::C0, &ServoConfig::default(), 90)
+        .unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn idle_servo_is_switched_off_until_next_command() {
+    let pulse = I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0x4D, 6]);
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        pulse.clone(),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0, 0b0001_0000]),
+        pulse,
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_servo_idle_timeout(Channel::C0, Some(1000));
+    pwm.set_servo_angle(Channel::C0, &ServoConfig::default(), 180)
+        .unwrap();
+    pwm.tick(500).unwrap();
+    pwm.tick(1499).unwrap();
+    assert!(!pwm.is_servo_idle(Channel::C0));
+    pwm.tick(1500).unwrap();
+    assert!(pwm.is_servo_idle(Channel::C0));
+    pwm.tick(5000).unwrap();
+    pwm.set_servo_angle(Channel::C0, &ServoConfig::default(), 180)
+        .unwrap();
+    assert!(!pwm.is_servo_idle(Channel::C0));
+    pwm.tick(5500).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn idle_timeout_ignores_channels_never_commanded() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0x4D, 6]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0, 0b0001_0000]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_servo_idle_timeout(Channel::All, Some(1000));
+    pwm.tick(0).unwrap();
+    pwm.tick(5000).unwrap();
+    assert!(!pwm.is_servo_idle(Channel::C0));
+    pwm.set_servo_angle(Channel::C0, &ServoConfig::default(), 180)
+        .unwrap();
+    pwm.tick(6000).unwrap();
+    pwm.tick(7000).unwrap();
+    assert!(pwm.is_servo_idle(Channel::C0));
+    assert!(!pwm.is_servo_idle(Channel::C1));
+    destroy(pwm);
+}
+
+#[test]
+fn can_calibrate_servo_endpoints() {
+    // 990 µs = 798.4 counts, 2010 µs = 1620.97 counts with prescale 30
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C3_ON_L, 0, 0, 0x1E, 3]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C3_ON_L, 0, 0, 0x55, 6]),
+    ];
+    let mut pwm = new(&trans);
+    let mut session = CalibrationSession::new(&mut pwm, Channel::C3, ServoConfig::default());
+    session.nudge_min(-10).unwrap();
+    assert_invalid_input_data(session.nudge_min(1100));
+    session.nudge_max(10).unwrap();
+    assert_invalid_input_data(session.nudge_max(-1020));
+    let config = session.accept();
+    assert_eq!(990, config.min_pulse_us);
+    assert_eq!(2010, config.max_pulse_us);
+    assert_eq!(180, config.max_angle_deg);
+    destroy(pwm);
+}
+
+#[test]
+fn servo_pulses_are_clamped_and_small_changes_ignored() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0xDC, 5]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0x26, 3]),
+    ];
+    let mut pwm = new(&trans);
+    let limits = ChannelLimits {
+        max_count: 1500,
+        deadband: 10,
+        ..Default::default()
+    };
+    pwm.set_channel_limits(Channel::C0, limits).unwrap();
+    pwm.set_servo_angle(Channel::C0, &ServoConfig::default(), 180)
+        .unwrap();
+    pwm.set_servo_pulse_us(Channel::C0, 1860).unwrap();
+    pwm.set_servo_pulse_us(Channel::C0, 1000).unwrap();
+    destroy(pwm);
+}
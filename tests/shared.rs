@@ -0,0 +1,43 @@
+#![cfg(feature = "std")]
+
+use embedded_hal::pwm::SetDutyCycle;
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{Channel, SharedPca9685};
+
+mod common;
+use crate::common::{destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+#[test]
+fn channels_can_be_used_from_other_threads() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_OFF_L, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_OFF_L, 0, 4]),
+    ];
+    let pwm = SharedPca9685::new(new(&trans));
+    let mut channel = pwm.channel(Channel::C1);
+    assert_eq!(4096, channel.max_duty_cycle());
+    let mut clone = channel.clone();
+    std::thread::spawn(move || clone.set_duty_cycle(2048).unwrap())
+        .join()
+        .unwrap();
+    channel.set_duty(1024_u16).unwrap();
+    let pwm = pwm.try_into_inner().unwrap_err();
+    drop(channel);
+    destroy(pwm.try_into_inner().unwrap());
+}
+
+#[test]
+fn shared_channel_full_on_clears_full_off() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_OFF_L, 0, 0b0001_0000]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0b0001_0000, 0, 0]),
+    ];
+    let pwm = SharedPca9685::new(new(&trans));
+    let mut channel = pwm.channel(Channel::C1);
+    channel.set_duty_cycle(0).unwrap();
+    channel.set_duty_cycle(4096).unwrap();
+    drop(channel);
+    destroy(pwm.try_into_inner().unwrap());
+}
@@ -0,0 +1,80 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{Channel, SlowBlink};
+
+mod common;
+use crate::common::{assert_invalid_input_data, destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+const BLINK: SlowBlink = SlowBlink {
+    period_ms: 1000,
+    on_ms: 250,
+};
+
+#[test]
+fn toggles_between_duty_and_full_off() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0, 0, 0b0001_0000]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0, 0, 8]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_on_off(Channel::C1, 0, 2048).unwrap();
+    pwm.start_slow_blink(Channel::C1, BLINK, 100).unwrap();
+    assert!(pwm.is_slow_blinking(Channel::C1));
+    pwm.tick(349).unwrap();
+    pwm.tick(350).unwrap();
+    pwm.tick(1099).unwrap();
+    pwm.tick(1100).unwrap();
+    pwm.tick(1200).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn full_off_channel_blinks_full_on() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0b0001_0000, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0, 0b0001_0000]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.start_slow_blink(Channel::C0, BLINK, 0).unwrap();
+    pwm.tick(0).unwrap();
+    pwm.tick(250).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn stop_restores_duty() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0, 0, 0b0001_0000]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0, 0, 8]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_on_off(Channel::C1, 0, 2048).unwrap();
+    pwm.start_slow_blink(Channel::All, BLINK, 0).unwrap();
+    pwm.tick(500).unwrap();
+    pwm.stop_slow_blink(Channel::C1).unwrap();
+    assert!(!pwm.is_slow_blinking(Channel::C1));
+    assert!(pwm.is_slow_blinking(Channel::C2));
+    pwm.stop_slow_blink(Channel::All).unwrap();
+    pwm.tick(1000).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_start_with_invalid_timing() {
+    let mut pwm = new(&[]);
+    let blink = SlowBlink {
+        period_ms: 0,
+        on_ms: 0,
+    };
+    assert_invalid_input_data(pwm.start_slow_blink(Channel::C0, blink, 0));
+    let blink = SlowBlink {
+        period_ms: 100,
+        on_ms: 101,
+    };
+    assert_invalid_input_data(pwm.start_slow_blink(Channel::C0, blink, 0));
+    destroy(pwm);
+}
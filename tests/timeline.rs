@@ -0,0 +1,62 @@
+#![cfg(feature = "alloc")]
+
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{ChannelOnOffControl, Easing, Frame, Keyframe, SceneStore, Timeline};
+
+mod common;
+use crate::common::{destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+/// Frame with all channels switched off at the same counter value.
+fn uniform(off: u16) -> Frame {
+    Frame::new([ChannelOnOffControl::duty(off).unwrap(); 16])
+}
+
+/// Write of the same `OFF` counter to all channels with the `ON` counter at 0.
+fn all_off_counter_write(off: u16) -> I2cTrans {
+    let [off_l, off_h] = off.to_le_bytes();
+    I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_ON_L, 0, 0, off_l, off_h])
+}
+
+#[test]
+fn can_play_timeline() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        all_off_counter_write(1024),
+        all_off_counter_write(2048),
+        all_off_counter_write(1024),
+        I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_ON_L, 0, 0, 0, 0b0001_0000]),
+    ];
+    let mut pwm = new(&trans);
+    let mut timeline = Timeline::new(Frame::all_off());
+    for frame in [uniform(2048), Frame::all_off()] {
+        timeline.push(Keyframe {
+            frame,
+            duration_ms: 1000,
+            easing: Easing::Linear,
+        });
+    }
+    assert_eq!(2000, timeline.duration_ms());
+    for elapsed_ms in [500, 1000, 1500, 3000] {
+        pwm.write_frame(&timeline.frame_at(elapsed_ms)).unwrap();
+    }
+    destroy(pwm);
+}
+
+#[test]
+fn can_write_stored_scenes() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        all_off_counter_write(4095),
+        all_off_counter_write(100),
+    ];
+    let mut pwm = new(&trans);
+    let mut scenes = SceneStore::new();
+    scenes.insert("day", uniform(4000));
+    scenes.insert("night", uniform(100));
+    assert_eq!(Some(uniform(4000)), scenes.insert("day", uniform(4095)));
+    for name in ["day", "night"] {
+        pwm.write_frame(scenes.get(name).unwrap()).unwrap();
+    }
+    assert_eq!(None, scenes.get("dusk"));
+    destroy(pwm);
+}
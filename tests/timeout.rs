@@ -0,0 +1,74 @@
+#![cfg(feature = "async")]
+
+use embedded_hal_async::delay::DelayNs;
+use pwm_pca9685::{with_timeout, Error};
+use std::{
+    future::{pending, ready, Future},
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+};
+
+/// Delay which expires immediately.
+struct ExpiredDelay;
+
+impl DelayNs for ExpiredDelay {
+    async fn delay_ns(&mut self, _ns: u32) {}
+}
+
+/// Delay which never expires.
+struct EndlessDelay;
+
+impl DelayNs for EndlessDelay {
+    async fn delay_ns(&mut self, _ns: u32) {
+        pending().await
+    }
+}
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Poll a future once and return its output.
+fn poll_once<F: Future>(future: F) -> Poll<F::Output> {
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    pin!(future).poll(&mut cx)
+}
+
+#[test]
+fn completed_operation_returns_its_result() {
+    let operation = ready(Ok::<_, Error<()>>(42));
+    let result = poll_once(with_timeout(&mut EndlessDelay, 1000, operation));
+    assert_eq!(Poll::Ready(Ok(42)), result);
+}
+
+#[test]
+fn completed_operation_returns_its_error() {
+    let operation = ready(Err::<(), _>(Error::<()>::InvalidInputData));
+    let result = poll_once(with_timeout(&mut EndlessDelay, 1000, operation));
+    assert_eq!(Poll::Ready(Err(Error::InvalidInputData)), result);
+}
+
+#[test]
+fn completed_operation_wins_over_expired_deadline() {
+    let operation = ready(Ok::<_, Error<()>>(()));
+    let result = poll_once(with_timeout(&mut ExpiredDelay, 0, operation));
+    assert_eq!(Poll::Ready(Ok(())), result);
+}
+
+#[test]
+fn expired_deadline_returns_timeout() {
+    let operation = pending::<Result<(), Error<()>>>();
+    let result = poll_once(with_timeout(&mut ExpiredDelay, 1000, operation));
+    assert_eq!(Poll::Ready(Err(Error::Timeout)), result);
+}
+
+#[test]
+fn pending_operation_keeps_waiting_for_deadline() {
+    let operation = pending::<Result<(), Error<()>>>();
+    let result = poll_once(with_timeout(&mut EndlessDelay, 1000, operation));
+    assert_eq!(Poll::Pending, result);
+}
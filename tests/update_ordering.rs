@@ -0,0 +1,110 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{Channel, ChannelOnOffControl, UpdateOrdering};
+
+mod common;
+use crate::common::{destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+#[test]
+fn direct_ordering_writes_directly() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0, 4]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_channel_on_off(Channel::C0, 0, 2048).unwrap();
+    pwm.set_channel_on_off(Channel::C0, 0, 1024).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn glitch_free_routes_earlier_off_through_full_off() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_OFF_L, 0, 0b0001_0000]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0, 4]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_update_ordering(UpdateOrdering::GlitchFree);
+    pwm.set_channel_on_off(Channel::C0, 0, 2048).unwrap();
+    pwm.set_channel_on_off(Channel::C0, 0, 1024).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn glitch_free_writes_later_off_directly() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_ON_L, 0, 0, 0, 4]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C0_OFF_L, 0, 8]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_update_ordering(UpdateOrdering::GlitchFree);
+    pwm.set_channel_on_off(Channel::C0, 0, 1024).unwrap();
+    pwm.set_channel_off(Channel::C0, 2048).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn glitch_free_writes_all_registers_for_partial_update() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C3_ON_L, 0, 0, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C3_OFF_L, 0, 0b0001_0000]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C3_ON_L, 0, 1, 0, 8]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_update_ordering(UpdateOrdering::GlitchFree);
+    pwm.set_channel_on_off(Channel::C3, 0, 2048).unwrap();
+    pwm.set_channel_on(Channel::C3, 256).unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn glitch_free_routes_only_affected_channels_in_batch() {
+    let mut first = vec![Register::C0_ON_L];
+    let mut intermediate = vec![Register::C0_ON_L];
+    let mut last = vec![Register::C0_ON_L];
+    for i in 0..4 {
+        first.extend_from_slice(&[0, 0, 0, 8]);
+        if i == 1 {
+            intermediate.extend_from_slice(&[0, 0, 0, 4 | 0b0001_0000]);
+            last.extend_from_slice(&[0, 0, 0, 4]);
+        } else {
+            intermediate.extend_from_slice(&[0, 0, 0, 8]);
+            last.extend_from_slice(&[0, 0, 0, 8]);
+        }
+    }
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, first),
+        I2cTrans::write(DEV_ADDR, intermediate),
+        I2cTrans::write(DEV_ADDR, last),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_update_ordering(UpdateOrdering::GlitchFree);
+    pwm.set_first_4_channels_on_off(&[0; 4], &[2048; 4])
+        .unwrap();
+    pwm.set_first_4_channels_on_off(&[0; 4], &[2048, 1024, 2048, 2048])
+        .unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn glitch_free_does_not_route_full_off() {
+    let values = [ChannelOnOffControl {
+        full_off: true,
+        ..Default::default()
+    }; 16];
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_ON_L, 0, 0, 0, 8]),
+        I2cTrans::write(DEV_ADDR, vec![Register::ALL_C_ON_L, 0, 0, 0, 0b0001_0000]),
+    ];
+    let mut pwm = new(&trans);
+    pwm.set_update_ordering(UpdateOrdering::GlitchFree);
+    pwm.set_channel_on_off(Channel::All, 0, 2048).unwrap();
+    pwm.set_all_channels(&values).unwrap();
+    destroy(pwm);
+}
@@ -0,0 +1,111 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use pwm_pca9685::{Channel, ChannelOnOffControl, Duty, EncodedFrame, Frame, FramePlayer, Waveform};
+
+mod common;
+use crate::common::{assert_invalid_input_data, destroy, new, Register, DEV_ADDR, MODE1_AI};
+
+const fn duty(count: u16) -> Duty {
+    match Duty::new(count) {
+        Some(duty) => duty,
+        None => panic!(),
+    }
+}
+
+static RAMP: [Duty; 2] = [duty(0x100), duty(0x200)];
+static STROBE: [Duty; 2] = [duty(0x300), duty(0)];
+
+#[test]
+fn writes_changed_channels_together() {
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                Register::C1_ON_L,
+                0,
+                0,
+                0,
+                1,
+                0,
+                0,
+                0,
+                0b0001_0000,
+                0,
+                0,
+                0,
+                3,
+            ],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0, 0, 2]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C3_ON_L, 0, 0, 0, 0b0001_0000]),
+        I2cTrans::write(DEV_ADDR, vec![Register::C1_ON_L, 0, 0, 0, 0b0001_0000]),
+    ];
+    let mut pwm = new(&trans);
+    let ramp = Waveform {
+        table: &RAMP,
+        step_ms: 100,
+    };
+    let strobe = Waveform {
+        table: &STROBE,
+        step_ms: 150,
+    };
+    pwm.start_waveform(Channel::C1, ramp, 0).unwrap();
+    pwm.start_waveform(Channel::C3, strobe, 0).unwrap();
+    assert!(pwm.is_waveform_playing(Channel::C1));
+    pwm.tick(0).unwrap();
+    pwm.tick(50).unwrap();
+    pwm.tick(100).unwrap();
+    pwm.tick(150).unwrap();
+    pwm.stop_waveform(Channel::All).unwrap();
+    assert!(!pwm.is_waveform_playing(Channel::C1));
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_start_empty_waveform() {
+    let mut pwm = new(&[]);
+    let empty = Waveform {
+        table: &[],
+        step_ms: 100,
+    };
+    assert_invalid_input_data(pwm.start_waveform(Channel::C0, empty, 0));
+    let stopped = Waveform {
+        table: &RAMP,
+        step_ms: 0,
+    };
+    assert_invalid_input_data(pwm.start_waveform(Channel::C0, stopped, 0));
+    destroy(pwm);
+}
+
+#[test]
+fn can_play_encoded_frames() {
+    let first = Frame::all_off();
+    let mut second = Frame::all_off();
+    second.set(Channel::C0, ChannelOnOffControl::full_on());
+    let frames = [EncodedFrame::new(&first), EncodedFrame::new(&second)];
+    let trans = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MODE1, MODE1_AI]),
+        I2cTrans::write(DEV_ADDR, frames[0].as_bytes().to_vec()),
+        I2cTrans::write(DEV_ADDR, frames[1].as_bytes().to_vec()),
+    ];
+    let mut pwm = new(&trans);
+    let mut player = FramePlayer::new(&frames, 10, 100);
+    pwm.play_frames(&mut player, 100).unwrap();
+    pwm.play_frames(&mut player, 105).unwrap();
+    pwm.play_frames(&mut player, 110).unwrap();
+    assert!(!player.is_finished(119));
+    pwm.play_frames(&mut player, 120).unwrap();
+    assert!(player.is_finished(120));
+    // The cached outputs match the last frame, so nothing is written.
+    pwm.update_channel(Channel::C0, |v| *v = ChannelOnOffControl::full_on())
+        .unwrap();
+    destroy(pwm);
+}
+
+#[test]
+fn cannot_play_frames_without_interval() {
+    let frames = [EncodedFrame::new(&Frame::all_off())];
+    let mut pwm = new(&[]);
+    assert_invalid_input_data(pwm.play_frames(&mut FramePlayer::new(&frames, 0, 0), 0));
+    destroy(pwm);
+}